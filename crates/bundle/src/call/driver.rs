@@ -1,11 +1,21 @@
-use crate::{SignetCallBundle, SignetCallBundleResponse};
-use alloy::{consensus::TxEnvelope, primitives::U256};
-use signet_evm::{DriveBundleResult, EvmNeedsTx, EvmTransacted, SignetInspector, SignetLayered};
+use crate::{call::trace::run_traced_tx, SignetCallBundle, SignetCallBundleResponse};
+use alloy::{
+    consensus::TxEnvelope,
+    primitives::U256,
+    rpc::types::state::{AccountOverride, StateOverride},
+};
+use signet_evm::{
+    DriveBundleResult, EvmErrored, EvmNeedsTx, EvmTransacted, PrecompileRegistry, SignetInspector,
+    SignetLayered,
+};
 use std::fmt::Debug;
-use tracing::{debug_span, instrument, Level};
+use tracing::{debug, debug_span, instrument, Level};
 use trevm::{
     helpers::Ctx,
-    revm::{context::result::EVMError, Database, DatabaseCommit, Inspector},
+    revm::{
+        context::result::EVMError, database::InMemoryDB, inspector::NoOpInspector, state::Bytecode,
+        Database, DatabaseCommit, Inspector,
+    },
     trevm_bail, trevm_ensure, trevm_try, BundleDriver, BundleError,
 };
 
@@ -13,10 +23,34 @@ use trevm::{
 ///
 /// This type allows for the simulation of a [`SignetCallBundle`], outputting
 /// the results of the simulation in a [`SignetCallBundleResponse`].
+///
+/// If the bundle contains host transactions, they are simulated first
+/// against the host EVM provided to [`SignetBundleDriver::new_with_host`].
+/// The host EVM should be created with an [`OrderDetector::for_host`], e.g.
+/// by [`signet_evm::signet_host_evm_with_inspector`].
+///
+/// When tracing is enabled with [`SignetBundleDriver::with_tracing`], a call
+/// tracer is layered next to the [`OrderDetector`] of each EVM, and the
+/// response contains a [`SignetTxTrace`] for every transaction simulated.
+///
+/// [`OrderDetector`]: signet_evm::OrderDetector
+/// [`SignetTxTrace`]: crate::SignetTxTrace
+/// [`OrderDetector::for_host`]: signet_evm::OrderDetector::for_host
 #[derive(Debug)]
-pub struct SignetBundleDriver<'a> {
+pub struct SignetBundleDriver<'a, HostDb = InMemoryDB, HostInsp = NoOpInspector>
+where
+    HostDb: Database,
+    HostInsp: Inspector<Ctx<HostDb>>,
+{
     /// The bundle to drive.
     bundle: &'a SignetCallBundle,
+    /// The host EVM to simulate the host transactions against, if any.
+    host_evm: Option<EvmNeedsTx<HostDb, HostInsp>>,
+    /// Registry of Signet precompiles, used to select the precompile set for
+    /// the rollup block.
+    precompiles: PrecompileRegistry,
+    /// Whether to trace the transactions in the bundle.
+    tracing: bool,
     /// The accumulated results of the bundle, if applicable.
     response: SignetCallBundleResponse,
 }
@@ -29,23 +63,92 @@ impl<'a> From<&'a SignetCallBundle> for SignetBundleDriver<'a> {
 
 impl<'a> SignetBundleDriver<'a> {
     /// Create a new bundle driver with the given bundle and response.
+    ///
+    /// The driver has no host EVM, and will error if the bundle contains host
+    /// transactions.
     pub fn new(bundle: &'a SignetCallBundle) -> Self {
-        Self { bundle, response: Default::default() }
+        Self {
+            bundle,
+            host_evm: None,
+            precompiles: Default::default(),
+            tracing: false,
+            response: Default::default(),
+        }
     }
 }
 
-impl SignetBundleDriver<'_> {
+impl<'a, HostDb, HostInsp> SignetBundleDriver<'a, HostDb, HostInsp>
+where
+    HostDb: Database,
+    HostInsp: Inspector<Ctx<HostDb>>,
+{
+    /// Create a new bundle driver with the given bundle, and a host EVM to
+    /// simulate the bundle's host transactions against.
+    pub fn new_with_host(
+        bundle: &'a SignetCallBundle,
+        host_evm: EvmNeedsTx<HostDb, HostInsp>,
+    ) -> Self {
+        Self {
+            bundle,
+            host_evm: Some(host_evm),
+            precompiles: Default::default(),
+            tracing: false,
+            response: Default::default(),
+        }
+    }
+}
+
+impl<HostDb, HostInsp> SignetBundleDriver<'_, HostDb, HostInsp>
+where
+    HostDb: Database,
+    HostInsp: Inspector<Ctx<HostDb>>,
+{
+    /// Set the registry of Signet precompiles. Defaults to
+    /// [`PrecompileRegistry::default`].
+    pub fn with_precompile_registry(mut self, precompiles: PrecompileRegistry) -> Self {
+        self.precompiles = precompiles;
+        self
+    }
+
+    /// Get the registry of Signet precompiles.
+    pub const fn precompile_registry(&self) -> &PrecompileRegistry {
+        &self.precompiles
+    }
+
+    /// Enable call tracing. The response will contain a trace of every
+    /// transaction in the bundle, including the host transactions.
+    pub const fn with_tracing(mut self) -> Self {
+        self.tracing = true;
+        self
+    }
+
+    /// Returns `true` if call tracing is enabled.
+    pub const fn is_tracing(&self) -> bool {
+        self.tracing
+    }
+
     /// Get a reference to the bundle.
     pub const fn bundle(&self) -> &SignetCallBundle {
         self.bundle
     }
 
+    /// Get a reference to the host EVM, if any.
+    pub const fn host_evm(&self) -> Option<&EvmNeedsTx<HostDb, HostInsp>> {
+        self.host_evm.as_ref()
+    }
+
+    /// Take the host EVM from the driver. After the bundle has run, this
+    /// contains the state changes of the host transactions.
+    pub const fn take_host_evm(&mut self) -> Option<EvmNeedsTx<HostDb, HostInsp>> {
+        self.host_evm.take()
+    }
+
     /// Get a reference to the response.
     pub const fn response(&self) -> &SignetCallBundleResponse {
         &self.response
     }
 
-    /// Take the response from the bundle driver. This consumes
+    /// Take the response from the bundle driver. This consumes the driver.
     pub fn into_response(self) -> SignetCallBundleResponse {
         self.response
     }
@@ -54,7 +157,13 @@ impl SignetBundleDriver<'_> {
     pub fn clear(&mut self) -> SignetCallBundleResponse {
         std::mem::take(&mut self.response)
     }
+}
 
+impl<HostDb, HostInsp> SignetBundleDriver<'_, HostDb, HostInsp>
+where
+    HostDb: Database + DatabaseCommit,
+    HostInsp: Inspector<Ctx<HostDb>>,
+{
     /// Check the aggregate fills, accept the result, accumulate the transaction
     /// details into the response.
     fn accept_and_accumulate<Db, Insp>(
@@ -95,15 +204,97 @@ impl SignetBundleDriver<'_> {
 
         Ok(trevm)
     }
+
+    /// Simulate the host transactions against the host EVM, accumulating
+    /// their results and fills into the response.
+    ///
+    /// Like the rollup transactions, host transactions that revert are
+    /// reported in the response rather than erroring the simulation.
+    fn run_host_txs<Db: Database>(&mut self, txs: &[TxEnvelope]) -> Result<(), BundleError<Db>> {
+        let host_error =
+            |err: &dyn std::fmt::Display| custom_error(format!("host simulation error: {err}"));
+
+        let htrevm = self
+            .host_evm
+            .take()
+            .ok_or_else(|| custom_error("no host EVM to simulate host transactions"))?;
+
+        let host_block = self.bundle.host_block.clone().unwrap_or_default();
+
+        let result = htrevm.try_with_block(&host_block, |mut htrevm| {
+            let beneficiary = htrevm.beneficiary();
+            let basefee = htrevm.block().basefee;
+
+            let mut pre_sim_coinbase_balance = trevm_try!(
+                htrevm.try_read_balance(beneficiary).map_err(|err| host_error(&err)),
+                htrevm
+            );
+
+            for (idx, tx) in txs.iter().enumerate() {
+                let _span = debug_span!("host tx loop", tx = %tx.tx_hash(), idx).entered();
+                let htrevm_transacted = if self.tracing {
+                    run_traced_tx(htrevm, tx).map(|(htrevm, trace)| {
+                        self.response.host_traces.push(trace);
+                        htrevm
+                    })
+                } else {
+                    htrevm.run_tx(tx)
+                }
+                .map_err(|err| {
+                    debug!(err = %err.error(), "error while running host transaction");
+                    err.map_err(|err| host_error(&err))
+                })?;
+
+                let (execution_result, next) = htrevm_transacted.accept();
+                htrevm = next;
+
+                let post_sim_coinbase_balance = trevm_try!(
+                    htrevm.try_read_balance(beneficiary).map_err(|err| host_error(&err)),
+                    htrevm
+                );
+                let coinbase_diff =
+                    post_sim_coinbase_balance.saturating_sub(pre_sim_coinbase_balance);
+                pre_sim_coinbase_balance = post_sim_coinbase_balance;
+
+                trevm_try!(
+                    self.response.accumulate_host_tx(tx, coinbase_diff, basefee, execution_result),
+                    htrevm
+                );
+            }
+
+            // Taking these clears the order detector. The host detector only
+            // detects fills.
+            let (fills, _) =
+                htrevm.inner_mut_unchecked().inspector.as_mut_detector().take_aggregates();
+            self.response.host_fills = fills;
+
+            Ok(htrevm)
+        });
+
+        match result {
+            Ok(htrevm) => {
+                self.host_evm = Some(htrevm);
+                Ok(())
+            }
+            Err(err) => {
+                let (err, htrevm) = err.take_err();
+                self.host_evm = Some(htrevm);
+                Err(err)
+            }
+        }
+    }
 }
 
 // [`BundleDriver`] Implementation for [`SignetCallBundle`].
 // This is useful mainly for the `signet_simBundle` endpoint,
 // which is used to simulate a signet bundle while respecting aggregate fills.
-impl<Db, Insp> BundleDriver<Db, SignetLayered<Insp>> for SignetBundleDriver<'_>
+impl<Db, Insp, HostDb, HostInsp> BundleDriver<Db, SignetLayered<Insp>>
+    for SignetBundleDriver<'_, HostDb, HostInsp>
 where
     Db: Database + DatabaseCommit,
     Insp: Inspector<Ctx<Db>>,
+    HostDb: Database + DatabaseCommit,
+    HostInsp: Inspector<Ctx<HostDb>>,
 {
     type Error = BundleError<Db>;
 
@@ -132,8 +323,35 @@ where
             BundleError::BlockNumberMismatch
         );
 
+        // Block hash overrides require access to the block hashes of the
+        // database, which is not supported.
+        trevm_ensure!(
+            self.bundle.block_overrides().is_none_or(|overrides| overrides.block_hash.is_none()),
+            trevm,
+            custom_error("block hash overrides are not supported")
+        );
+        // The block number is checked against the bundle above, so it can
+        // not be overridden.
+        trevm_ensure!(
+            self.bundle.block_overrides().is_none_or(|overrides| overrides.number.is_none()),
+            trevm,
+            custom_error("block number overrides are not supported")
+        );
+
         // Decode and validate the transactions in the bundle
         let txs = trevm_try!(self.bundle.decode_and_validate_txs(), trevm);
+        let host_txs = trevm_try!(self.bundle.decode_and_validate_host_txs(), trevm);
+
+        // Simulate the host transactions first, if any.
+        if !host_txs.is_empty() {
+            trevm_try!(self.run_host_txs(&host_txs), trevm);
+        }
+
+        // Apply the state overrides before any transaction is simulated.
+        let trevm = match self.bundle.state_overrides() {
+            Some(overrides) => apply_state_overrides(trevm, overrides)?,
+            None => trevm,
+        };
 
         trevm.try_with_block(self.bundle, |mut trevm| {
             // Get the coinbase and basefee from the block
@@ -142,6 +360,9 @@ where
             let coinbase = trevm.beneficiary();
             let basefee = trevm.block().basefee;
 
+            // Select the precompile set for the block's spec and height.
+            self.precompiles.apply(&mut trevm);
+
             // Cache the pre simulation coinbase balance, so we can use it to calculate the coinbase diff after every tx simulated.
             let initial_coinbase_balance = trevm_try!(
                 trevm
@@ -157,7 +378,14 @@ where
             let span = debug_span!("bundle loop", count = txs.len()).entered();
             for (idx, tx) in txs.iter().enumerate() {
                 let _span = debug_span!("tx loop", tx = %tx.tx_hash(), idx).entered();
-                let run_result = trevm.run_tx(tx);
+                let run_result = if self.tracing {
+                    run_traced_tx(trevm, tx).map(|(trevm, trace)| {
+                        self.response.traces.push(trace);
+                        trevm
+                    })
+                } else {
+                    trevm.run_tx(tx)
+                };
 
                 let transacted_trevm = run_result.map_err(|e| e.map_err(Into::into))?;
 
@@ -198,3 +426,88 @@ where
         Ok(())
     }
 }
+
+/// Create a [`BundleError`] with a custom message.
+fn custom_error<Db: Database>(msg: impl Into<String>) -> BundleError<Db> {
+    BundleError::EVMError { inner: EVMError::Custom(msg.into()) }
+}
+
+/// Apply `eth_call`-style state overrides to the EVM.
+///
+/// Only the `stateDiff` storage overrides are supported. Replacing an
+/// account's storage with `state` requires clearing it in the database, which
+/// is not supported.
+fn apply_state_overrides<Db, Insp>(
+    mut trevm: EvmNeedsTx<Db, Insp>,
+    overrides: &StateOverride,
+) -> Result<EvmNeedsTx<Db, Insp>, EvmErrored<Db, Insp, BundleError<Db>>>
+where
+    Db: Database + DatabaseCommit,
+    Insp: Inspector<Ctx<Db>>,
+{
+    for (address, account_override) in overrides {
+        let AccountOverride { balance, nonce, code, state, state_diff, move_precompile_to } =
+            account_override;
+
+        trevm_ensure!(
+            move_precompile_to.is_none(),
+            trevm,
+            custom_error("moving precompiles is not supported")
+        );
+        trevm_ensure!(
+            state.is_none(),
+            trevm,
+            custom_error(format!(
+                "state override of account {address} is not supported, use stateDiff"
+            ))
+        );
+
+        if let Some(balance) = balance {
+            trevm_try!(
+                trevm
+                    .try_set_balance_unchecked(*address, *balance)
+                    .map_err(EVMError::Database)
+                    .map_err(BundleError::from),
+                trevm
+            );
+        }
+        if let Some(nonce) = nonce {
+            trevm_try!(
+                trevm
+                    .try_set_nonce_unchecked(*address, *nonce)
+                    .map_err(EVMError::Database)
+                    .map_err(BundleError::from),
+                trevm
+            );
+        }
+        if let Some(code) = code {
+            let bytecode = trevm_try!(
+                Bytecode::new_raw_checked(code.clone())
+                    .map_err(|_| custom_error(format!("invalid bytecode for account {address}"))),
+                trevm
+            );
+            trevm_try!(
+                trevm
+                    .try_set_bytecode_unchecked(*address, bytecode)
+                    .map_err(EVMError::Database)
+                    .map_err(BundleError::from),
+                trevm
+            );
+        }
+        for (slot, value) in state_diff.iter().flatten() {
+            trevm_try!(
+                trevm
+                    .try_set_storage_unchecked(
+                        *address,
+                        U256::from_be_bytes((*slot).into()),
+                        U256::from_be_bytes((*value).into()),
+                    )
+                    .map_err(EVMError::Database)
+                    .map_err(BundleError::from),
+                trevm
+            );
+        }
+    }
+
+    Ok(trevm)
+}
@@ -1,6 +1,9 @@
 mod driver;
 pub use driver::SignetBundleDriver;
 
+mod trace;
+pub use trace::{SignetCallFrame, SignetTxTrace};
+
 mod trevm;
 
 mod alloy;
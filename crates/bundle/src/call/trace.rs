@@ -0,0 +1,263 @@
+//! Call tracing for `signet_callBundle`.
+use alloy::{
+    consensus::TxEnvelope,
+    primitives::{Log, TxHash, B256},
+    rpc::types::trace::geth::{AccountState, CallConfig, CallFrame, DiffMode},
+    sol_types::SolEvent,
+};
+use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+use serde::{Deserialize, Serialize};
+use signet_evm::{EvmErrored, EvmNeedsTx, EvmTransacted, OrderDetector, SignetLayered};
+use signet_zenith::RollupOrders;
+use trevm::{
+    helpers::Ctx,
+    inspectors::Layered,
+    revm::{state::EvmState, Database, DatabaseCommit, Inspector},
+    Trevm,
+};
+
+/// A call frame of a traced transaction, with the orders and fills emitted
+/// by the frame.
+///
+/// Orders and fills are only attributed to frames that did not revert, as
+/// only those count towards the bundle's aggregate orders and fills.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignetCallFrame {
+    /// The call frame, without its subcalls.
+    #[serde(flatten)]
+    pub frame: CallFrame,
+    /// Orders emitted by this frame.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub orders: Vec<RollupOrders::Order>,
+    /// Fills emitted by this frame.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fills: Vec<RollupOrders::Filled>,
+    /// The subcalls of this frame.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<SignetCallFrame>,
+}
+
+impl SignetCallFrame {
+    /// Create a frame from a geth-style [`CallFrame`], attributing the
+    /// orders and fills in its logs using the given [`OrderDetector`].
+    fn new(mut frame: CallFrame, detector: &OrderDetector) -> Self {
+        let calls = std::mem::take(&mut frame.calls)
+            .into_iter()
+            .map(|call| Self::new(call, detector))
+            .collect();
+
+        let mut orders = Vec::new();
+        let mut fills = Vec::new();
+
+        // Logs of reverted frames, and of frames whose parent reverted, are
+        // not counted by the detector.
+        let logs = if frame.error.is_none() { frame.logs.as_slice() } else { &[] };
+        for log in logs.iter().cloned().map(Log::from) {
+            if !detector.is_contract(log.address) {
+                continue;
+            }
+
+            if let Ok(Log { data, .. }) = RollupOrders::Filled::decode_log(&log) {
+                fills.push(data);
+            } else if detector.fills_only() {
+                continue;
+            } else if let Ok(Log { data, .. }) = RollupOrders::Order::decode_log(&log) {
+                orders.push(data);
+            }
+        }
+
+        Self { frame, orders, fills, calls }
+    }
+
+    /// Iterate over the orders emitted by this frame and its subcalls, in
+    /// depth-first order.
+    pub fn all_orders(&self) -> Box<dyn Iterator<Item = &RollupOrders::Order> + '_> {
+        Box::new(self.orders.iter().chain(self.calls.iter().flat_map(Self::all_orders)))
+    }
+
+    /// Iterate over the fills emitted by this frame and its subcalls, in
+    /// depth-first order.
+    pub fn all_fills(&self) -> Box<dyn Iterator<Item = &RollupOrders::Filled> + '_> {
+        Box::new(self.fills.iter().chain(self.calls.iter().flat_map(Self::all_fills)))
+    }
+}
+
+/// The trace of a transaction in a `signet_callBundle` simulation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignetTxTrace {
+    /// The hash of the transaction.
+    pub tx_hash: TxHash,
+    /// The call tree of the transaction.
+    pub call: SignetCallFrame,
+    /// The logs emitted by the transaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<Log>,
+    /// The state of the accounts changed by the transaction, before and
+    /// after it ran. Only the changed fields are included in the post state.
+    pub state_diff: DiffMode,
+}
+
+/// Compute the prestate/poststate diff of a transaction from its state
+/// changes.
+fn state_diff(state: &EvmState) -> DiffMode {
+    let mut diff = DiffMode::default();
+
+    for (address, account) in state.iter().filter(|(_, account)| account.is_touched()) {
+        let original = &account.original_info;
+        let info = &account.info;
+
+        let code_changed = original.code_hash != info.code_hash;
+        let (pre_storage, post_storage) = account
+            .storage
+            .iter()
+            .filter(|(_, slot)| slot.is_changed())
+            .map(|(key, slot)| {
+                let key = B256::from(*key);
+                ((key, slot.original_value.into()), (key, slot.present_value.into()))
+            })
+            .unzip();
+
+        let pre = AccountState {
+            balance: Some(original.balance),
+            nonce: Some(original.nonce),
+            code: original
+                .code
+                .as_ref()
+                .map(|code| code.original_bytes())
+                .filter(|code| !code.is_empty()),
+            storage: pre_storage,
+        };
+        let post = AccountState {
+            balance: (original.balance != info.balance).then_some(info.balance),
+            nonce: (original.nonce != info.nonce).then_some(info.nonce),
+            code: code_changed
+                .then(|| info.code.as_ref().map(|code| code.original_bytes()))
+                .flatten(),
+            storage: post_storage,
+        };
+
+        if post == AccountState::default() && !account.is_selfdestructed() {
+            continue;
+        }
+        if !original.is_empty() {
+            diff.pre.insert(*address, pre);
+        }
+        if !account.is_selfdestructed() {
+            diff.post.insert(*address, post);
+        }
+    }
+
+    diff
+}
+
+/// Layer a call tracer next to the [`OrderDetector`] of a Signet EVM.
+fn layer_tracer<Db, Insp, S>(
+    trevm: Trevm<Db, SignetLayered<Insp>, S>,
+    tracer: TracingInspector,
+) -> Trevm<Db, SignetLayered<Layered<TracingInspector, Insp>>, S>
+where
+    Db: Database,
+    Insp: Inspector<Ctx<Db>>,
+{
+    let (inspector, trevm) = trevm.take_inspector();
+    let (outer, detector) = inspector.into_parts();
+    trevm.set_inspector(Layered::new(Layered::new(tracer, outer), detector))
+}
+
+/// Remove the call tracer layered by [`layer_tracer`], restoring the
+/// original inspector.
+fn remove_tracer<Db, Insp, S>(
+    trevm: Trevm<Db, SignetLayered<Layered<TracingInspector, Insp>>, S>,
+) -> (TracingInspector, Trevm<Db, SignetLayered<Insp>, S>)
+where
+    Db: Database,
+    Insp: Inspector<Ctx<Db>>,
+{
+    let (inspector, trevm) = trevm.take_inspector();
+    let (outer, detector) = inspector.into_parts();
+    let (tracer, outer) = outer.into_parts();
+    (tracer, trevm.set_inspector(Layered::new(outer, detector)))
+}
+
+/// Run a transaction with a call tracer layered next to the
+/// [`OrderDetector`], returning the transacted EVM and the trace of the
+/// transaction.
+pub(crate) fn run_traced_tx<Db, Insp>(
+    trevm: EvmNeedsTx<Db, Insp>,
+    tx: &TxEnvelope,
+) -> Result<(EvmTransacted<Db, Insp>, SignetTxTrace), EvmErrored<Db, Insp>>
+where
+    Db: Database + DatabaseCommit,
+    Insp: Inspector<Ctx<Db>>,
+{
+    let config = CallConfig::default().with_log();
+    let tracer = TracingInspector::new(TracingInspectorConfig::from_geth_call_config(&config));
+
+    let trevm = match layer_tracer(trevm, tracer).run_tx(tx) {
+        Ok(trevm) => trevm,
+        Err(err) => return Err(remove_tracer(err).1),
+    };
+    let (tracer, trevm) = remove_tracer(trevm);
+
+    let result = trevm.result();
+    let call = tracer.into_geth_builder().geth_call_traces(config, result.gas_used());
+
+    let trace = SignetTxTrace {
+        tx_hash: *tx.tx_hash(),
+        call: SignetCallFrame::new(call, trevm.inspector().inner()),
+        logs: result.logs().to_vec(),
+        state_diff: state_diff(trevm.state()),
+    };
+
+    Ok((trevm, trace))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy::primitives::{Address, U256};
+
+    #[test]
+    fn trace_ser_roundtrip() {
+        let order = RollupOrders::Order {
+            deadline: U256::from(100),
+            inputs: vec![RollupOrders::Input { token: Address::ZERO, amount: U256::from(1) }],
+            outputs: vec![],
+        };
+
+        let trace = SignetTxTrace {
+            tx_hash: TxHash::repeat_byte(1),
+            call: SignetCallFrame {
+                frame: CallFrame {
+                    from: Address::repeat_byte(2),
+                    to: Some(Address::repeat_byte(3)),
+                    typ: "CALL".to_string(),
+                    ..Default::default()
+                },
+                calls: vec![SignetCallFrame {
+                    frame: CallFrame {
+                        from: Address::repeat_byte(3),
+                        to: Some(Address::repeat_byte(4)),
+                        typ: "STATICCALL".to_string(),
+                        ..Default::default()
+                    },
+                    orders: vec![order.clone()],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            logs: vec![],
+            state_diff: Default::default(),
+        };
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json["call"]["type"], "CALL");
+        assert_eq!(json["call"]["calls"][0]["type"], "STATICCALL");
+        assert!(json["call"]["calls"][0]["orders"].is_array());
+
+        let deser: SignetTxTrace = serde_json::from_value(json).unwrap();
+        assert_eq!(deser, trace);
+        assert_eq!(deser.call.all_orders().collect::<Vec<_>>(), vec![&order]);
+    }
+}
@@ -22,5 +22,10 @@ impl Block for SignetCallBundle {
         *difficulty = self.bundle.difficulty.unwrap_or(*difficulty);
         *basefee =
             self.bundle.base_fee.map(|n| n.try_into().unwrap_or(u64::MAX)).unwrap_or(*basefee);
+
+        // Block overrides take precedence over the bundle's block fields.
+        if let Some(overrides) = &self.block_overrides {
+            overrides.fill_block_env(block_env);
+        }
     }
 }
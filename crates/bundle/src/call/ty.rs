@@ -1,10 +1,15 @@
 //! Signet bundle types.
+use crate::SignetTxTrace;
 use alloy::{
     consensus::{transaction::SignerRecoverable, Transaction, TxEnvelope},
     eips::{eip2718::Encodable2718, BlockNumberOrTag, Decodable2718},
     primitives::{keccak256, Bytes, B256, U256},
     rlp::Buf,
-    rpc::types::mev::{EthCallBundle, EthCallBundleResponse, EthCallBundleTransactionResult},
+    rpc::types::{
+        mev::{EthCallBundle, EthCallBundleResponse, EthCallBundleTransactionResult},
+        state::StateOverride,
+        BlockOverrides,
+    },
 };
 use serde::{Deserialize, Serialize};
 use signet_types::{AggregateFills, AggregateOrders};
@@ -18,17 +23,42 @@ use trevm::{
 /// The Signet bundle contains the following:
 ///
 /// - A standard [`EthCallBundle`] with the transactions to simulate.
+/// - Host transactions to simulate before the rollup transactions, and
+///   overrides for the host block they are simulated in.
+/// - `eth_call`-style state and block overrides, applied to the rollup before
+///   the bundle is simulated.
 ///
 /// This is based on the flashbots `eth_callBundle` bundle. See [their docs].
 ///
 /// [their docs]: https://docs.flashbots.net/flashbots-auction/advanced/rpc-endpoint
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignetCallBundle {
     /// The bundle of transactions to simulate. Same structure as a Flashbots
     /// [`EthCallBundle`] bundle.
     #[serde(flatten)]
     pub bundle: EthCallBundle,
+
+    /// Host transactions to simulate against the host chain, before the
+    /// rollup transactions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub host_txs: Vec<Bytes>,
+
+    /// Overrides for the host block the host transactions are simulated in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_block: Option<BlockOverrides>,
+
+    /// Overrides for the rollup state, applied before the bundle is
+    /// simulated. Storage can only be overridden with `stateDiff`, not
+    /// replaced with `state`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+
+    /// Overrides for the rollup block the bundle is simulated in. These take
+    /// precedence over the block fields of the [`EthCallBundle`]. The block
+    /// number and block hashes can not be overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
 }
 
 impl SignetCallBundle {
@@ -38,6 +68,27 @@ impl SignetCallBundle {
         &self.bundle.txs
     }
 
+    /// Returns the host transactions in this bundle.
+    #[allow(clippy::missing_const_for_fn)] // false positive
+    pub fn host_txs(&self) -> &[Bytes] {
+        &self.host_txs
+    }
+
+    /// Returns the host block overrides for this bundle.
+    pub const fn host_block(&self) -> Option<&BlockOverrides> {
+        self.host_block.as_ref()
+    }
+
+    /// Returns the state overrides for this bundle.
+    pub const fn state_overrides(&self) -> Option<&StateOverride> {
+        self.state_overrides.as_ref()
+    }
+
+    /// Returns the block overrides for this bundle.
+    pub const fn block_overrides(&self) -> Option<&BlockOverrides> {
+        self.block_overrides.as_ref()
+    }
+
     /// Returns the block number for this bundle.
     pub const fn block_number(&self) -> u64 {
         self.bundle.block_number
@@ -98,6 +149,35 @@ impl SignetCallBundle {
         self
     }
 
+    /// Adds an [`Encodable2718`] host transaction to the bundle.
+    pub fn append_2718_host_tx(self, tx: impl Encodable2718) -> Self {
+        self.append_raw_host_tx(tx.encoded_2718())
+    }
+
+    /// Adds an EIP-2718 envelope to the bundle's host transactions.
+    pub fn append_raw_host_tx(mut self, tx: impl Into<Bytes>) -> Self {
+        self.host_txs.push(tx.into());
+        self
+    }
+
+    /// Sets the host block overrides for the bundle.
+    pub fn with_host_block(mut self, host_block: BlockOverrides) -> Self {
+        self.host_block = Some(host_block);
+        self
+    }
+
+    /// Sets the state overrides for the bundle.
+    pub fn with_state_overrides(mut self, state_overrides: StateOverride) -> Self {
+        self.state_overrides = Some(state_overrides);
+        self
+    }
+
+    /// Sets the block overrides for the bundle.
+    pub fn with_block_overrides(mut self, block_overrides: BlockOverrides) -> Self {
+        self.block_overrides = Some(block_overrides);
+        self
+    }
+
     /// Sets the block number for the bundle.
     pub const fn with_block_number(mut self, block_number: u64) -> Self {
         self.bundle.block_number = block_number;
@@ -141,7 +221,7 @@ impl SignetCallBundle {
     ///
     /// The hash is calculated as
     /// `keccak256(tx_hash1 || tx_hash2 || ... || tx_hashn)` where `||` is the
-    /// concatenation operator.
+    /// concatenation operator. Host transactions are not included.
     pub fn bundle_hash(&self) -> B256 {
         let mut hasher = alloy::primitives::Keccak256::new();
 
@@ -170,6 +250,24 @@ impl SignetCallBundle {
 
         Ok(txs)
     }
+
+    /// Decode and validate the host transactions in the bundle.
+    pub fn decode_and_validate_host_txs<Db: Database>(
+        &self,
+    ) -> Result<Vec<TxEnvelope>, BundleError<Db>> {
+        let txs = self
+            .host_txs()
+            .iter()
+            .map(|tx| TxEnvelope::decode_2718(&mut tx.chunk()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| BundleError::TransactionDecodingError(err))?;
+
+        if txs.iter().any(|tx| tx.is_eip4844()) {
+            return Err(BundleError::UnsupportedTransactionType);
+        }
+
+        Ok(txs)
+    }
 }
 
 /// Response for `signet_callBundle`.
@@ -178,10 +276,15 @@ impl SignetCallBundle {
 /// - The inner [`EthCallBundleResponse`] response.
 /// - Aggregate orders produced by the bundle.
 /// - Fills produced by the bundle.
+/// - The results of the host transactions, and the fills they produced.
+/// - If tracing was enabled, the traces of the transactions. See
+///   [`SignetBundleDriver::with_tracing`].
 ///
 /// The aggregate orders contains both the net outputs the filler can expect to
 /// receive from this bundle and the net inputs the filler must provide to
 /// ensure this bundle is valid.
+///
+/// [`SignetBundleDriver::with_tracing`]: crate::SignetBundleDriver::with_tracing
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct SignetCallBundleResponse {
     #[serde(flatten)]
@@ -200,6 +303,22 @@ pub struct SignetCallBundleResponse {
     /// by the transaction. These can be deducted from the net inputs required
     /// by the orders to ensure the bundle is valid.
     pub fills: AggregateFills,
+    /// Results of the host transactions in the bundle, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub host_results: Vec<EthCallBundleTransactionResult>,
+    /// Fills produced by the host transactions in the bundle. Like
+    /// [`Self::fills`], these can be deducted from the net inputs required by
+    /// the orders.
+    #[serde(default)]
+    pub host_fills: AggregateFills,
+    /// Traces of the transactions in the bundle, in order, if tracing was
+    /// enabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub traces: Vec<SignetTxTrace>,
+    /// Traces of the host transactions in the bundle, in order, if tracing
+    /// was enabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub host_traces: Vec<SignetTxTrace>,
 }
 
 impl core::ops::Deref for SignetCallBundleResponse {
@@ -230,7 +349,15 @@ impl AsMut<EthCallBundleResponse> for SignetCallBundleResponse {
 
 impl From<EthCallBundleResponse> for SignetCallBundleResponse {
     fn from(inner: EthCallBundleResponse) -> Self {
-        Self { inner, orders: Default::default(), fills: Default::default() }
+        Self {
+            inner,
+            orders: Default::default(),
+            fills: Default::default(),
+            host_results: Default::default(),
+            host_fills: Default::default(),
+            traces: Default::default(),
+            host_traces: Default::default(),
+        }
     }
 }
 
@@ -256,6 +383,34 @@ impl SignetCallBundleResponse {
         base_fee: u64,
         execution_result: ExecutionResult,
     ) -> Result<(), BundleError<Db>> {
+        let result = Self::tx_result(tx, coinbase_diff, base_fee, execution_result)?;
+
+        // Accumulate the result
+        self.accumulate_tx_result(result);
+        Ok(())
+    }
+
+    /// Accumulate the result of host transaction execution into the
+    /// response. Host transactions do not count towards the bundle totals.
+    pub fn accumulate_host_tx<Db: Database>(
+        &mut self,
+        tx: &TxEnvelope,
+        coinbase_diff: U256,
+        base_fee: u64,
+        execution_result: ExecutionResult,
+    ) -> Result<(), BundleError<Db>> {
+        let result = Self::tx_result(tx, coinbase_diff, base_fee, execution_result)?;
+        self.host_results.push(result);
+        Ok(())
+    }
+
+    /// Create the result of a transaction from its execution result.
+    fn tx_result<Db: Database>(
+        tx: &TxEnvelope,
+        coinbase_diff: U256,
+        base_fee: u64,
+        execution_result: ExecutionResult,
+    ) -> Result<EthCallBundleTransactionResult, BundleError<Db>> {
         if let TxEnvelope::Eip4844(_) = tx {
             return Err(BundleError::UnsupportedTransactionType);
         }
@@ -282,9 +437,7 @@ impl SignetCallBundleResponse {
         result.coinbase_diff = coinbase_diff;
         result.eth_sent_to_coinbase = result.coinbase_diff.saturating_sub(result.gas_fees);
 
-        // Accumulate the result
-        self.accumulate_tx_result(result);
-        Ok(())
+        Ok(result)
     }
 }
 
@@ -294,7 +447,10 @@ mod test {
     use alloy::{
         eips::BlockNumberOrTag,
         primitives::{Address, U256},
-        rpc::types::mev::{EthCallBundle, EthCallBundleTransactionResult},
+        rpc::types::{
+            mev::{EthCallBundle, EthCallBundleTransactionResult},
+            state::AccountOverride,
+        },
     };
 
     #[test]
@@ -312,6 +468,30 @@ mod test {
                 coinbase: Some(Address::repeat_byte(8)),
                 timeout: Some(9),
             },
+            host_txs: vec![b"host_tx1".into()],
+            host_block: Some(BlockOverrides {
+                number: Some(U256::from(10)),
+                time: Some(11),
+                base_fee: Some(U256::from(12)),
+                ..Default::default()
+            }),
+            state_overrides: Some(
+                [(
+                    Address::repeat_byte(13),
+                    AccountOverride {
+                        balance: Some(U256::from(14)),
+                        nonce: Some(15),
+                        ..Default::default()
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            block_overrides: Some(BlockOverrides {
+                time: Some(16),
+                coinbase: Some(Address::repeat_byte(17)),
+                ..Default::default()
+            }),
         };
 
         let serialized = serde_json::to_string(&bundle).unwrap();
@@ -320,6 +500,17 @@ mod test {
         assert_eq!(bundle, deserialized);
     }
 
+    #[test]
+    fn call_bundle_deser_no_host() {
+        let json = r#"{"txs":["0x01"],"blockNumber":"0x1","stateBlockNumber":"0x2"}"#;
+        let deserialized: SignetCallBundle = serde_json::from_str(json).unwrap();
+
+        assert!(deserialized.host_txs.is_empty());
+        assert!(deserialized.host_block.is_none());
+        assert!(deserialized.state_overrides.is_none());
+        assert!(deserialized.block_overrides.is_none());
+    }
+
     #[test]
     fn call_bundle_resp_ser_roundtrip() {
         let resp: SignetCallBundleResponse = EthCallBundleResponse {
@@ -367,6 +558,7 @@ mod test {
                         state_block_number: BlockNumberOrTag::Number(12345677),
                         ..Default::default()
                     },
+                    ..Default::default()
                 },
             ),
             (
@@ -381,6 +573,7 @@ mod test {
                         base_fee: Some(1000000000),
                         ..Default::default()
                     },
+                    ..Default::default()
                 },
             ),
             (
@@ -394,6 +587,58 @@ mod test {
                         timeout: Some(5),
                         ..Default::default()
                     },
+                    ..Default::default()
+                },
+            ),
+            (
+                "with_host_txs",
+                SignetCallBundle {
+                    bundle: EthCallBundle {
+                        txs: vec![b"\x02\xf8test_tx_1".into()],
+                        block_number: 12345678,
+                        state_block_number: BlockNumberOrTag::Number(12345677),
+                        ..Default::default()
+                    },
+                    host_txs: vec![b"\x02\xf8host_tx_1".into()],
+                    host_block: Some(BlockOverrides {
+                        number: Some(U256::from(23456789u64)),
+                        time: Some(1700000000),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ),
+            (
+                "with_state_and_block_overrides",
+                SignetCallBundle {
+                    bundle: EthCallBundle {
+                        txs: vec![b"\x02\xf8test_tx_1".into()],
+                        block_number: 12345678,
+                        state_block_number: BlockNumberOrTag::Number(12345677),
+                        ..Default::default()
+                    },
+                    state_overrides: Some(
+                        [(
+                            Address::repeat_byte(0x42),
+                            AccountOverride {
+                                balance: Some(U256::from(1000000000000000000u64)),
+                                state_diff: Some(
+                                    [(B256::repeat_byte(0x01), B256::repeat_byte(0x02))]
+                                        .into_iter()
+                                        .collect(),
+                                ),
+                                ..Default::default()
+                            },
+                        )]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    block_overrides: Some(BlockOverrides {
+                        time: Some(1700000000),
+                        base_fee: Some(U256::from(1000000000u64)),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
                 },
             ),
         ];
@@ -11,7 +11,11 @@
 //! [`SignetCallBundleResponse`]. This is used primarily by the RPC server to
 //! serve `signet_callBundle` requests. The response includes the standard
 //! flashbots-style response information, as well as a description of the fills
-//! necessary to make the bundle valid on Signet.
+//! necessary to make the bundle valid on Signet. Call bundles may also contain
+//! host transactions, which are simulated against a host EVM, so that the
+//! response includes the host fills they produce. When tracing is enabled, the
+//! response also includes a call trace and state diff of every transaction,
+//! with orders and fills attributed to the call frames that emitted them.
 //!
 //! The [`SignetEthBundle`] type is used to simulate transaction bundles while
 //! building blocks. It is used primarily by builders and relays. The
@@ -40,10 +44,14 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 mod call;
-pub use call::{SignetBundleApi, SignetBundleDriver, SignetCallBundle, SignetCallBundleResponse};
+pub use call::{
+    SignetBundleApi, SignetBundleDriver, SignetCallBundle, SignetCallBundleResponse,
+    SignetCallFrame, SignetTxTrace,
+};
 
 mod send;
 pub use send::{
-    BundleInspector, BundleRecoverError, RecoverError, RecoveredBundle, SignetEthBundle,
-    SignetEthBundleDriver, SignetEthBundleError, SignetEthBundleInsp, TxRequirement,
+    BundleInspector, BundlePlacement, BundleRecoverError, DriverOutput, RecoverError,
+    RecoveredBundle, SignetEthBundle, SignetEthBundleDriver, SignetEthBundleError,
+    SignetEthBundleInsp, TxRequirement, PLACEMENT_FIELD, REPLACEMENT_NONCE_FIELD,
 };
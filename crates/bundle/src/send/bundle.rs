@@ -21,6 +21,30 @@ use crate::{BundleRecoverError, RecoverError, RecoveredBundle};
 /// The inspector type required by the Signet bundle driver.
 pub type BundleInspector<I = NoOpInspector> = Layered<TimeLimit, I>;
 
+/// Name of the extra bundle field holding the replacement nonce. See
+/// [`SignetEthBundle::replacement_nonce`].
+pub const REPLACEMENT_NONCE_FIELD: &str = "replacementNonce";
+
+/// Name of the extra bundle field holding the placement constraint. See
+/// [`SignetEthBundle::placement`].
+pub const PLACEMENT_FIELD: &str = "placement";
+
+/// A constraint on the position of a bundle in the block.
+///
+/// Serialized as `"topOfBlock"`, `{"after": "0x..."}` or
+/// `{"minPosition": 3}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundlePlacement {
+    /// The bundle must be the first item of the block.
+    TopOfBlock,
+    /// The bundle must immediately follow the transaction with this hash.
+    After(TxHash),
+    /// The bundle must start at or after this position, i.e. after at least
+    /// this many transactions of the block.
+    MinPosition(u64),
+}
+
 /// Bundle of transactions for `signet_sendBundle`.
 ///
 /// The Signet bundle contains the following:
@@ -188,6 +212,28 @@ impl SignetEthBundle {
         Some(uuid.as_str())
     }
 
+    /// Returns the replacement nonce for this bundle, if any.
+    ///
+    /// The replacement nonce is an optional sequence number for updates to
+    /// the bundle with the same [replacement UUID]. Updates with a lower
+    /// nonce than a previous update are stale, and should be ignored. It is
+    /// read from the [`REPLACEMENT_NONCE_FIELD`] extra field, and is `None`
+    /// if the field is missing or invalid.
+    ///
+    /// [replacement UUID]: Self::replacement_uuid
+    pub fn replacement_nonce(&self) -> Option<u64> {
+        self.bundle.extra_fields.get_deserialized(REPLACEMENT_NONCE_FIELD)?.ok()
+    }
+
+    /// Returns the placement constraint for this bundle, if any.
+    ///
+    /// Bundles with a placement constraint are only added to the block at a
+    /// position that satisfies it. It is read from the [`PLACEMENT_FIELD`]
+    /// extra field, and is `None` if the field is missing or invalid.
+    pub fn placement(&self) -> Option<BundlePlacement> {
+        self.bundle.extra_fields.get_deserialized(PLACEMENT_FIELD)?.ok()
+    }
+
     /// Checks if the bundle is valid at a given timestamp.
     pub fn is_valid_at_timestamp(&self, timestamp: u64) -> bool {
         let min_timestamp = self.min_timestamp().unwrap_or(0);
@@ -282,6 +328,41 @@ mod test {
         let deserialized: SignetEthBundle = serde_json::from_str(json).unwrap();
 
         assert!(deserialized.host_txs.is_empty());
+        assert_eq!(deserialized.replacement_nonce(), None);
+    }
+
+    #[test]
+    fn test_deser_replacement_nonce() {
+        let json = r#"
+        {"txs":["0x747831"],"blockNumber":"0x1","replacementUuid":"uuid","replacementNonce":7}"#;
+
+        let deserialized: SignetEthBundle = serde_json::from_str(json).unwrap();
+
+        assert_eq!(deserialized.replacement_uuid(), Some("uuid"));
+        assert_eq!(deserialized.replacement_nonce(), Some(7));
+    }
+
+    #[test]
+    fn test_deser_placement() {
+        let json = r#"
+        {"txs":["0x747831"],"blockNumber":"0x1","placement":"topOfBlock"}"#;
+        let deserialized: SignetEthBundle = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.placement(), Some(BundlePlacement::TopOfBlock));
+
+        let json = r#"
+        {"txs":["0x747831"],"blockNumber":"0x1","placement":{"after":"0x0404040404040404040404040404040404040404040404040404040404040404"}}"#;
+        let deserialized: SignetEthBundle = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.placement(), Some(BundlePlacement::After(B256::repeat_byte(4))));
+
+        let json = r#"
+        {"txs":["0x747831"],"blockNumber":"0x1","placement":{"minPosition":3}}"#;
+        let deserialized: SignetEthBundle = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.placement(), Some(BundlePlacement::MinPosition(3)));
+
+        let json = r#"
+        {"txs":["0x747831"],"blockNumber":"0x1","placement":"bottomOfBlock"}"#;
+        let deserialized: SignetEthBundle = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.placement(), None);
     }
 
     /// Generate test vectors for TypeScript SDK.
@@ -1,6 +1,9 @@
+use crate::SignetEthBundle;
 use alloy::{
     consensus::{transaction::Recovered, Transaction, TxEnvelope},
+    eips::Encodable2718,
     primitives::{Address, TxHash, U256},
+    rpc::types::mev::EthSendBundle,
     serde::OtherFields,
 };
 
@@ -13,6 +16,9 @@ pub struct TxRequirement {
     pub nonce: u64,
     /// Max spend (max_fee_per_gas * gas_limit) + value
     pub balance: U256,
+    /// Whether the transaction may be dropped from the bundle if it is
+    /// invalid
+    pub droppable: bool,
 }
 
 /// Version of [`SignetEthBundle`] with decoded transactions.
@@ -108,6 +114,13 @@ impl RecoveredBundle {
         self.host_txs.as_slice()
     }
 
+    /// Remove the transactions and host transactions with the given hashes,
+    /// e.g. droppable transactions that were dropped during simulation.
+    pub fn remove_txs(&mut self, tx_hashes: &[TxHash]) {
+        self.txs.retain(|tx| !tx_hashes.contains(tx.hash()));
+        self.host_txs.retain(|tx| !tx_hashes.contains(tx.hash()));
+    }
+
     /// Get an iterator draining the transactions.
     pub fn drain_txns(&mut self) -> impl Iterator<Item = Recovered<TxEnvelope>> + '_ {
         self.txs.drain(..)
@@ -122,10 +135,12 @@ impl RecoveredBundle {
     /// - signer address
     /// - nonce
     /// - min_balance ((max_fee_per_gas * gas_limit) + value)
+    /// - whether the transaction is droppable
     pub fn tx_reqs(&self) -> impl Iterator<Item = TxRequirement> + '_ {
         self.txs.iter().map(|tx| {
             let balance = U256::from(tx.max_fee_per_gas() * tx.gas_limit() as u128) + tx.value();
-            TxRequirement { signer: tx.signer(), nonce: tx.nonce(), balance }
+            let droppable = self.is_droppable(tx.hash());
+            TxRequirement { signer: tx.signer(), nonce: tx.nonce(), balance, droppable }
         })
     }
 
@@ -133,10 +148,12 @@ impl RecoveredBundle {
     /// - signer address
     /// - nonce
     /// - min_balance ((max_fee_per_gas * gas_limit) + value)
+    /// - whether the transaction is droppable
     pub fn host_tx_reqs(&self) -> impl Iterator<Item = TxRequirement> + '_ {
         self.host_txs.iter().map(|tx| {
             let balance = U256::from(tx.max_fee_per_gas() * tx.gas_limit() as u128) + tx.value();
-            TxRequirement { signer: tx.signer(), nonce: tx.nonce(), balance }
+            let droppable = self.is_droppable(tx.hash());
+            TxRequirement { signer: tx.signer(), nonce: tx.nonce(), balance, droppable }
         })
     }
 
@@ -194,11 +211,53 @@ impl RecoveredBundle {
         }
     }
 
+    /// Getter for the replacement nonce. See
+    /// [`SignetEthBundle::replacement_nonce`].
+    ///
+    /// [`SignetEthBundle::replacement_nonce`]: crate::send::bundle::SignetEthBundle::replacement_nonce
+    pub fn replacement_nonce(&self) -> Option<u64> {
+        self.extra_fields.get_deserialized(crate::REPLACEMENT_NONCE_FIELD)?.ok()
+    }
+
+    /// Getter for the placement constraint. See
+    /// [`SignetEthBundle::placement`].
+    ///
+    /// [`SignetEthBundle::placement`]: crate::send::bundle::SignetEthBundle::placement
+    pub fn placement(&self) -> Option<crate::BundlePlacement> {
+        self.extra_fields.get_deserialized(crate::PLACEMENT_FIELD)?.ok()
+    }
+
     /// Getter for dropping_tx_hashes, a standard bundle prop.
     pub const fn dropping_tx_hashes(&self) -> &[TxHash] {
         self.dropping_tx_hashes.as_slice()
     }
 
+    /// Check if the transaction with the given hash may be dropped from the
+    /// bundle, i.e. it is in [`Self::dropping_tx_hashes`]. Droppable
+    /// transactions that fail for any reason are skipped, instead of
+    /// invalidating the bundle.
+    pub fn is_droppable(&self, tx_hash: &TxHash) -> bool {
+        self.dropping_tx_hashes.contains(tx_hash)
+    }
+
+    /// Check if the beneficiary payments of the transaction with the given
+    /// hash count towards the bundle refund. These are the transactions in
+    /// [`Self::refund_tx_hashes`], or the last transaction of the bundle if
+    /// none are specified.
+    pub fn is_refund_tx(&self, tx_hash: &TxHash) -> bool {
+        if self.refund_tx_hashes.is_empty() {
+            return self.txs.last().is_some_and(|tx| tx.hash() == tx_hash);
+        }
+        self.refund_tx_hashes.contains(tx_hash)
+    }
+
+    /// Get the address that receives the bundle refund. This is the
+    /// [`Self::refund_recipient`], or the signer of the first transaction of
+    /// the bundle if none is specified.
+    pub fn refund_recipient_or_default(&self) -> Option<Address> {
+        self.refund_recipient.or_else(|| self.txs.first().map(|tx| tx.signer()))
+    }
+
     /// Getter for refund_percent, a standard bundle prop.
     pub const fn refund_percent(&self) -> Option<u8> {
         self.refund_percent
@@ -232,3 +291,29 @@ impl RecoveredBundle {
         self.block_number == block_number
     }
 }
+
+impl From<&RecoveredBundle> for SignetEthBundle {
+    /// Encode the transactions of the bundle, e.g. to send or store it.
+    fn from(bundle: &RecoveredBundle) -> Self {
+        let encode = |txs: &[Recovered<TxEnvelope>]| {
+            txs.iter().map(|tx| tx.encoded_2718().into()).collect::<Vec<_>>()
+        };
+
+        Self::new(
+            EthSendBundle {
+                txs: encode(&bundle.txs),
+                block_number: bundle.block_number,
+                min_timestamp: bundle.min_timestamp,
+                max_timestamp: bundle.max_timestamp,
+                reverting_tx_hashes: bundle.reverting_tx_hashes.clone(),
+                replacement_uuid: bundle.replacement_uuid.clone(),
+                dropping_tx_hashes: bundle.dropping_tx_hashes.clone(),
+                refund_percent: bundle.refund_percent,
+                refund_recipient: bundle.refund_recipient,
+                refund_tx_hashes: bundle.refund_tx_hashes.clone(),
+                extra_fields: bundle.extra_fields.clone(),
+            },
+            encode(&bundle.host_txs),
+        )
+    }
+}
@@ -1,6 +1,12 @@
 use crate::{RecoveredBundle, SignetEthBundleError};
-use alloy::{hex, primitives::U256};
-use signet_evm::{DriveBundleResult, EvmErrored, EvmNeedsTx, SignetInspector, SignetLayered};
+use alloy::{
+    consensus::{Receipt, ReceiptEnvelope, TxType},
+    hex,
+    primitives::{TxHash, U256},
+};
+use signet_evm::{
+    DriveBundleResult, EvmNeedsTx, PrecompileRegistry, SignetInspector, SignetLayered,
+};
 use signet_types::{AggregateFills, AggregateOrders};
 use std::borrow::Cow;
 use tracing::{debug, debug_span, enabled, error};
@@ -8,7 +14,10 @@ use trevm::{
     helpers::Ctx,
     inspectors::{Layered, TimeLimit},
     revm::{
-        context::result::EVMError, inspector::InspectorEvmTr, Database, DatabaseCommit, Inspector,
+        context::result::{EVMError, ExecutionResult},
+        inspector::InspectorEvmTr,
+        state::EvmState,
+        Database, DatabaseCommit, Inspector,
     },
     trevm_bail, trevm_ensure, trevm_try, BundleDriver, BundleError,
 };
@@ -36,11 +45,35 @@ where
     /// Beneficiary balance increase during execution.
     pub beneficiary_balance_increase: U256,
 
+    /// Beneficiary balance increase during execution of the transactions
+    /// that count towards the bundle refund. See
+    /// [`RecoveredBundle::is_refund_tx`].
+    pub refundable_balance_increase: U256,
+
     /// Running aggregate of fills during execution.
     pub bundle_fills: AggregateFills,
 
     /// Running aggregate of orders during execution.
     pub bundle_orders: AggregateOrders,
+
+    /// Receipts of the rollup transactions whose state changes were
+    /// accepted. The cumulative gas used is relative to the start of the
+    /// bundle.
+    pub receipts: Vec<ReceiptEnvelope>,
+
+    /// Hashes of the droppable transactions that failed and were skipped.
+    /// Their state changes were discarded, and they used no gas.
+    pub dropped_txs: Vec<TxHash>,
+
+    /// The state loaded by each rollup transaction that ran, if state
+    /// recording is enabled. This includes transactions whose state changes
+    /// were discarded.
+    pub rollup_states: Vec<EvmState>,
+
+    /// The state loaded by each host transaction that ran, if state recording
+    /// is enabled. This includes transactions whose state changes were
+    /// discarded.
+    pub host_states: Vec<EvmState>,
 }
 
 impl<Db, Insp> DriverOutput<Db, Insp>
@@ -64,11 +97,34 @@ where
         self.bundle_orders.absorb(orders);
     }
 
+    /// Record the receipt of a rollup transaction. This should be called
+    /// after the transaction's gas is used.
+    pub fn push_receipt(&mut self, tx_type: TxType, result: &ExecutionResult) {
+        let receipt = Receipt {
+            status: result.is_success().into(),
+            cumulative_gas_used: self.total_gas_used,
+            logs: result.logs().to_vec(),
+        };
+        self.receipts.push(ReceiptEnvelope::from_typed(tx_type, receipt));
+    }
+
+    /// Record a droppable transaction that failed and was skipped.
+    pub fn drop_tx(&mut self, tx_hash: TxHash) {
+        self.dropped_txs.push(tx_hash);
+    }
+
     /// Record an increase in the beneficiary balance.
     pub const fn record_beneficiary_increase(&mut self, increase: U256) {
         self.beneficiary_balance_increase =
             self.beneficiary_balance_increase.saturating_add(increase);
     }
+
+    /// Record an increase in the beneficiary balance from a transaction
+    /// that counts towards the bundle refund.
+    pub const fn record_refundable_increase(&mut self, increase: U256) {
+        self.refundable_balance_increase =
+            self.refundable_balance_increase.saturating_add(increase);
+    }
 }
 
 /// Driver for applying a Signet Ethereum bundle to an EVM.
@@ -84,10 +140,17 @@ where
     /// Reference to the fill state to check against.
     pub fill_state: Cow<'b, AggregateFills>,
 
+    /// Registry of Signet precompiles, used to select the precompile set for
+    /// the rollup block.
+    precompiles: Cow<'b, PrecompileRegistry>,
+
     /// Execution deadline for this bundle. This limits the total WALLCLOCK
     /// time spent simulating the bundle.
     deadline: tokio::time::Instant,
 
+    /// Whether to record the state loaded by each transaction.
+    record_state: bool,
+
     // -- Accumulated outputs below here--
     output: DriverOutput<Db, Insp>,
 }
@@ -120,23 +183,55 @@ where
         Self {
             bundle,
             fill_state,
+            precompiles: Cow::Owned(PrecompileRegistry::default()),
             deadline,
+            record_state: false,
             output: DriverOutput {
                 host_evm: Some(host_evm),
                 total_gas_used: 0,
                 total_host_gas_used: 0,
                 beneficiary_balance_increase: U256::ZERO,
+                refundable_balance_increase: U256::ZERO,
                 bundle_fills: AggregateFills::default(),
                 bundle_orders: AggregateOrders::default(),
+                receipts: Vec::new(),
+                dropped_txs: Vec::new(),
+                rollup_states: Vec::new(),
+                host_states: Vec::new(),
             },
         }
     }
 
+    /// Set the registry of Signet precompiles. Defaults to
+    /// [`PrecompileRegistry::default`].
+    pub fn with_precompile_registry(mut self, precompiles: Cow<'b, PrecompileRegistry>) -> Self {
+        self.precompiles = precompiles;
+        self
+    }
+
+    /// Enable state recording. The [`DriverOutput`] will contain the state
+    /// loaded by every transaction that ran, e.g. to find the state read by
+    /// the bundle.
+    pub const fn with_state_recording(mut self) -> Self {
+        self.record_state = true;
+        self
+    }
+
+    /// Returns `true` if state recording is enabled.
+    pub const fn is_recording_state(&self) -> bool {
+        self.record_state
+    }
+
     /// Get a reference to the bundle.
     pub const fn bundle(&self) -> &RecoveredBundle {
         self.bundle
     }
 
+    /// Get the registry of Signet precompiles.
+    pub fn precompile_registry(&self) -> &PrecompileRegistry {
+        &self.precompiles
+    }
+
     /// Get the deadline for this driver.
     pub const fn deadline(&self) -> tokio::time::Instant {
         self.deadline
@@ -152,6 +247,12 @@ where
         self.output.beneficiary_balance_increase
     }
 
+    /// Get the beneficiary balance increase for this driver during execution
+    /// of the transactions that count towards the bundle refund.
+    pub const fn refundable_balance_increase(&self) -> U256 {
+        self.output.refundable_balance_increase
+    }
+
     /// Take the aggregate orders and fills from this driver.
     pub fn into_outputs(self) -> DriverOutput<Db, Insp> {
         self.output
@@ -194,6 +295,9 @@ where
 
         // -- STATEFUL ACTIONS --
 
+        // Select the precompile set for the rollup block's spec and height.
+        self.precompiles.apply(&mut trevm);
+
         // Get the beneficiary address and its initial balance
         let beneficiary = trevm.beneficiary();
         let inital_beneficiary_balance =
@@ -203,53 +307,69 @@ where
 
         // We simply run all host transactions first, accumulating their state
         // changes into the host_evm's state. If any reverts, we error out the
-        // simulation.
+        // simulation, unless the bundle allows dropping it.
         for tx in self.bundle.host_txs().iter() {
-            self.output.host_evm = Some(trevm_try!(
-                self.output
-                    .host_evm
-                    .take()
-                    .expect("host_evm missing")
-                    .run_tx(tx)
-                    .and_then(|mut htrevm| {
-                        let result = htrevm.result();
-                        if let Some(output) = result.output()  {
-                            if !result.is_success() {
-                                debug!(
-                                    tx_hash = %tx.hash(),
-                                    callee = ?htrevm.callee(),
-                                    sender = ?htrevm.caller(),
-                                    input = hex::encode(htrevm.input()),
-                                    output = hex::encode(output),
-                                    "host transaction reverted"
-                                );
-                            }
+            let droppable = self.bundle.is_droppable(tx.hash());
+            let htrevm = self.output.host_evm.take().expect("host_evm missing");
+
+            let result = match htrevm.run_tx(tx) {
+                // Droppable transactions that are invalid are skipped.
+                Err(err) if droppable && !matches!(err.error(), EVMError::Database(_)) => {
+                    debug!(tx_hash = %tx.hash(), err = %err.error(), "dropping invalid host transaction");
+                    self.output.drop_tx(*tx.hash());
+                    Ok(err.discard_error())
+                }
+                result => result.and_then(|mut htrevm| {
+                    if self.record_state {
+                        self.output.host_states.push(htrevm.result_and_state().state.clone());
+                    }
+
+                    let result = htrevm.result();
+                    if let Some(output) = result.output() {
+                        if !result.is_success() {
+                            debug!(
+                                tx_hash = %tx.hash(),
+                                callee = ?htrevm.callee(),
+                                sender = ?htrevm.caller(),
+                                input = hex::encode(htrevm.input()),
+                                output = hex::encode(output),
+                                "host transaction reverted"
+                            );
                         }
+                    }
 
-                        trevm_ensure!(
-                            result.is_success(),
-                            htrevm,
-                            EVMError::Custom("host transaction reverted".to_string())
-                        );
-
-                        // Accumulate gas used
-                        self.output.use_host_gas(result.gas_used());
-
-                        // The host fills go in the bundle fills.
-                        let host_fills = htrevm
-                            .inner_mut_unchecked()
-                            .inspector
-                            .as_mut_detector()
-                            .take_aggregates()
-                            .0;
-                        self.output.bundle_fills.absorb(&host_fills);
-
-                        Ok(htrevm.accept_state())
-                    })
-                    .map_err(|err| {
-                        debug!(err = %err.error(), err_dbg = ?err.error(), "error while running host transaction");
-                        SignetEthBundleError::HostSimulation("host simulation error")
-                    }),
+                    if !result.is_success() && droppable {
+                        self.output.drop_tx(*tx.hash());
+                        return Ok(htrevm.reject());
+                    }
+
+                    trevm_ensure!(
+                        result.is_success(),
+                        htrevm,
+                        EVMError::Custom("host transaction reverted".to_string())
+                    );
+
+                    // Accumulate gas used
+                    self.output.use_host_gas(result.gas_used());
+
+                    // The host fills go in the bundle fills.
+                    let host_fills = htrevm
+                        .inner_mut_unchecked()
+                        .inspector
+                        .as_mut_detector()
+                        .take_aggregates()
+                        .0;
+                    self.output.bundle_fills.absorb(&host_fills);
+
+                    Ok(htrevm.accept_state())
+                }),
+            };
+
+            self.output.host_evm = Some(trevm_try!(
+                result.map_err(|err| {
+                    debug!(err = %err.error(), err_dbg = ?err.error(), "error while running host transaction");
+                    SignetEthBundleError::HostSimulation("host simulation error")
+                }),
                 trevm
             ));
         }
@@ -270,13 +390,41 @@ where
             *limit = TimeLimit::new(self.deadline - tokio::time::Instant::now());
 
             let tx_hash = tx.hash();
+            let droppable = self.bundle.is_droppable(tx_hash);
+
+            // Measure the beneficiary payment of the transactions that count
+            // towards the bundle refund.
+            let refundable_from = if self.bundle.is_refund_tx(tx_hash) {
+                Some(trevm_try!(
+                    trevm.try_read_balance(beneficiary).map_err(EVMError::Database),
+                    trevm
+                ))
+            } else {
+                None
+            };
 
             // Temporary rebinding of trevm within each loop iteration.
             // The type of t is `EvmTransacted`, while the type of trevm is
             // `EvmNeedsTx`.
-            let mut t = trevm.run_tx(tx).map_err(EvmErrored::err_into).inspect_err(
-                |err| error!(err = %err.error(), "error while running rollup transaction"),
-            )?;
+            let mut t = match trevm.run_tx(tx) {
+                Ok(t) => t,
+                // Droppable transactions that are invalid (e.g. bad nonce or
+                // insufficient balance) are skipped.
+                Err(err) if droppable && !matches!(err.error(), EVMError::Database(_)) => {
+                    debug!(err = %err.error(), "dropping invalid transaction");
+                    self.output.drop_tx(*tx_hash);
+                    trevm = err.discard_error();
+                    continue;
+                }
+                Err(err) => {
+                    error!(err = %err.error(), "error while running rollup transaction");
+                    return Err(err.err_into());
+                }
+            };
+
+            if self.record_state {
+                self.output.rollup_states.push(t.result_and_state().state.clone());
+            }
 
             // Record tx details to the span for debugging.
             if enabled!(tracing::Level::DEBUG) {
@@ -317,6 +465,11 @@ where
                         debug!("transaction marked as revertible, reverting");
                         trevm = t.reject();
                         continue;
+                    } else if droppable {
+                        debug!("transaction dropped due to insufficient fills");
+                        self.output.drop_tx(*tx_hash);
+                        trevm = t.reject();
+                        continue;
                     } else {
                         debug!("transaction dropped due to insufficient fills, not marked as revertible");
                         return Err(t.errored(BundleError::BundleReverted.into()));
@@ -329,13 +482,17 @@ where
             } else {
                 // EVM Execution did not succeed.
                 // If not success, we are in a revert or halt. If the tx is
-                // not marked as revertible by the bundle, we error our
-                // simulation.
+                // not marked as revertible by the bundle, it is dropped if
+                // it is droppable, otherwise we error our simulation.
                 if !self.bundle.reverting_tx_hashes().contains(tx_hash) {
-                    debug!(
-                        output = result.output().map(hex::encode),
-                        "transaction reverted, not marked as revertible"
-                    );
+                    let output = result.output().map(hex::encode);
+                    if droppable {
+                        debug!(output, "transaction reverted, dropping");
+                        self.output.drop_tx(*tx_hash);
+                        trevm = t.reject();
+                        continue;
+                    }
+                    debug!(output, "transaction reverted, not marked as revertible");
                     return Err(t.errored(BundleError::BundleReverted.into()));
                 }
             }
@@ -343,7 +500,16 @@ where
             // If we did not shortcut return/continue, we accept the state
             // changes from this transaction.
             self.output.use_gas(gas_used);
-            trevm = t.accept_state()
+            self.output.push_receipt(tx.tx_type(), t.result());
+            trevm = t.accept_state();
+
+            if let Some(refundable_from) = refundable_from {
+                let balance = trevm_try!(
+                    trevm.try_read_balance(beneficiary).map_err(EVMError::Database),
+                    trevm
+                );
+                self.output.record_refundable_increase(balance.saturating_sub(refundable_from));
+            }
         }
 
         // -- CLEANUP --
@@ -1,11 +1,13 @@
 mod bundle;
-pub use bundle::{BundleInspector, SignetEthBundle};
+pub use bundle::{
+    BundleInspector, BundlePlacement, SignetEthBundle, PLACEMENT_FIELD, REPLACEMENT_NONCE_FIELD,
+};
 
 mod decoded;
 pub use decoded::{RecoveredBundle, TxRequirement};
 
 mod driver;
-pub use driver::{SignetEthBundleDriver, SignetEthBundleInsp};
+pub use driver::{DriverOutput, SignetEthBundleDriver, SignetEthBundleInsp};
 
 mod error;
 pub use error::{BundleRecoverError, RecoverError, SignetEthBundleError};
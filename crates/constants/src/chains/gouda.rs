@@ -1,8 +1,8 @@
 //! Constants for the Gouda testnet (runs on the Parmigiana host chain).
 
 use crate::{
-    HostConstants, HostTokens, HostUsdRecord, RollupConstants, RollupTokens, SignetConstants,
-    SignetEnvironmentConstants, SignetSystemConstants, UsdRecords,
+    HardforkSchedule, HostConstants, HostTokens, HostUsdRecord, RollupConstants, RollupTokens,
+    SignetConstants, SignetEnvironmentConstants, SignetSystemConstants, UsdRecords,
 };
 use alloy::primitives::{address, Address};
 use std::borrow::Cow;
@@ -94,8 +94,12 @@ pub const BASE_FEE_RECIPIENT: Address = address!("0xe0eDA3701D44511ce419344A4CeD
 pub const ROLLUP: RollupConstants =
     crate::RollupConstants::new(RU_CHAIN_ID, RU_ORDERS, RU_PASSAGE, BASE_FEE_RECIPIENT, RU_TOKENS);
 
+/// Rollup hardfork schedule for Gouda.
+pub const RU_FORKS: HardforkSchedule = HardforkSchedule::PRAGUE;
+
 /// Signet system constants for gouda.
-pub const GOUDA_SYS: SignetSystemConstants = crate::SignetSystemConstants::new(HOST, ROLLUP);
+pub const GOUDA_SYS: SignetSystemConstants =
+    crate::SignetSystemConstants::new(HOST, ROLLUP).with_forks(RU_FORKS);
 
 /// The URL of the Transaction Cache endpoint for gouda.
 pub const TX_CACHE_URL: &str = "https://transactions.gouda.signet.sh";
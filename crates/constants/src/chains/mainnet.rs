@@ -1,8 +1,8 @@
 //! Constants for the Mainnet.
 
 use crate::{
-    HostConstants, HostTokens, HostUsdRecord, RollupConstants, RollupTokens, SignetConstants,
-    SignetEnvironmentConstants, SignetSystemConstants, UsdRecords,
+    HardforkSchedule, HostConstants, HostTokens, HostUsdRecord, RollupConstants, RollupTokens,
+    SignetConstants, SignetEnvironmentConstants, SignetSystemConstants, UsdRecords,
 };
 use alloy::primitives::{address, Address};
 use std::borrow::Cow;
@@ -86,8 +86,12 @@ pub const BASE_FEE_RECIPIENT: Address = address!("0x86Fa9c9fb93C5F6022276db84bf2
 pub const ROLLUP: RollupConstants =
     crate::RollupConstants::new(RU_CHAIN_ID, RU_ORDERS, RU_PASSAGE, BASE_FEE_RECIPIENT, RU_TOKENS);
 
+/// Rollup hardfork schedule for Mainnet.
+pub const RU_FORKS: HardforkSchedule = HardforkSchedule::PRAGUE;
+
 /// Signet system constants for Mainnet.
-pub const MAINNET_SYS: SignetSystemConstants = crate::SignetSystemConstants::new(HOST, ROLLUP);
+pub const MAINNET_SYS: SignetSystemConstants =
+    crate::SignetSystemConstants::new(HOST, ROLLUP).with_forks(RU_FORKS);
 
 /// Signet environment constants for Mainnet.
 pub const MAINNET_ENV: SignetEnvironmentConstants = SignetEnvironmentConstants::new(
@@ -1,8 +1,8 @@
 //! Constants for the Pecorino testnet.
 
 use crate::{
-    HostConstants, HostTokens, HostUsdRecord, RollupConstants, RollupTokens, SignetConstants,
-    SignetEnvironmentConstants, SignetSystemConstants, UsdRecords,
+    HardforkSchedule, HostConstants, HostTokens, HostUsdRecord, RollupConstants, RollupTokens,
+    SignetConstants, SignetEnvironmentConstants, SignetSystemConstants, UsdRecords,
 };
 use alloy::primitives::{address, Address};
 use std::borrow::Cow;
@@ -93,8 +93,12 @@ pub const BASE_FEE_RECIPIENT: Address = address!("0xe0eDA3701D44511ce419344A4CeD
 pub const ROLLUP: RollupConstants =
     crate::RollupConstants::new(RU_CHAIN_ID, RU_ORDERS, RU_PASSAGE, BASE_FEE_RECIPIENT, RU_TOKENS);
 
+/// Rollup hardfork schedule for Pecorino.
+pub const RU_FORKS: HardforkSchedule = HardforkSchedule::PRAGUE;
+
 /// Signet system constants for Pecorino.
-pub const PECORINO_SYS: SignetSystemConstants = crate::SignetSystemConstants::new(HOST, ROLLUP);
+pub const PECORINO_SYS: SignetSystemConstants =
+    crate::SignetSystemConstants::new(HOST, ROLLUP).with_forks(RU_FORKS);
 
 /// Signet environment constants for Pecorino.
 pub const PECORINO_ENV: SignetEnvironmentConstants = SignetEnvironmentConstants::new(
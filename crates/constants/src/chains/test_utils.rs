@@ -2,8 +2,8 @@
 
 use crate::{
     types::{
-        HostConstants, HostTokens, HostUsdRecord, RollupConstants, RollupTokens, SignetConstants,
-        SignetEnvironmentConstants, SignetSystemConstants,
+        HardforkSchedule, HostConstants, HostTokens, HostUsdRecord, RollupConstants, RollupTokens,
+        SignetConstants, SignetEnvironmentConstants, SignetSystemConstants,
     },
     UsdRecords,
 };
@@ -101,8 +101,12 @@ pub const HOST: HostConstants = HostConstants::new(
 pub const ROLLUP: RollupConstants =
     RollupConstants::new(RU_CHAIN_ID, RU_ORDERS, RU_PASSAGE, BASE_FEE_RECIPIENT, RU_TOKENS);
 
+/// Rollup hardfork schedule for unit tests.
+pub const RU_FORKS: HardforkSchedule = HardforkSchedule::PRAGUE;
+
 /// System constants for unit tests.
-pub const TEST_SYS: SignetSystemConstants = SignetSystemConstants::new(HOST, ROLLUP);
+pub const TEST_SYS: SignetSystemConstants =
+    SignetSystemConstants::new(HOST, ROLLUP).with_forks(RU_FORKS);
 
 /// Environment constants for unit tests.
 pub const TEST_ENV: SignetEnvironmentConstants = SignetEnvironmentConstants::new(
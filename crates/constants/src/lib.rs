@@ -23,8 +23,8 @@ pub use chains::{gouda, mainnet, parmigiana, test_utils};
 
 mod types;
 pub use types::{
-    ConfigError, HostConstants, HostPermitted, HostTokens, HostUsdRecord, KnownChains,
-    PairedHeights, ParseChainError, RollupConstants, RollupPermitted, RollupTokens,
+    ConfigError, HardforkSchedule, HostConstants, HostPermitted, HostTokens, HostUsdRecord,
+    KnownChains, PairedHeights, ParseChainError, RollupConstants, RollupPermitted, RollupTokens,
     SignetConstants, SignetEnvironmentConstants, SignetSystemConstants, UsdRecords, MINTER_ADDRESS,
 };
 
@@ -0,0 +1,325 @@
+use crate::types::{KnownChains, ParseChainError};
+use alloy::genesis::{ChainConfig, Genesis};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// Key of the Amsterdam activation timestamp in the [`ChainConfig`] extra
+/// fields. Amsterdam is not yet a first-class [`ChainConfig`] field.
+const AMSTERDAM_TIME: &str = "amsterdamTime";
+
+/// The hardfork schedule of the rollup chain.
+///
+/// Block-based forks activate at a rollup height, and time-based forks at a
+/// rollup block timestamp. A fork that is `None` is not scheduled. The fields
+/// mirror the hardfork fields of the genesis [`ChainConfig`], from which the
+/// schedule may be loaded with [`HardforkSchedule::from_chain_config`].
+///
+/// The default schedule activates all forks up to and including Prague at
+/// genesis.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardforkSchedule {
+    /// Homestead activation block.
+    pub homestead_block: Option<u64>,
+    /// DAO fork activation block.
+    pub dao_fork_block: Option<u64>,
+    /// Whether the chain supports the DAO fork.
+    pub dao_fork_support: bool,
+    /// Tangerine Whistle (EIP-150) activation block.
+    pub eip150_block: Option<u64>,
+    /// Spurious Dragon (EIP-158) activation block.
+    pub eip158_block: Option<u64>,
+    /// Byzantium activation block.
+    pub byzantium_block: Option<u64>,
+    /// Constantinople activation block.
+    pub constantinople_block: Option<u64>,
+    /// Petersburg activation block.
+    pub petersburg_block: Option<u64>,
+    /// Istanbul activation block.
+    pub istanbul_block: Option<u64>,
+    /// Muir Glacier activation block.
+    pub muir_glacier_block: Option<u64>,
+    /// Berlin activation block.
+    pub berlin_block: Option<u64>,
+    /// London activation block.
+    pub london_block: Option<u64>,
+    /// Arrow Glacier activation block.
+    pub arrow_glacier_block: Option<u64>,
+    /// Gray Glacier activation block.
+    pub gray_glacier_block: Option<u64>,
+    /// Whether the chain is past the merge.
+    pub terminal_total_difficulty_passed: bool,
+    /// Shanghai activation timestamp.
+    pub shanghai_time: Option<u64>,
+    /// Cancun activation timestamp.
+    pub cancun_time: Option<u64>,
+    /// Prague activation timestamp.
+    pub prague_time: Option<u64>,
+    /// Osaka activation timestamp.
+    pub osaka_time: Option<u64>,
+    /// BPO1 activation timestamp.
+    pub bpo1_time: Option<u64>,
+    /// BPO2 activation timestamp.
+    pub bpo2_time: Option<u64>,
+    /// BPO3 activation timestamp.
+    pub bpo3_time: Option<u64>,
+    /// BPO4 activation timestamp.
+    pub bpo4_time: Option<u64>,
+    /// BPO5 activation timestamp.
+    pub bpo5_time: Option<u64>,
+    /// Amsterdam activation timestamp.
+    pub amsterdam_time: Option<u64>,
+}
+
+impl Default for HardforkSchedule {
+    fn default() -> Self {
+        Self::PRAGUE
+    }
+}
+
+impl HardforkSchedule {
+    /// A schedule with no forks scheduled. Only Frontier is active.
+    pub const EMPTY: Self = Self {
+        homestead_block: None,
+        dao_fork_block: None,
+        dao_fork_support: false,
+        eip150_block: None,
+        eip158_block: None,
+        byzantium_block: None,
+        constantinople_block: None,
+        petersburg_block: None,
+        istanbul_block: None,
+        muir_glacier_block: None,
+        berlin_block: None,
+        london_block: None,
+        arrow_glacier_block: None,
+        gray_glacier_block: None,
+        terminal_total_difficulty_passed: false,
+        shanghai_time: None,
+        cancun_time: None,
+        prague_time: None,
+        osaka_time: None,
+        bpo1_time: None,
+        bpo2_time: None,
+        bpo3_time: None,
+        bpo4_time: None,
+        bpo5_time: None,
+        amsterdam_time: None,
+    };
+
+    /// A schedule activating all forks up to and including Prague at
+    /// genesis.
+    pub const PRAGUE: Self = Self {
+        homestead_block: Some(0),
+        eip150_block: Some(0),
+        eip158_block: Some(0),
+        byzantium_block: Some(0),
+        constantinople_block: Some(0),
+        petersburg_block: Some(0),
+        istanbul_block: Some(0),
+        berlin_block: Some(0),
+        london_block: Some(0),
+        terminal_total_difficulty_passed: true,
+        shanghai_time: Some(0),
+        cancun_time: Some(0),
+        prague_time: Some(0),
+        ..Self::EMPTY
+    };
+
+    /// Get the hard-coded Mainnet rollup schedule.
+    pub const fn mainnet() -> Self {
+        crate::chains::mainnet::RU_FORKS
+    }
+
+    /// Get the hard-coded Parmigiana rollup schedule.
+    pub const fn parmigiana() -> Self {
+        crate::chains::parmigiana::RU_FORKS
+    }
+
+    /// Get the hard-coded Gouda rollup schedule.
+    pub const fn gouda() -> Self {
+        crate::chains::gouda::RU_FORKS
+    }
+
+    /// Get the hard-coded Pecorino rollup schedule.
+    #[deprecated(note = "Pecorino is being deprecated in favor of Parmigiana")]
+    #[allow(deprecated)]
+    pub const fn pecorino() -> Self {
+        crate::chains::pecorino::RU_FORKS
+    }
+
+    /// Get the hard-coded local test rollup schedule.
+    pub const fn test() -> Self {
+        crate::chains::test_utils::RU_FORKS
+    }
+
+    /// Load the schedule from a [`ChainConfig`].
+    ///
+    /// The Amsterdam activation timestamp is read from the `amsterdamTime`
+    /// extra field, if present.
+    pub fn from_chain_config(config: &ChainConfig) -> Self {
+        Self {
+            homestead_block: config.homestead_block,
+            dao_fork_block: config.dao_fork_block,
+            dao_fork_support: config.dao_fork_support,
+            eip150_block: config.eip150_block,
+            eip158_block: config.eip158_block,
+            byzantium_block: config.byzantium_block,
+            constantinople_block: config.constantinople_block,
+            petersburg_block: config.petersburg_block,
+            istanbul_block: config.istanbul_block,
+            muir_glacier_block: config.muir_glacier_block,
+            berlin_block: config.berlin_block,
+            london_block: config.london_block,
+            arrow_glacier_block: config.arrow_glacier_block,
+            gray_glacier_block: config.gray_glacier_block,
+            terminal_total_difficulty_passed: config.terminal_total_difficulty_passed,
+            shanghai_time: config.shanghai_time,
+            cancun_time: config.cancun_time,
+            prague_time: config.prague_time,
+            osaka_time: config.osaka_time,
+            bpo1_time: config.bpo1_time,
+            bpo2_time: config.bpo2_time,
+            bpo3_time: config.bpo3_time,
+            bpo4_time: config.bpo4_time,
+            bpo5_time: config.bpo5_time,
+            amsterdam_time: config.extra_fields.get(AMSTERDAM_TIME).and_then(Value::as_u64),
+        }
+    }
+
+    /// Convert the schedule to a [`ChainConfig`] with only the hardfork fields
+    /// set. The Amsterdam activation timestamp is written to the
+    /// `amsterdamTime` extra field.
+    pub fn to_chain_config(&self) -> ChainConfig {
+        let mut config = ChainConfig {
+            homestead_block: self.homestead_block,
+            dao_fork_block: self.dao_fork_block,
+            dao_fork_support: self.dao_fork_support,
+            eip150_block: self.eip150_block,
+            eip158_block: self.eip158_block,
+            byzantium_block: self.byzantium_block,
+            constantinople_block: self.constantinople_block,
+            petersburg_block: self.petersburg_block,
+            istanbul_block: self.istanbul_block,
+            muir_glacier_block: self.muir_glacier_block,
+            berlin_block: self.berlin_block,
+            london_block: self.london_block,
+            arrow_glacier_block: self.arrow_glacier_block,
+            gray_glacier_block: self.gray_glacier_block,
+            terminal_total_difficulty_passed: self.terminal_total_difficulty_passed,
+            shanghai_time: self.shanghai_time,
+            cancun_time: self.cancun_time,
+            prague_time: self.prague_time,
+            osaka_time: self.osaka_time,
+            bpo1_time: self.bpo1_time,
+            bpo2_time: self.bpo2_time,
+            bpo3_time: self.bpo3_time,
+            bpo4_time: self.bpo4_time,
+            bpo5_time: self.bpo5_time,
+            ..Default::default()
+        };
+        if let Some(time) = self.amsterdam_time {
+            config.extra_fields.insert(AMSTERDAM_TIME.to_owned(), time.into());
+        }
+        config
+    }
+
+    /// Load the schedule from the [`ChainConfig`] of a [`Genesis`].
+    ///
+    /// Returns `None` if the config schedules no forks at all, rather than
+    /// treating the chain as Frontier-only.
+    pub fn from_genesis(genesis: &Genesis) -> Option<Self> {
+        let schedule = Self::from_chain_config(&genesis.config);
+        (schedule != Self::EMPTY).then_some(schedule)
+    }
+}
+
+impl TryFrom<KnownChains> for HardforkSchedule {
+    type Error = ParseChainError;
+
+    fn try_from(chain: KnownChains) -> Result<Self, Self::Error> {
+        match chain {
+            KnownChains::Mainnet => Ok(Self::mainnet()),
+            KnownChains::Parmigiana => Ok(Self::parmigiana()),
+            KnownChains::Gouda => Ok(Self::gouda()),
+            #[allow(deprecated)]
+            KnownChains::Pecorino => Ok(Self::pecorino()),
+            KnownChains::Test => Ok(Self::test()),
+        }
+    }
+}
+
+impl FromStr for HardforkSchedule {
+    type Err = ParseChainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<KnownChains>()?.try_into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_chain_config() {
+        let genesis: Genesis = serde_json::from_value(serde_json::json!({
+            "config": {
+                "chainId": 88888,
+                "homesteadBlock": 0,
+                "londonBlock": 0,
+                "terminalTotalDifficultyPassed": true,
+                "shanghaiTime": 0,
+                "cancunTime": 0,
+                "pragueTime": 0,
+                "osakaTime": 1000,
+                "amsterdamTime": 2000
+            },
+            "alloc": {}
+        }))
+        .unwrap();
+
+        let schedule = HardforkSchedule::from_genesis(&genesis).unwrap();
+        assert_eq!(schedule.homestead_block, Some(0));
+        assert_eq!(schedule.byzantium_block, None);
+        assert!(schedule.terminal_total_difficulty_passed);
+        assert_eq!(schedule.prague_time, Some(0));
+        assert_eq!(schedule.osaka_time, Some(1000));
+        assert_eq!(schedule.amsterdam_time, Some(2000));
+    }
+
+    #[test]
+    fn chain_config_round_trip() {
+        let schedule = HardforkSchedule {
+            osaka_time: Some(1000),
+            amsterdam_time: Some(2000),
+            ..HardforkSchedule::PRAGUE
+        };
+        assert_eq!(HardforkSchedule::from_chain_config(&schedule.to_chain_config()), schedule);
+    }
+
+    #[test]
+    fn genesis_without_forks() {
+        let genesis: Genesis = serde_json::from_value(serde_json::json!({
+            "config": { "chainId": 88888 },
+            "alloc": {}
+        }))
+        .unwrap();
+
+        assert_eq!(HardforkSchedule::from_genesis(&genesis), None);
+
+        let mut constants = serde_json::to_value(crate::SignetSystemConstants::test()).unwrap();
+        constants.as_object_mut().unwrap().remove("forks");
+        let mut genesis = genesis;
+        genesis.config.extra_fields.insert("signetConstants".to_owned(), constants);
+
+        let constants = crate::SignetSystemConstants::try_from_genesis(&genesis).unwrap();
+        assert_eq!(constants.forks(), &HardforkSchedule::PRAGUE);
+    }
+
+    #[test]
+    fn known_chains() {
+        assert_eq!("test".parse::<HardforkSchedule>().unwrap(), HardforkSchedule::test());
+        assert_eq!(HardforkSchedule::default(), HardforkSchedule::PRAGUE);
+    }
+}
@@ -1,6 +1,9 @@
 mod error;
 pub use error::ConfigError;
 
+mod forks;
+pub use forks::HardforkSchedule;
+
 mod height;
 pub use height::PairedHeights;
 
@@ -39,12 +42,22 @@ pub struct SignetSystemConstants {
     host: HostConstants,
     /// Rollup constants.
     rollup: RollupConstants,
+    /// Rollup hardfork schedule.
+    #[serde(default)]
+    forks: HardforkSchedule,
 }
 
 impl SignetSystemConstants {
-    /// Create a new set of constants.
+    /// Create a new set of constants, with the default
+    /// [`HardforkSchedule`].
     pub const fn new(host: HostConstants, rollup: RollupConstants) -> Self {
-        Self { host, rollup }
+        Self { host, rollup, forks: HardforkSchedule::PRAGUE }
+    }
+
+    /// Set the rollup hardfork schedule.
+    pub const fn with_forks(mut self, forks: HardforkSchedule) -> Self {
+        self.forks = forks;
+        self
     }
 
     /// Get the hard-coded Mainnet constants.
@@ -74,12 +87,21 @@ impl SignetSystemConstants {
         crate::chains::test_utils::TEST_SYS
     }
 
-    /// Load the constants from a [`Genesis`].
+    /// Load the constants from a [`Genesis`]. The rollup hardfork schedule
+    /// is loaded from the genesis [`ChainConfig`]. If the config schedules no
+    /// forks, the `forks` entry of the `signetConstants` is used instead,
+    /// which defaults to [`HardforkSchedule::PRAGUE`].
+    ///
+    /// [`ChainConfig`]: alloy::genesis::ChainConfig
     pub fn try_from_genesis(genesis: &Genesis) -> Result<Self, ConfigError> {
         let k = "signetConstants";
         let constants =
             genesis.config.extra_fields.get(k).ok_or_else(|| ConfigError::missing(k))?;
-        serde_json::from_value(constants.clone()).map_err(Into::into)
+        let constants: Self = serde_json::from_value(constants.clone())?;
+        Ok(match HardforkSchedule::from_genesis(genesis) {
+            Some(forks) => constants.with_forks(forks),
+            None => constants,
+        })
     }
 
     /// Get the host addresses.
@@ -92,6 +114,11 @@ impl SignetSystemConstants {
         self.rollup
     }
 
+    /// Get the rollup hardfork schedule.
+    pub const fn forks(&self) -> &HardforkSchedule {
+        &self.forks
+    }
+
     /// True if the contract is a system contract deployed on the rollup.
     pub const fn const_is_ru_system_contract(&self, address: Address) -> bool {
         self.rollup.const_is_system_contract(address)
@@ -1,6 +1,6 @@
 use crate::{
-    orders::SignetInspector, BlockResult, EvmNeedsTx, EvmTransacted, ExecutionOutcome, RunTxResult,
-    SignetLayered,
+    orders::SignetInspector, sys::SystemHook, BlockResult, EthereumHardfork, EvmNeedsTx,
+    EvmTransacted, ExecutionOutcome, PrecompileRegistry, RunTxResult, SignetLayered,
 };
 use alloy::{
     consensus::{
@@ -19,7 +19,7 @@ use signet_types::{
 #[cfg(doc)]
 use signet_zenith::Transactor;
 use std::collections::VecDeque;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 use tracing::{debug, debug_span, info_span, warn};
 use trevm::{
     helpers::Ctx,
@@ -30,6 +30,7 @@ use trevm::{
         },
         context_interface::block::BlobExcessGasAndPrice,
         database::State,
+        primitives::hardfork::SpecId,
         Database, DatabaseCommit, Inspector,
     },
     trevm_try, Block, BlockDriver, BlockOutput, Cfg, Tx,
@@ -78,7 +79,7 @@ where
 
 /// Shim to impl [`Tx`] for [`TransactionSigned`]
 #[derive(Debug)]
-struct FillShim<'a>(&'a TransactionSigned, Address);
+pub(crate) struct FillShim<'a>(pub(crate) &'a TransactionSigned, pub(crate) Address);
 
 impl Tx for FillShim<'_> {
     fn fill_tx_env(&self, tx_env: &mut TxEnv) {
@@ -210,10 +211,10 @@ pub struct SignetDriver<'a, 'b, C: Extractable> {
 
     /// The working context is a clone of the block's [`AggregateFills`] that
     /// is updated progessively as the block is evaluated.
-    working_context: AggregateFills,
+    pub(crate) working_context: AggregateFills,
 
     /// Transactions in the RU block (if any)
-    to_process: VecDeque<TransactionSigned>,
+    pub(crate) to_process: VecDeque<TransactionSigned>,
 
     /// Transactions that have been processed.
     processed: Vec<TransactionSigned>,
@@ -225,7 +226,18 @@ pub struct SignetDriver<'a, 'b, C: Extractable> {
     pub(crate) output: BlockOutput,
 
     /// Payable gas used in the block.
-    payable_gas_used: u64,
+    pub(crate) payable_gas_used: u64,
+
+    /// Registry of Signet precompiles, used to select the precompile set for
+    /// the block.
+    pub(crate) precompiles: PrecompileRegistry,
+
+    /// System hooks, run at the start and end of the block.
+    pub(crate) hooks: Vec<Arc<dyn SystemHook>>,
+
+    /// Number of system hook outputs applied in the block. Used as the event
+    /// index of the output's magic signature.
+    pub(crate) system_outputs: usize,
 }
 
 impl<'a, 'b, C: Extractable> SignetDriver<'a, 'b, C> {
@@ -252,9 +264,50 @@ impl<'a, 'b, C: Extractable> SignetDriver<'a, 'b, C> {
             transactions_root: OnceLock::new(),
             output: BlockOutput::with_capacity(cap),
             payable_gas_used: 0,
+            precompiles: PrecompileRegistry::default(),
+            hooks: Vec::new(),
+            system_outputs: 0,
         }
     }
 
+    /// Set the registry of Signet precompiles. Defaults to
+    /// [`PrecompileRegistry::default`].
+    pub fn with_precompile_registry(mut self, precompiles: PrecompileRegistry) -> Self {
+        self.precompiles = precompiles;
+        self
+    }
+
+    /// Get the registry of Signet precompiles.
+    pub const fn precompile_registry(&self) -> &PrecompileRegistry {
+        &self.precompiles
+    }
+
+    /// Register a [`SystemHook`]. Hooks run in registration order.
+    pub fn with_system_hook(self, hook: impl SystemHook + 'static) -> Self {
+        self.with_shared_system_hook(Arc::new(hook))
+    }
+
+    /// Register a shared [`SystemHook`]. Hooks run in registration order.
+    pub fn with_shared_system_hook(mut self, hook: Arc<dyn SystemHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Get the registered [`SystemHook`]s.
+    pub fn system_hooks(&self) -> &[Arc<dyn SystemHook>] {
+        &self.hooks
+    }
+
+    /// Get the [`SpecId`] of the block, from the rollup hardfork schedule in
+    /// the [`SignetSystemConstants`].
+    pub fn spec_id(&self) -> SpecId {
+        EthereumHardfork::spec_id_in_schedule(
+            self.constants.forks(),
+            self.ru_height(),
+            self.extracts.host_block.timestamp(),
+        )
+    }
+
     /// Populate the memoized transactions root.
     fn seal(&self) {
         self.transactions_root
@@ -474,13 +527,29 @@ impl<'a, 'b, C: Extractable> SignetDriver<'a, 'b, C> {
     where
         Db: Database + DatabaseCommit,
         Insp: Inspector<Ctx<Db>>,
+    {
+        // Accept the result.
+        let (result, trevm) = trevm.accept();
+        self.push_accepted(&trevm, result, tx);
+        trevm
+    }
+
+    /// Push an accepted transaction and a receipt for its result to the
+    /// block. The receipt type is determined by the EVM's current tx env,
+    /// which must be that of the transaction.
+    pub(crate) fn push_accepted<Db, Insp>(
+        &mut self,
+        trevm: &EvmNeedsTx<Db, Insp>,
+        result: ExecutionResult,
+        tx: TransactionSigned,
+    ) where
+        Db: Database + DatabaseCommit,
+        Insp: Inspector<Ctx<Db>>,
     {
         // Invalidate memoized root before mutation.
         self.unseal();
         // Push the transaction to the block.
         self.processed.push(tx);
-        // Accept the result.
-        let (result, trevm) = trevm.accept();
 
         // Create a receipt for the transaction.
         let tx_env = trevm.inner().ctx.tx();
@@ -496,7 +565,6 @@ impl<'a, 'b, C: Extractable> SignetDriver<'a, 'b, C> {
         };
 
         self.output.push_result(receipt, sender);
-        trevm
     }
 
     /// Execute a transaction.
@@ -533,7 +601,7 @@ impl<'a, 'b, C: Extractable> SignetDriver<'a, 'b, C> {
     }
 
     /// Execute all transactions. This is run before enters and transacts
-    fn execute_all_transactions<Db, Insp>(
+    pub(crate) fn execute_all_transactions<Db, Insp>(
         &mut self,
         mut trevm: EvmNeedsTx<Db, Insp>,
     ) -> RunTxResult<Self, Db, Insp>
@@ -552,6 +620,76 @@ impl<'a, 'b, C: Extractable> SignetDriver<'a, 'b, C> {
         Ok(trevm)
     }
 
+    /// Run the block lifecycle, executing the builder-created transactions
+    /// with `execute_transactions`.
+    pub(crate) fn run_block<Db, Insp, F>(
+        &mut self,
+        mut trevm: EvmNeedsTx<Db, Insp>,
+        execute_transactions: F,
+    ) -> RunTxResult<Self, Db, Insp>
+    where
+        Db: Database + DatabaseCommit,
+        Insp: Inspector<Ctx<Db>>,
+        F: FnOnce(&mut Self, EvmNeedsTx<Db, Insp>) -> RunTxResult<Self, Db, Insp>,
+    {
+        let _span = info_span!(
+            "SignetDriver::run_txns",
+            txn_count = self.to_process.len(),
+            enter_count = self.extracts.enters.len(),
+            enter_token_count = self.extracts.enter_tokens.len(),
+            transact_count = self.extracts.transacts.len(),
+            base_fee_beneficiary = %self.constants.base_fee_recipient(),
+            rollup_passage = %self.constants.rollup().passage(),
+            parent_hash = %self.parent.hash(),
+        )
+        .entered();
+
+        // NB:
+        // The signet block lifecycle is roughly as follows:
+        // - Run pre-block system hooks.
+        // - Execute the builder-created block by executing each transaction in
+        //   order.
+        // - Process each enter event in order.
+        // - Process each enter token event in order.
+        // - Run system built-in application logic
+        // - Process each transact event in order.
+        // - Run post-block system hooks.
+        // - Set the balance of the rollup passage to zero.
+        // - Credit the basefee to the basefee beneficiary.
+
+        // Set the spec from the rollup hardfork schedule, and select the
+        // precompile set for the block's spec and height.
+        trevm.set_spec_id(self.spec_id());
+        self.precompiles.apply(&mut trevm);
+
+        // Run the pre-block system hooks.
+        // Gas is unmetered, and does not pay basefee
+        trevm = self.run_pre_block_hooks(trevm)?;
+
+        // Run the transactions.
+        // Transaction gas is metered, and pays basefee
+        trevm = execute_transactions(self, trevm)?;
+
+        // Run all Enter and EnterToken events
+        // Gas is unmetered, and does not pay basefee
+        trevm = self.run_all_mints(trevm)?;
+
+        // Run the transact events.
+        // Transact gas is metered, and pays basefee
+        trevm = self.execute_all_transacts(trevm)?;
+
+        // Run the post-block system hooks.
+        // Gas is unmetered, and does not pay basefee
+        trevm = self.run_post_block_hooks(trevm)?;
+
+        // Clear the balance of the rollup passage.
+        trevm = self.clear_ru_passage_balance(trevm)?;
+
+        // Credit the basefee to the basefee beneficiary. This is the sum
+        // of the basefee for the transactions and transact events.
+        self.credit_base_fee(trevm, self.payable_gas_used())
+    }
+
     /// Clear the balance of the rollup passage. This is run at the end of the
     /// block, after all transactions, enters, and transact events have been
     /// processed. It ensures that ETH sent to the rollup passage is burned,
@@ -610,6 +748,7 @@ impl<'a, 'b, C: Extractable> SignetDriver<'a, 'b, C> {
 impl<C: Extractable> Cfg for SignetDriver<'_, '_, C> {
     fn fill_cfg_env(&self, cfg_env: &mut CfgEnv) {
         cfg_env.chain_id = self.extracts.chain_id;
+        cfg_env.spec = self.spec_id();
     }
 }
 
@@ -626,48 +765,8 @@ where
         self
     }
 
-    fn run_txns(&mut self, mut trevm: EvmNeedsTx<Db, Insp>) -> RunTxResult<Self, Db, Insp> {
-        let _span = info_span!(
-            "SignetDriver::run_txns",
-            txn_count = self.to_process.len(),
-            enter_count = self.extracts.enters.len(),
-            enter_token_count = self.extracts.enter_tokens.len(),
-            transact_count = self.extracts.transacts.len(),
-            base_fee_beneficiary = %self.constants.base_fee_recipient(),
-            rollup_passage = %self.constants.rollup().passage(),
-            parent_hash = %self.parent.hash(),
-        )
-        .entered();
-
-        // NB:
-        // The signet block lifecycle is roughly as follows:
-        // - Execute the builder-created block by executing each transaction in
-        //   order.
-        // - Process each enter event in order.
-        // - Process each enter token event in order.
-        // - Run system built-in application logic
-        // - Process each transact event in order.
-        // - Set the balance of the rollup passage to zero.
-        // - Credit the basefee to the basefee beneficiary.
-
-        // Run the transactions.
-        // Transaction gas is metered, and pays basefee
-        trevm = self.execute_all_transactions(trevm)?;
-
-        // Run all Enter and EnterToken events
-        // Gas is unmetered, and does not pay basefee
-        trevm = self.run_all_mints(trevm)?;
-
-        // Run the transact events.
-        // Transact gas is metered, and pays basefee
-        trevm = self.execute_all_transacts(trevm)?;
-
-        // Clear the balance of the rollup passage.
-        trevm = self.clear_ru_passage_balance(trevm)?;
-
-        // Credit the basefee to the basefee beneficiary. This is the sum
-        // of the basefee for the transactions and transact events.
-        self.credit_base_fee(trevm, self.payable_gas_used())
+    fn run_txns(&mut self, trevm: EvmNeedsTx<Db, Insp>) -> RunTxResult<Self, Db, Insp> {
+        self.run_block(trevm, Self::execute_all_transactions)
     }
 
     fn post_block(&mut self, _trevm: &crate::EvmNeedsBlock<Db, Insp>) -> Result<(), Self::Error> {
@@ -7,6 +7,7 @@ use alloy::{
     primitives::B256,
 };
 use bitflags::bitflags;
+use signet_types::constants::HardforkSchedule;
 
 bitflags! {
     #[doc="Ethereum HardForks."]
@@ -233,6 +234,23 @@ impl EthereumHardfork {
             | fork_active(config.bpo5_time, timestamp, Self::Bpo5)
     }
 
+    /// Returns the [`SpecId`] of the highest active hardfork at the given
+    /// rollup block number and timestamp, as determined by the given
+    /// [`HardforkSchedule`].
+    ///
+    /// [`SpecId`]: trevm::revm::primitives::hardfork::SpecId
+    pub fn spec_id_in_schedule(
+        schedule: &HardforkSchedule,
+        block: u64,
+        timestamp: u64,
+    ) -> trevm::revm::primitives::hardfork::SpecId {
+        // Amsterdam is not a first-class `ChainConfig` fork, so it is not
+        // covered by `active_hardforks`.
+        let forks = Self::active_hardforks(&schedule.to_chain_config(), block, timestamp)
+            | fork_active(schedule.amsterdam_time, timestamp, Self::Amsterdam);
+        forks.spec_id()
+    }
+
     /// Returns all active hardforks at the given [`Header`]'s block number
     /// and timestamp, as determined by the given [`ChainConfig`].
     pub fn active_hardforks_at_header(config: &ChainConfig, header: &Header) -> Self {
@@ -591,6 +609,22 @@ mod tests {
         assert_eq!(latest, EthereumHardfork::London);
     }
 
+    #[test]
+    fn schedule_spec_id() {
+        let schedule = HardforkSchedule {
+            osaka_time: Some(100),
+            amsterdam_time: Some(200),
+            ..HardforkSchedule::PRAGUE
+        };
+        assert_eq!(EthereumHardfork::spec_id_in_schedule(&schedule, 0, 0), SpecId::PRAGUE);
+        assert_eq!(EthereumHardfork::spec_id_in_schedule(&schedule, 0, 100), SpecId::OSAKA);
+        assert_eq!(EthereumHardfork::spec_id_in_schedule(&schedule, 0, 200), SpecId::AMSTERDAM);
+        assert_eq!(
+            EthereumHardfork::spec_id_in_schedule(&HardforkSchedule::EMPTY, 0, 0),
+            SpecId::FRONTIER
+        );
+    }
+
     #[test]
     fn bpo_forks_activate() {
         let config =
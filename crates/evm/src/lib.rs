@@ -28,11 +28,14 @@ pub use driver::SignetDriver;
 mod orders;
 pub use orders::{Framed, FramedFilleds, FramedOrders, OrderDetector, SignetInspector};
 
+mod parallel;
+pub use parallel::ParallelSignetDriver;
+
 mod outcome;
 pub use outcome::ExecutionOutcome;
 
 mod precompiles;
-pub use precompiles::signet_precompiles;
+pub use precompiles::{signet_precompiles, PrecompileRegistry};
 
 mod result;
 pub use result::BlockResult;
@@ -49,6 +52,11 @@ use trevm::{
 pub mod sys;
 
 /// Create a new EVM with the given database.
+///
+/// The EVM starts with the [`signet_precompiles`] set. The set for each block
+/// is selected by the [`PrecompileRegistry`], once the cfg and block have
+/// been filled. [`SignetDriver`] and the bundle drivers do this when driving
+/// a block or bundle, otherwise use [`PrecompileRegistry::apply`].
 pub fn signet_evm<Db: Database + DatabaseCommit>(
     db: Db,
     constants: SignetSystemConstants,
@@ -61,6 +69,8 @@ pub fn signet_evm<Db: Database + DatabaseCommit>(
 }
 
 /// Create a new EVM with the given database and inspector.
+///
+/// See [`signet_evm`] for how precompiles are selected.
 pub fn signet_evm_with_inspector<Db, I>(
     db: Db,
     outer: I,
@@ -78,3 +88,25 @@ where
         .with_precompiles(signet_precompiles())
         .build_trevm()
 }
+
+/// Create a new EVM for the host chain with the given database and
+/// inspector.
+///
+/// This is the same as [`signet_evm_with_inspector`], except that the
+/// [`OrderDetector`] is created with [`OrderDetector::for_host`], so that it
+/// detects fills on the host chain, and that the EVM uses the Ethereum
+/// precompile set of its spec. The Signet precompiles are not active on the
+/// host chain.
+pub fn signet_host_evm_with_inspector<Db, I>(
+    db: Db,
+    outer: I,
+    constants: SignetSystemConstants,
+) -> EvmNeedsCfg<Db, I>
+where
+    I: Inspector<Ctx<Db>>,
+    Db: Database + DatabaseCommit,
+{
+    let inspector = SignetLayered::new(outer, OrderDetector::for_host(constants));
+
+    TrevmBuilder::new().with_db(db).with_insp(inspector).build_trevm()
+}
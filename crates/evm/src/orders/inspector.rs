@@ -92,6 +92,11 @@ impl OrderDetector {
         self.chain_id
     }
 
+    /// Returns `true` if the detector only detects fills.
+    pub const fn fills_only(&self) -> bool {
+        self.fills_only
+    }
+
     /// Take the orders from the inspector, clearing it.
     pub fn take(&mut self) -> (FramedOrders, FramedFilleds) {
         (std::mem::take(&mut self.orders), std::mem::take(&mut self.filleds))
@@ -0,0 +1,418 @@
+use crate::{
+    driver::{ControlFlow, FillShim, SignetDriverError},
+    signet_precompiles, EvmNeedsBlock, EvmNeedsTx, OrderDetector, PrecompileRegistry, RunTxResult,
+    SignetDriver, SignetLayered,
+};
+use alloy::{
+    consensus::{transaction::SignerRecoverable, Transaction as _},
+    primitives::{address, map::HashSet, Address, B256, U256},
+};
+use signet_extract::Extractable;
+use signet_types::{primitives::TransactionSigned, AggregateFills, AggregateOrders};
+use std::num::NonZeroUsize;
+use tracing::{debug, debug_span, warn};
+use trevm::{
+    inspectors::Layered,
+    revm::{
+        bytecode::opcode,
+        context::{
+            result::{EVMError, ResultAndState},
+            BlockEnv, CfgEnv, ContextTr, Transaction as _,
+        },
+        database_interface::WrapDatabaseRef,
+        inspector::NoOpInspector,
+        interpreter::{interpreter::EthInterpreter, interpreter_types::Jumps, Interpreter},
+        primitives::hardfork::SpecId,
+        state::EvmState,
+        Database, DatabaseCommit, DatabaseRef, Inspector,
+    },
+    trevm_try, BlockDriver, TrevmBuilder,
+};
+
+/// Beneficiary used when speculatively executing transactions. Fees are
+/// credited to this address, and moved to the block beneficiary when the
+/// outcome is reused.
+// NB: the hex is: beneficiary
+const SPECULATIVE_BENEFICIARY: Address = address!("00000000000000000062656e6566696369617279");
+
+/// A [`BlockDriver`] that executes the transactions of a [`SignetDriver`]
+/// block in parallel.
+///
+/// Transactions are first executed optimistically, on several threads,
+/// against the state at the start of the block's transactions. They are then
+/// accepted in block order. A transaction's optimistic outcome is reused if
+/// none of the accounts or storage slots it accessed were written by an
+/// earlier transaction in the block. Otherwise, the transaction is re-run
+/// against the current state, as in sequential execution.
+///
+/// Transactions that read the block beneficiary, e.g. via the `COINBASE`
+/// opcode, are always re-run.
+///
+/// The result is identical to driving the [`SignetDriver`] directly. Created
+/// via [`SignetDriver::parallel`].
+#[derive(Debug)]
+pub struct ParallelSignetDriver<'d, 'a, 'b, C: Extractable> {
+    driver: &'d mut SignetDriver<'a, 'b, C>,
+    threads: NonZeroUsize,
+}
+
+impl<'a, 'b, C: Extractable> SignetDriver<'a, 'b, C> {
+    /// Drive the block with its transactions executed in parallel, on up to
+    /// `threads` threads. See [`ParallelSignetDriver`] for details.
+    ///
+    /// Parallel execution requires a database that can be read concurrently,
+    /// and does not support outer inspectors, as optimistic outcomes are
+    /// reused without re-running the transaction.
+    pub const fn parallel(&mut self, threads: NonZeroUsize) -> ParallelSignetDriver<'_, 'a, 'b, C> {
+        ParallelSignetDriver { driver: self, threads }
+    }
+}
+
+impl<'a, 'b, C, Db> BlockDriver<Db, SignetLayered<NoOpInspector>>
+    for ParallelSignetDriver<'_, 'a, 'b, C>
+where
+    C: Extractable,
+    Db: Database + DatabaseCommit + DatabaseRef + Sync,
+{
+    type Block = SignetDriver<'a, 'b, C>;
+
+    type Error = SignetDriverError<Db>;
+
+    fn block(&self) -> &Self::Block {
+        self.driver
+    }
+
+    fn run_txns(&mut self, trevm: EvmNeedsTx<Db>) -> RunTxResult<Self, Db> {
+        let threads = self.threads;
+        self.driver.run_block(trevm, |driver, trevm| {
+            driver.execute_all_transactions_parallel(trevm, threads)
+        })
+    }
+
+    fn post_block(&mut self, _trevm: &EvmNeedsBlock<Db>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<C: Extractable> SignetDriver<'_, '_, C> {
+    /// Execute all transactions, optimistically in parallel. The result is
+    /// identical to [`Self::execute_all_transactions`].
+    fn execute_all_transactions_parallel<Db>(
+        &mut self,
+        mut trevm: EvmNeedsTx<Db>,
+        threads: NonZeroUsize,
+    ) -> RunTxResult<Self, Db>
+    where
+        Db: Database + DatabaseCommit + DatabaseRef + Sync,
+    {
+        // The speculative beneficiary must not exist, as its balance is used
+        // to determine the fees paid.
+        let db = trevm.inner().ctx.db_ref();
+        if !matches!(db.basic_ref(SPECULATIVE_BENEFICIARY), Ok(None)) {
+            warn!("Speculative beneficiary exists, executing transactions sequentially");
+            return self.execute_all_transactions(trevm);
+        }
+
+        let txs: Vec<_> = self
+            .to_process
+            .drain(..)
+            .filter_map(|tx| {
+                if tx.is_eip4844() {
+                    warn!("EIP-4844 transactions are not allowed in Signet blocks");
+                    return None;
+                }
+                match tx.recover_signer() {
+                    Ok(sender) => Some((tx, sender)),
+                    Err(_) => {
+                        warn!(tx_hash = %tx.hash(), "Failed to recover signer for transaction");
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let _span = debug_span!(
+            "SignetDriver::execute_all_transactions_parallel",
+            txn_count = txs.len(),
+            threads = threads.get()
+        )
+        .entered();
+
+        let env = SpeculationEnv::new(&trevm, &self.precompiles);
+        let chunk_size = txs.len().div_ceil(threads.get()).max(1);
+        let speculations: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = txs
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(|| env.speculate(db, chunk)))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("speculation thread panicked"))
+                .collect()
+        });
+
+        let beneficiary = trevm.block().beneficiary;
+        let mut writes = WriteSet::default();
+        let mut reused = 0usize;
+
+        for ((tx, sender), speculation) in txs.into_iter().zip(speculations) {
+            let _span = debug_span!("signet::evm::execute_transaction", tx_hash = %tx.hash(), %sender, nonce = tx.nonce())
+                .entered();
+
+            match speculation {
+                Some(Speculation { result, fills, orders, tip })
+                    if !writes.conflicts(&result.state) =>
+                {
+                    // Mirror `check_fills_and_accept`.
+                    if let Err(err) =
+                        self.working_context.checked_remove_ru_tx_events(&fills, &orders)
+                    {
+                        debug!(%err, "Discarding transaction outcome due to market error");
+                        continue;
+                    }
+                    self.payable_gas_used += result.result.gas_used();
+                    reused += 1;
+
+                    writes.record(&result.state);
+                    writes.accounts.insert(beneficiary);
+
+                    // Accounts were loaded via `DatabaseRef`. Some databases,
+                    // e.g. `State`, require committed accounts to have been
+                    // loaded via `Database`.
+                    for (address, _) in result.state.iter().filter(|(_, acct)| acct.is_touched()) {
+                        trevm_try!(
+                            trevm.try_read_account(*address).map_err(EVMError::Database),
+                            trevm
+                        );
+                    }
+                    trevm.commit_unchecked(result.state);
+                    trevm_try!(
+                        trevm
+                            .try_increase_balance_unchecked(beneficiary, tip)
+                            .map_err(EVMError::Database),
+                        trevm
+                    );
+
+                    // Fill the tx env, as the receipt type is derived from it.
+                    trevm = trevm.fill_tx(&FillShim(&tx, sender)).clear_tx();
+                    self.push_accepted(&trevm, result.result, tx);
+                }
+                _ => {
+                    let t = match run_tx!(self, trevm, &FillShim(&tx, sender), sender) {
+                        ControlFlow::Discard(t) => {
+                            trevm = t;
+                            continue;
+                        }
+                        ControlFlow::Keep(t) => t,
+                    };
+                    writes.record(&t.result_and_state().state);
+                    trevm = self.check_fills_and_accept(t, tx)?;
+                }
+            }
+        }
+
+        debug!(reused, "Parallel execution completed");
+        Ok(trevm)
+    }
+}
+
+/// The environment for speculative execution. This is the environment of the
+/// block's transactions, with the beneficiary replaced by
+/// [`SPECULATIVE_BENEFICIARY`].
+struct SpeculationEnv<'a> {
+    cfg: CfgEnv,
+    block: BlockEnv,
+    spec: SpecId,
+    detector: OrderDetector,
+    precompiles: &'a PrecompileRegistry,
+}
+
+impl<'a> SpeculationEnv<'a> {
+    fn new<Db>(trevm: &EvmNeedsTx<Db>, precompiles: &'a PrecompileRegistry) -> Self
+    where
+        Db: Database + DatabaseCommit,
+    {
+        let mut detector = trevm.inspector().inner().clone();
+        detector.take_aggregates();
+        Self {
+            cfg: trevm.cfg().clone(),
+            block: BlockEnv { beneficiary: SPECULATIVE_BENEFICIARY, ..trevm.block().clone() },
+            spec: trevm.spec_id(),
+            detector,
+            precompiles,
+        }
+    }
+
+    /// Speculatively execute a series of transactions, each against the
+    /// state in `db`.
+    fn speculate<Db>(
+        &self,
+        db: &Db,
+        txs: &[(TransactionSigned, Address)],
+    ) -> Vec<Option<Speculation>>
+    where
+        Db: DatabaseRef,
+    {
+        let mut trevm = TrevmBuilder::new()
+            .with_db(WrapDatabaseRef(db))
+            .with_insp(Layered::new(BeneficiaryDetector::default(), self.detector.clone()))
+            .with_precompiles(signet_precompiles())
+            .build_trevm()
+            .fill_cfg(&self.cfg)
+            .fill_block(&self.block);
+        trevm.set_spec_id(self.spec);
+        self.precompiles.apply(&mut trevm);
+
+        let basefee = self.block.basefee as u128;
+        let london = self.spec.is_enabled_in(SpecId::LONDON);
+
+        let mut speculations = Vec::with_capacity(txs.len());
+        for (tx, sender) in txs {
+            let ready = trevm.fill_tx(&FillShim(tx, *sender));
+            let gas_price = ready.tx().effective_gas_price(basefee);
+            let tip_per_gas = if london { gas_price.saturating_sub(basefee) } else { gas_price };
+
+            trevm = match ready.run() {
+                Ok(mut t) => {
+                    let inspector = &mut t.inner_mut_unchecked().inspector;
+                    let observed = std::mem::take(&mut inspector.outer_mut().observed);
+                    let (fills, orders) = inspector.inner_mut().take_aggregates();
+                    let (result, t) = t.take_result_and_state();
+                    speculations.push(Speculation::new(
+                        result,
+                        fills,
+                        orders,
+                        tip_per_gas,
+                        observed,
+                    ));
+                    t
+                }
+                Err(e) => {
+                    let mut t = e.discard_error();
+                    // Accounts loaded by the failed transaction remain in the
+                    // journal. Drop them, so they are not attributed to the
+                    // next transaction.
+                    t.inner_mut_unchecked().ctx.journal_mut().finalize();
+                    let inspector = t.inspector_mut();
+                    inspector.outer_mut().observed = false;
+                    inspector.inner_mut().take_aggregates();
+                    speculations.push(None);
+                    t
+                }
+            };
+        }
+        speculations
+    }
+}
+
+/// The outcome of speculatively executing a transaction. It may be reused if
+/// it does not conflict with earlier transactions.
+#[derive(Debug)]
+struct Speculation {
+    /// The result, without the fees paid to the beneficiary.
+    result: ResultAndState,
+    /// The fills detected.
+    fills: AggregateFills,
+    /// The orders detected.
+    orders: AggregateOrders,
+    /// The fees paid to the beneficiary.
+    tip: U256,
+}
+
+impl Speculation {
+    /// Check a speculative outcome, and move the fees paid from the
+    /// speculative beneficiary. Returns `None` if the transaction must be
+    /// re-run.
+    fn new(
+        mut result: ResultAndState,
+        fills: AggregateFills,
+        orders: AggregateOrders,
+        tip_per_gas: u128,
+        observed_beneficiary: bool,
+    ) -> Option<Self> {
+        if observed_beneficiary {
+            return None;
+        }
+
+        // The fees are the only change to the speculative beneficiary. If
+        // anything else touched it, e.g. a value transfer, the outcome may
+        // differ from sequential execution.
+        let acct = result.state.remove(&SPECULATIVE_BENEFICIARY)?;
+        let tip = acct.info.balance;
+        let expected = U256::from(tip_per_gas) * U256::from(result.result.gas_used());
+        if tip != expected || acct.info.nonce != 0 || !acct.info.is_empty_code_hash() {
+            return None;
+        }
+
+        Some(Self { result, fills, orders, tip })
+    }
+}
+
+/// Accounts and storage slots written by accepted transactions.
+#[derive(Debug, Default)]
+struct WriteSet {
+    /// Accounts whose balance, nonce or code were written, or which were
+    /// created or destroyed.
+    accounts: HashSet<Address>,
+    /// Storage slots written.
+    slots: HashSet<(Address, U256)>,
+}
+
+impl WriteSet {
+    /// Record the writes of a transaction.
+    fn record(&mut self, state: &EvmState) {
+        for (address, acct) in state.iter().filter(|(_, acct)| acct.is_touched()) {
+            let original = &acct.original_info;
+            if acct.info.balance != original.balance
+                || acct.info.nonce != original.nonce
+                || acct.info.code_hash != original.code_hash
+                || acct.is_created()
+                || acct.is_selfdestructed()
+            {
+                self.accounts.insert(*address);
+            }
+            self.slots.extend(acct.changed_storage_slots().map(|(slot, _)| (*address, *slot)));
+        }
+    }
+
+    /// Check if a transaction accessed anything written.
+    fn conflicts(&self, state: &EvmState) -> bool {
+        state.iter().any(|(address, acct)| {
+            self.accounts.contains(address)
+                || acct.storage.keys().any(|slot| self.slots.contains(&(*address, *slot)))
+        })
+    }
+}
+
+/// Inspector detecting transactions that observe the block beneficiary, by
+/// the `COINBASE` opcode or by accessing the [`SPECULATIVE_BENEFICIARY`]
+/// account. The outcome of these transactions depends on the beneficiary, so
+/// they must be re-run.
+#[derive(Debug, Default, Clone, Copy)]
+struct BeneficiaryDetector {
+    observed: bool,
+}
+
+impl<Ctx> Inspector<Ctx, EthInterpreter> for BeneficiaryDetector {
+    fn step(&mut self, interp: &mut Interpreter<EthInterpreter>, _context: &mut Ctx) {
+        let is_beneficiary = |idx: usize| {
+            interp
+                .stack
+                .peek(idx)
+                .is_ok_and(|word| Address::from_word(B256::from(word)) == SPECULATIVE_BENEFICIARY)
+        };
+
+        self.observed |= match interp.bytecode.opcode() {
+            opcode::COINBASE => true,
+            opcode::BALANCE
+            | opcode::EXTCODESIZE
+            | opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::SELFDESTRUCT => is_beneficiary(0),
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                is_beneficiary(1)
+            }
+            _ => false,
+        };
+    }
+}
@@ -1,9 +1,33 @@
-use std::sync::OnceLock;
-use trevm::revm::precompile::{secp256r1, Precompiles};
+use alloy::primitives::{map::HashMap, Address};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use trevm::{
+    helpers::Ctx,
+    revm::{
+        handler::EthPrecompiles,
+        precompile::{secp256r1, Precompile, PrecompileId, PrecompileSpecId, Precompiles},
+        primitives::hardfork::SpecId,
+        Database, Inspector,
+    },
+    EvmNeedsTx,
+};
 
 static PRECOMPILES: OnceLock<Precompiles> = OnceLock::new();
 
+/// Cache key for a precompile set. The base spec, and the id and address of
+/// each Signet precompile added to it.
+type CacheKey = (PrecompileSpecId, Vec<(PrecompileId, Address)>);
+
+/// Precompile sets built by [`PrecompileRegistry::precompiles`]. These are
+/// leaked, as the EVM requires a `'static` borrow. There is one entry per
+/// distinct combination of spec and active Signet precompiles, so the number
+/// of entries is small and bounded.
+static CACHE: LazyLock<Mutex<HashMap<CacheKey, &'static Precompiles>>> =
+    LazyLock::new(Default::default);
+
 /// Create a set of precompiles for the Signet EVM.
+///
+/// This is the Prague precompile set, with [`secp256r1::P256VERIFY`]. Prefer
+/// [`PrecompileRegistry::precompiles`], which follows the active hardfork.
 pub fn signet_precompiles() -> &'static Precompiles {
     PRECOMPILES.get_or_init(|| {
         let mut precompiles = Precompiles::prague().clone();
@@ -13,3 +37,155 @@ pub fn signet_precompiles() -> &'static Precompiles {
         precompiles
     })
 }
+
+/// A registry of Signet-specific precompiles, each activated at a rollup
+/// height.
+///
+/// The precompile set for a block is the Ethereum precompile set for the
+/// block's [`SpecId`], extended with the Signet precompiles active at the
+/// block's height. Signet precompiles never replace an Ethereum precompile at
+/// the same address, so when a hardfork enshrines a precompile (as Osaka does
+/// with P256VERIFY in [EIP-7951]), the hardfork's version is used.
+///
+/// The default registry contains [`secp256r1::P256VERIFY`], active from
+/// genesis.
+///
+/// [EIP-7951]: https://eips.ethereum.org/EIPS/eip-7951
+#[derive(Debug, Clone)]
+pub struct PrecompileRegistry {
+    /// Precompiles, with their activation heights, in ascending order of
+    /// activation height.
+    precompiles: Vec<(u64, Precompile)>,
+}
+
+impl Default for PrecompileRegistry {
+    fn default() -> Self {
+        Self::new().with_precompile(0, secp256r1::P256VERIFY)
+    }
+}
+
+impl PrecompileRegistry {
+    /// Create a new registry, containing no Signet precompiles.
+    pub const fn new() -> Self {
+        Self { precompiles: Vec::new() }
+    }
+
+    /// Register a precompile, active from the given rollup height.
+    ///
+    /// Precompiles are identified by their [`PrecompileId`] and address.
+    /// Custom precompiles must have a unique [`PrecompileId`].
+    pub fn with_precompile(mut self, activation_height: u64, precompile: Precompile) -> Self {
+        let idx = self.precompiles.partition_point(|(h, _)| *h <= activation_height);
+        self.precompiles.insert(idx, (activation_height, precompile));
+        self
+    }
+
+    /// Iterate over the registered precompiles and their activation heights.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &Precompile)> + '_ {
+        self.precompiles.iter().map(|(h, p)| (*h, p))
+    }
+
+    /// Iterate over the Signet precompiles active at the given rollup height.
+    pub fn active_at(&self, rollup_height: u64) -> impl Iterator<Item = &Precompile> + '_ {
+        self.precompiles.iter().take_while(move |(h, _)| *h <= rollup_height).map(|(_, p)| p)
+    }
+
+    /// Get the precompile set for the given spec and rollup height.
+    pub fn precompiles(&self, spec: SpecId, rollup_height: u64) -> &'static Precompiles {
+        let spec = PrecompileSpecId::from_spec_id(spec);
+        let base = Precompiles::new(spec);
+
+        let extra = self
+            .active_at(rollup_height)
+            .filter(|p| !base.contains(p.address()))
+            .collect::<Vec<_>>();
+        if extra.is_empty() {
+            return base;
+        }
+
+        let key = (spec, extra.iter().map(|p| (p.id().clone(), *p.address())).collect());
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        cache.entry(key).or_insert_with(|| {
+            let mut precompiles = base.clone();
+            precompiles.extend(extra.into_iter().cloned());
+            Box::leak(Box::new(precompiles))
+        })
+    }
+
+    /// Get the precompile provider for the given spec and rollup height.
+    pub fn eth_precompiles(&self, spec: SpecId, rollup_height: u64) -> EthPrecompiles {
+        EthPrecompiles { precompiles: self.precompiles(spec, rollup_height), spec }
+    }
+
+    /// Install the precompile set for the EVM's current spec and block
+    /// number.
+    ///
+    /// This must be called after the cfg and block have been filled. The
+    /// EVM replaces its precompiles with the plain Ethereum set whenever its
+    /// spec changes, so this must be called again if the spec is changed.
+    pub fn apply<Db, Insp>(&self, trevm: &mut EvmNeedsTx<Db, Insp>)
+    where
+        Db: Database,
+        Insp: Inspector<Ctx<Db>>,
+    {
+        let spec = trevm.spec_id();
+        let rollup_height = trevm.block_number().saturating_to();
+        trevm.override_precompiles(self.eth_precompiles(spec, rollup_height));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use trevm::revm::precompile::{u64_to_address, PrecompileOutput, PrecompileResult};
+
+    fn noop(_input: &[u8], _gas_limit: u64) -> PrecompileResult {
+        Ok(PrecompileOutput::new(0, Default::default()))
+    }
+
+    const NOOP: Precompile = Precompile::new(
+        PrecompileId::Custom(std::borrow::Cow::Borrowed("noop")),
+        u64_to_address(0x5150),
+        noop,
+    );
+
+    #[test]
+    fn follows_spec() {
+        let registry = PrecompileRegistry::default();
+        let p256 = u64_to_address(secp256r1::P256VERIFY_ADDRESS);
+
+        // Prague with P256VERIFY is the legacy set
+        let prague = registry.precompiles(SpecId::PRAGUE, 0);
+        assert_eq!(prague.len(), signet_precompiles().len());
+        assert!(prague.contains(&p256));
+        assert!(std::ptr::eq(prague, registry.precompiles(SpecId::PRAGUE, 100)));
+
+        // Osaka enshrines P256VERIFY, so the Osaka set is used as-is
+        let osaka = registry.precompiles(SpecId::OSAKA, 0);
+        assert!(std::ptr::eq(osaka, Precompiles::osaka()));
+        assert!(std::ptr::eq(registry.precompiles(SpecId::AMSTERDAM, 0), Precompiles::osaka()));
+
+        // An empty registry is the plain Ethereum set
+        let empty = PrecompileRegistry::new();
+        assert!(std::ptr::eq(empty.precompiles(SpecId::PRAGUE, 0), Precompiles::prague()));
+    }
+
+    #[test]
+    fn activates_at_height() {
+        let registry = PrecompileRegistry::default().with_precompile(10, NOOP);
+
+        let before = registry.precompiles(SpecId::OSAKA, 9);
+        assert!(!before.contains(NOOP.address()));
+
+        let after = registry.precompiles(SpecId::OSAKA, 10);
+        assert!(after.contains(NOOP.address()));
+        assert_eq!(after.len(), Precompiles::osaka().len() + 1);
+        assert!(std::ptr::eq(after, registry.precompiles(SpecId::OSAKA, 11)));
+
+        let prague = registry.precompiles(SpecId::PRAGUE, 10);
+        assert_eq!(prague.len(), Precompiles::prague().len() + 2);
+
+        assert_eq!(registry.active_at(0).count(), 1);
+        assert_eq!(registry.active_at(10).count(), 2);
+    }
+}
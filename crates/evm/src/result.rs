@@ -1,6 +1,12 @@
 use crate::ExecutionOutcome;
-use alloy::{consensus::Header, primitives::B256};
-use signet_journal::{HostJournal, JournalMeta};
+use alloy::{
+    consensus::{
+        proofs::{calculate_receipt_root, calculate_transaction_root},
+        Header, ReceiptEnvelope,
+    },
+    primitives::B256,
+};
+use signet_journal::{HostJournal, HostJournalV2, JournalCompression, JournalMeta};
 use signet_types::primitives::RecoveredBlock;
 use std::borrow::Cow;
 use trevm::journal::BundleStateIndex;
@@ -48,6 +54,11 @@ impl BlockResult {
         &self.execution_outcome
     }
 
+    /// Get the receipts of the rollup block.
+    pub fn receipts(&self) -> &[ReceiptEnvelope] {
+        self.execution_outcome.receipts_by_block(self.header().number)
+    }
+
     /// Calculate the [`BundleStateIndex`], making a sorted index of the
     /// contents of [`BundleState`] in the [`ExecutionOutcome`].
     ///
@@ -64,4 +75,27 @@ impl BlockResult {
     pub fn make_host_journal(&self, prev_journal_hash: B256) -> HostJournal<'_> {
         HostJournal::new(self.journal_meta(prev_journal_hash), self.index_bundle_state())
     }
+
+    /// Create a [`HostJournalV2`] by indexing the bundle state, and
+    /// including the block receipts.
+    ///
+    /// The journal header has its transactions and receipts roots computed
+    /// from the block, as in a `SignetHeaderV2`. The state diff will be
+    /// encoded with the given `compression`.
+    pub fn make_host_journal_v2(
+        &self,
+        prev_journal_hash: B256,
+        compression: JournalCompression,
+    ) -> HostJournalV2<'_> {
+        let receipts = self.receipts();
+        let header = Header {
+            transactions_root: calculate_transaction_root(&self.sealed_block.transactions),
+            receipts_root: calculate_receipt_root(receipts),
+            ..self.header().clone()
+        };
+        let meta = JournalMeta::new(self.host_height, prev_journal_hash, Cow::Owned(header));
+
+        HostJournalV2::new(meta, Cow::Borrowed(receipts), self.index_bundle_state())
+            .with_compression(compression)
+    }
 }
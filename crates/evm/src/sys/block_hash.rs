@@ -0,0 +1,87 @@
+use crate::sys::{HostBlockHashSysLog, StorageWrite, SystemHook, SystemHookContext, SystemOutput};
+use alloy::primitives::{address, bytes, Address, Bytes, B256, U256};
+use trevm::revm::state::Bytecode;
+
+/// Address of the rollup contract storing host block hashes. Installed by the
+/// [`HostBlockHashes`] hook.
+// NB: the hex is: hostblockhash
+pub const HOST_BLOCK_HASHES_ADDRESS: Address = address!("00000000000000686f7374626c6f636b68617368");
+
+/// Number of host block hashes stored by the [`HostBlockHashes`] hook. This
+/// is the `HISTORY_SERVE_WINDOW` of [EIP-2935].
+///
+/// [EIP-2935]: https://eips.ethereum.org/EIPS/eip-2935
+pub const HOST_BLOCK_HASH_WINDOW: u64 = 8191;
+
+/// Runtime code of the host block hash contract.
+///
+/// Called with a 32-byte host block number, it returns the 32-byte hash of
+/// that block. It reverts if the calldata is not 32 bytes, or if the hash is
+/// not in storage, e.g. because it is older than [`HOST_BLOCK_HASH_WINDOW`]
+/// blocks.
+pub const HOST_BLOCK_HASHES_CODE: Bytes = bytes!(
+    "60203614600a575f5ffd5b611fff5f350680611fff01545f35146020575f5ffd5b54806029575f5ffd5b5f5260205ff3"
+);
+
+/// [`SystemHook`] storing the hash of each host block in a rollup contract,
+/// in the style of [EIP-2935].
+///
+/// Before each rollup block, the hook writes the hash of the host block's
+/// parent to [`HOST_BLOCK_HASHES_ADDRESS`], deploying
+/// [`HOST_BLOCK_HASHES_CODE`] if necessary. Hashes are kept in a ring of
+/// [`HOST_BLOCK_HASH_WINDOW`] slots:
+/// - slot `n % HOST_BLOCK_HASH_WINDOW` holds the hash of host block `n`.
+/// - slot `HOST_BLOCK_HASH_WINDOW + n % HOST_BLOCK_HASH_WINDOW` holds `n`.
+///
+/// Each write is a [`SysAction`] whose receipt contains a
+/// [`HostBlockHashSysLog`].
+///
+/// [EIP-2935]: https://eips.ethereum.org/EIPS/eip-2935
+/// [`SysAction`]: crate::sys::SysAction
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HostBlockHashes;
+
+impl HostBlockHashes {
+    /// Get the slot holding the hash of the given host block.
+    pub const fn hash_slot(host_block_number: u64) -> U256 {
+        U256::from_limbs([host_block_number % HOST_BLOCK_HASH_WINDOW, 0, 0, 0])
+    }
+
+    /// Get the slot holding the number of the host block whose hash is in
+    /// [`Self::hash_slot`].
+    pub const fn number_slot(host_block_number: u64) -> U256 {
+        U256::from_limbs([
+            HOST_BLOCK_HASH_WINDOW + host_block_number % HOST_BLOCK_HASH_WINDOW,
+            0,
+            0,
+            0,
+        ])
+    }
+
+    /// Create the [`StorageWrite`] recording the hash of a host block.
+    pub fn write(host_block_number: u64, host_block_hash: B256) -> StorageWrite {
+        let log = HostBlockHashSysLog {
+            hostBlockNumber: host_block_number,
+            hostBlockHash: host_block_hash,
+        };
+
+        StorageWrite::new(HOST_BLOCK_HASHES_ADDRESS, log.into())
+            .with_code(Bytecode::new_raw(HOST_BLOCK_HASHES_CODE))
+            .with_slot(Self::hash_slot(host_block_number), host_block_hash.into())
+            .with_slot(Self::number_slot(host_block_number), U256::from(host_block_number))
+    }
+}
+
+impl SystemHook for HostBlockHashes {
+    fn name(&self) -> &'static str {
+        "HostBlockHashes"
+    }
+
+    fn pre_block(&self, ctx: &SystemHookContext<'_>) -> Vec<SystemOutput> {
+        // The host genesis block has no parent.
+        if ctx.host_height == 0 {
+            return Vec::new();
+        }
+        vec![Self::write(ctx.host_height - 1, ctx.host_parent_hash).into()]
+    }
+}
@@ -0,0 +1,180 @@
+use crate::sys::{SysBase, SysTx, UnmeteredSysTx};
+use alloy::{
+    consensus::TxEip1559,
+    primitives::{Address, Bytes, Log, TxKind, U256},
+};
+use signet_types::{
+    constants::MINTER_ADDRESS,
+    primitives::{Transaction, TransactionSigned},
+    MagicSig,
+};
+use trevm::revm::context::{TransactTo, TransactionType, TxEnv};
+
+/// The default gas limit for a [`SystemCall`].
+const DEFAULT_SYSTEM_CALL_GAS: u64 = 30_000_000;
+
+/// System transaction calling a rollup contract from the [`MINTER_ADDRESS`].
+#[derive(Debug, Clone)]
+pub struct SystemCall {
+    /// The contract being called.
+    to: Address,
+    /// The calldata.
+    input: Bytes,
+    /// The system log for the call.
+    log: Log,
+    /// The gas limit for the call. Gas is not paid for.
+    gas_limit: u64,
+
+    /// The magic signature for the call.
+    magic_sig: MagicSig,
+    /// The nonce of the call transaction.
+    nonce: Option<u64>,
+    /// The rollup chain ID.
+    rollup_chain_id: u64,
+}
+
+impl trevm::Tx for SystemCall {
+    fn fill_tx_env(&self, tx_env: &mut TxEnv) {
+        let TxEnv {
+            tx_type,
+            caller,
+            gas_limit,
+            gas_price,
+            kind,
+            value,
+            data,
+            nonce,
+            chain_id,
+            access_list,
+            gas_priority_fee,
+            blob_hashes,
+            max_fee_per_blob_gas,
+            authorization_list,
+        } = tx_env;
+
+        *tx_type = TransactionType::Custom as u8;
+        *caller = MINTER_ADDRESS;
+        *gas_limit = self.gas_limit;
+        *gas_price = 0;
+        *kind = TransactTo::Call(self.to);
+        *value = U256::ZERO;
+        *data = self.input.clone();
+        *nonce = self.nonce.expect("must be set");
+        *chain_id = Some(self.rollup_chain_id);
+        *access_list = Default::default();
+        *gas_priority_fee = Some(0);
+        blob_hashes.clear();
+        *max_fee_per_blob_gas = 0;
+        authorization_list.clear();
+    }
+}
+
+impl SystemCall {
+    /// Create a new [`SystemCall`] to the given contract, with the given
+    /// calldata and system log. The log is appended to the call's receipt if
+    /// the call succeeds.
+    pub fn new(to: Address, input: Bytes, log: Log) -> Self {
+        Self {
+            to,
+            input,
+            log,
+            gas_limit: DEFAULT_SYSTEM_CALL_GAS,
+            magic_sig: MagicSig::system(Default::default(), 0),
+            nonce: None,
+            rollup_chain_id: 0,
+        }
+    }
+
+    /// Set the gas limit for the call. Defaults to 30,000,000.
+    pub const fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Get the contract being called.
+    pub const fn to(&self) -> Address {
+        self.to
+    }
+
+    /// Get the gas limit for the call.
+    pub const fn gas_limit(&self) -> u64 {
+        self.gas_limit
+    }
+
+    /// Set the magic signature and rollup chain ID. Called by the driver
+    /// before the call is run.
+    pub(crate) const fn prepare(&mut self, magic_sig: MagicSig, rollup_chain_id: u64) {
+        self.magic_sig = magic_sig;
+        self.rollup_chain_id = rollup_chain_id;
+    }
+
+    /// Convert the [`SystemCall`] into a [`TransactionSigned`].
+    fn make_transaction(&self) -> TransactionSigned {
+        TransactionSigned::new_unhashed(
+            Transaction::Eip1559(TxEip1559 {
+                chain_id: self.rollup_chain_id,
+                nonce: self.nonce.expect("must be set"),
+                gas_limit: self.gas_limit,
+                max_fee_per_gas: 0,
+                max_priority_fee_per_gas: 0,
+                to: self.to.into(),
+                value: U256::ZERO,
+                access_list: Default::default(),
+                input: self.input.clone(),
+            }),
+            self.magic_sig.into(),
+        )
+    }
+}
+
+impl SysBase for SystemCall {
+    fn name() -> &'static str {
+        "SystemCall"
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "System call to {} with {} bytes of input data: `0x{}{}`",
+            self.to,
+            self.input.len(),
+            self.input.chunks(4).next().map(alloy::hex::encode).unwrap_or_default(),
+            if self.input.len() > 4 { "..." } else { "" },
+        )
+    }
+
+    fn has_nonce(&self) -> bool {
+        self.nonce.is_some()
+    }
+
+    fn populate_nonce(&mut self, nonce: u64) {
+        self.nonce = Some(nonce);
+    }
+
+    fn produce_transaction(&self) -> TransactionSigned {
+        self.make_transaction()
+    }
+
+    fn produce_log(&self) -> Log {
+        self.log.clone()
+    }
+
+    fn evm_sender(&self) -> Address {
+        MINTER_ADDRESS
+    }
+}
+
+impl SysTx for SystemCall {
+    fn callee(&self) -> TxKind {
+        self.to.into()
+    }
+
+    fn input(&self) -> Bytes {
+        self.input.clone()
+    }
+
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+}
+
+impl UnmeteredSysTx for SystemCall {}
@@ -2,11 +2,17 @@
 
 use crate::{
     driver::ControlFlow,
-    sys::{MeteredSysTx, MintNative, MintToken, SysAction, SysBase, TransactSysTx, UnmeteredSysTx},
+    orders::SignetInspector,
+    sys::{
+        MeteredSysTx, MintNative, MintToken, SysAction, SysBase, SystemHookContext, SystemOutput,
+        TransactSysTx, UnmeteredSysTx,
+    },
     EvmNeedsTx, RunTxResult, SignetDriver,
 };
+use alloy::consensus::BlockHeader;
 use alloy::primitives::{map::HashSet, U256};
 use signet_extract::Extractable;
+use signet_types::MagicSig;
 #[cfg(doc)]
 use signet_zenith::Transactor;
 use signet_zenith::MINTER_ADDRESS;
@@ -502,4 +508,141 @@ impl<'a, 'b, C: Extractable> SignetDriver<'a, 'b, C> {
             trevm.try_with_cfg(&DisableNonceCheck, |trevm| self.run_mints_inner(trevm))
         })
     }
+
+    /// Get the [`SystemHookContext`] for the block.
+    fn system_hook_context(&self) -> SystemHookContext<'_> {
+        SystemHookContext {
+            ru_height: self.ru_height(),
+            ru_chain_id: self.constants.ru_chain_id(),
+            host_height: self.extracts.host_block.number(),
+            host_timestamp: self.extracts.host_block.timestamp(),
+            host_parent_hash: self.extracts.host_block.parent_hash(),
+            constants: &self.constants,
+        }
+    }
+
+    /// Apply the outputs of system hooks.
+    ///
+    /// [`SystemOutput::Storage`] outputs are applied as [`SysAction`]s, and
+    /// [`SystemOutput::Call`] outputs as [`UnmeteredSysTx`]s. Each output is
+    /// signed with a system [`MagicSig`], whose txid is the parent block hash
+    /// and whose event index is the index of the output in the block.
+    ///
+    /// This function expects that gas and nonce checks are already disabled
+    /// in the EVM, and will not re-enable them.
+    fn apply_system_outputs_inner<Db, Insp>(
+        &mut self,
+        mut trevm: EvmNeedsTx<Db, Insp>,
+        outputs: Vec<SystemOutput>,
+    ) -> RunTxResult<Self, Db, Insp>
+    where
+        Db: Database + DatabaseCommit,
+        Insp: Inspector<Ctx<Db>>,
+    {
+        let ru_chain_id = self.constants.ru_chain_id();
+        let txid = self.parent().hash();
+
+        for output in outputs {
+            let magic_sig = MagicSig::system(txid, self.system_outputs);
+            self.system_outputs += 1;
+
+            match output {
+                SystemOutput::Storage(mut write) => {
+                    write.prepare(magic_sig, ru_chain_id);
+                    trevm = self.apply_sys_action_single(trevm, write)?;
+                    // System actions do not run the EVM, so the nonce must be
+                    // updated manually.
+                    trevm_try!(
+                        trevm
+                            .try_increment_nonce_unchecked(MINTER_ADDRESS)
+                            .map_err(EVMError::Database),
+                        trevm
+                    );
+                }
+                SystemOutput::Call(mut call) => {
+                    call.prepare(magic_sig, ru_chain_id);
+                    trevm = self.apply_unmetered_sys_transaction_inner(trevm, call)?;
+                }
+            }
+        }
+        Ok(trevm)
+    }
+
+    /// Apply the outputs of system hooks, with gas and nonce checks disabled.
+    fn apply_system_outputs<Db, Insp>(
+        &mut self,
+        trevm: EvmNeedsTx<Db, Insp>,
+        outputs: Vec<SystemOutput>,
+    ) -> RunTxResult<Self, Db, Insp>
+    where
+        Db: Database + DatabaseCommit,
+        Insp: Inspector<Ctx<Db>>,
+    {
+        if outputs.is_empty() {
+            return Ok(trevm);
+        }
+
+        let mut trevm = trevm.try_with_cfg(&DisableGasChecks, |trevm| {
+            trevm.try_with_cfg(&DisableNonceCheck, |trevm| {
+                self.apply_system_outputs_inner(trevm, outputs)
+            })
+        })?;
+
+        // System outputs cannot produce orders or fills. Discard any that
+        // were detected, so they are not attributed to the next transaction.
+        trevm.inner_mut_unchecked().inspector.as_mut_detector().take_aggregates();
+        Ok(trevm)
+    }
+
+    /// Run the [`SystemHook::pre_block`] logic of all registered hooks.
+    ///
+    /// [`SystemHook::pre_block`]: crate::sys::SystemHook::pre_block
+    pub(crate) fn run_pre_block_hooks<Db, Insp>(
+        &mut self,
+        trevm: EvmNeedsTx<Db, Insp>,
+    ) -> RunTxResult<Self, Db, Insp>
+    where
+        Db: Database + DatabaseCommit,
+        Insp: Inspector<Ctx<Db>>,
+    {
+        let _span =
+            debug_span!("SignetDriver::run_pre_block_hooks", hooks = self.hooks.len()).entered();
+        let ctx = self.system_hook_context();
+        let outputs = self
+            .hooks
+            .iter()
+            .flat_map(|hook| {
+                let outputs = hook.pre_block(&ctx);
+                debug!(hook = hook.name(), outputs = outputs.len(), "Ran pre-block hook");
+                outputs
+            })
+            .collect();
+        self.apply_system_outputs(trevm, outputs)
+    }
+
+    /// Run the [`SystemHook::post_block`] logic of all registered hooks.
+    ///
+    /// [`SystemHook::post_block`]: crate::sys::SystemHook::post_block
+    pub(crate) fn run_post_block_hooks<Db, Insp>(
+        &mut self,
+        trevm: EvmNeedsTx<Db, Insp>,
+    ) -> RunTxResult<Self, Db, Insp>
+    where
+        Db: Database + DatabaseCommit,
+        Insp: Inspector<Ctx<Db>>,
+    {
+        let _span =
+            debug_span!("SignetDriver::run_post_block_hooks", hooks = self.hooks.len()).entered();
+        let ctx = self.system_hook_context();
+        let outputs = self
+            .hooks
+            .iter()
+            .flat_map(|hook| {
+                let outputs = hook.post_block(&ctx);
+                debug!(hook = hook.name(), outputs = outputs.len(), "Ran post-block hook");
+                outputs
+            })
+            .collect();
+        self.apply_system_outputs(trevm, outputs)
+    }
 }
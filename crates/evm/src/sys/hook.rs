@@ -0,0 +1,78 @@
+use crate::sys::{StorageWrite, SystemCall};
+#[cfg(doc)]
+use crate::SignetDriver;
+use alloy::primitives::B256;
+use core::fmt;
+use signet_types::constants::SignetSystemConstants;
+
+/// Information about the block being built, provided to [`SystemHook`]s.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemHookContext<'a> {
+    /// The rollup block number.
+    pub ru_height: u64,
+    /// The rollup chain ID.
+    pub ru_chain_id: u64,
+    /// The host block number.
+    pub host_height: u64,
+    /// The host block timestamp. This is also the rollup block timestamp.
+    pub host_timestamp: u64,
+    /// The hash of the host block's parent.
+    pub host_parent_hash: B256,
+    /// The system constants.
+    pub constants: &'a SignetSystemConstants,
+}
+
+/// An output of a [`SystemHook`], applied to the rollup state by the
+/// [`SignetDriver`].
+#[derive(Debug, Clone)]
+pub enum SystemOutput {
+    /// Write storage directly, as a [`SysAction`].
+    ///
+    /// [`SysAction`]: crate::sys::SysAction
+    Storage(StorageWrite),
+    /// Call a contract, as an [`UnmeteredSysTx`].
+    ///
+    /// [`UnmeteredSysTx`]: crate::sys::UnmeteredSysTx
+    Call(SystemCall),
+}
+
+impl From<StorageWrite> for SystemOutput {
+    fn from(write: StorageWrite) -> Self {
+        Self::Storage(write)
+    }
+}
+
+impl From<SystemCall> for SystemOutput {
+    fn from(call: SystemCall) -> Self {
+        Self::Call(call)
+    }
+}
+
+/// A hook that runs system logic at the start or end of each rollup block.
+///
+/// Hooks are registered on the [`SignetDriver`] with
+/// [`SignetDriver::with_system_hook`], and run in registration order. Each
+/// hook produces [`SystemOutput`]s, which the driver applies as system
+/// actions or unmetered system transactions sent by the [`MINTER_ADDRESS`].
+/// Like mints, each output is added to the block as a transaction with a
+/// receipt containing the output's system log.
+///
+/// Pre-block hooks run before any transactions in the block. Post-block
+/// hooks run after transact events, before the rollup passage balance is
+/// cleared and the base fee is credited.
+///
+/// [`MINTER_ADDRESS`]: signet_types::constants::MINTER_ADDRESS
+pub trait SystemHook: fmt::Debug + Send + Sync {
+    /// Get the name of the hook. This is used for tracing.
+    fn name(&self) -> &'static str;
+
+    /// Produce the outputs to apply before the block's transactions.
+    fn pre_block(&self, _ctx: &SystemHookContext<'_>) -> Vec<SystemOutput> {
+        Vec::new()
+    }
+
+    /// Produce the outputs to apply after the block's transact events.
+    fn post_block(&self, _ctx: &SystemHookContext<'_>) -> Vec<SystemOutput> {
+        Vec::new()
+    }
+}
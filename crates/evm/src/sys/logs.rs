@@ -26,6 +26,11 @@ alloy::sol! {
         uint256 gas,
         uint256 maxFeePerGas,
     );
+
+    event HostBlockHash(
+        uint64 indexed hostBlockNumber,
+        bytes32 indexed hostBlockHash,
+    );
 }
 
 impl From<MintNative> for Log {
@@ -46,6 +51,12 @@ impl From<Transact> for Log {
     }
 }
 
+impl From<HostBlockHash> for Log {
+    fn from(value: HostBlockHash) -> Self {
+        Log { address: MINTER_ADDRESS, data: value.encode_log_data() }
+    }
+}
+
 impl<R> From<&ExtractedEvent<'_, R, Transactor::Transact>> for Transact {
     fn from(event: &ExtractedEvent<'_, R, Transactor::Transact>) -> Self {
         Transact {
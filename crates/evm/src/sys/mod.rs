@@ -1,13 +1,28 @@
+mod block_hash;
+pub use block_hash::{
+    HostBlockHashes, HOST_BLOCK_HASHES_ADDRESS, HOST_BLOCK_HASHES_CODE, HOST_BLOCK_HASH_WINDOW,
+};
+
+mod call;
+pub use call::SystemCall;
+
 mod driver;
 
+mod hook;
+pub use hook::{SystemHook, SystemHookContext, SystemOutput};
+
 mod logs;
 pub use logs::{
-    MintNative as MintNativeSysLog, MintToken as MintTokenSysLog, Transact as TransactSysLog,
+    HostBlockHash as HostBlockHashSysLog, MintNative as MintNativeSysLog,
+    MintToken as MintTokenSysLog, Transact as TransactSysLog,
 };
 
 mod native;
 pub use native::MintNative;
 
+mod storage;
+pub use storage::StorageWrite;
+
 mod token;
 pub use token::MintToken;
 
@@ -0,0 +1,168 @@
+use crate::sys::{SysAction, SysBase};
+use alloy::{
+    consensus::{ReceiptEnvelope, TxEip1559},
+    primitives::{Address, Log, U256},
+};
+use signet_types::{
+    constants::MINTER_ADDRESS,
+    primitives::{Transaction, TransactionSigned},
+    MagicSig,
+};
+use trevm::{
+    helpers::Ctx,
+    revm::{context::result::EVMError, state::Bytecode, Database, DatabaseCommit, Inspector},
+    Trevm, MIN_TRANSACTION_GAS,
+};
+
+/// System action writing storage slots of a rollup account directly, without
+/// running the EVM.
+///
+/// If code is set, it is deployed to the account before the slots are
+/// written, unless the account already has code.
+#[derive(Debug, Clone)]
+pub struct StorageWrite {
+    /// The account to write to.
+    address: Address,
+    /// The slots to write, and their new values.
+    slots: Vec<(U256, U256)>,
+    /// Code to deploy to the account, if it has none.
+    code: Option<Bytecode>,
+    /// The system log for the write.
+    log: Log,
+
+    /// The magic signature for the write.
+    magic_sig: MagicSig,
+    /// The nonce of the write transaction.
+    nonce: Option<u64>,
+    /// The rollup chain ID.
+    rollup_chain_id: u64,
+}
+
+impl StorageWrite {
+    /// Create a new [`StorageWrite`] to the given account, with the given
+    /// system log. The log is included in the receipt for the write.
+    pub fn new(address: Address, log: Log) -> Self {
+        Self {
+            address,
+            slots: Vec::new(),
+            code: None,
+            log,
+            magic_sig: MagicSig::system(Default::default(), 0),
+            nonce: None,
+            rollup_chain_id: 0,
+        }
+    }
+
+    /// Add a slot to write.
+    pub fn with_slot(mut self, slot: U256, value: U256) -> Self {
+        self.slots.push((slot, value));
+        self
+    }
+
+    /// Set the code to deploy to the account, if it has none.
+    pub fn with_code(mut self, code: Bytecode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Get the account to write to.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Get the slots to write, and their new values.
+    pub fn slots(&self) -> &[(U256, U256)] {
+        &self.slots
+    }
+
+    /// Set the magic signature and rollup chain ID. Called by the driver
+    /// before the write is applied.
+    pub(crate) const fn prepare(&mut self, magic_sig: MagicSig, rollup_chain_id: u64) {
+        self.magic_sig = magic_sig;
+        self.rollup_chain_id = rollup_chain_id;
+    }
+
+    /// Convert the [`StorageWrite`] into a [`TransactionSigned`].
+    fn make_transaction(&self) -> TransactionSigned {
+        TransactionSigned::new_unhashed(
+            Transaction::Eip1559(TxEip1559 {
+                chain_id: self.rollup_chain_id,
+                nonce: self.nonce.expect("must be set"),
+                gas_limit: MIN_TRANSACTION_GAS,
+                max_fee_per_gas: 0,
+                max_priority_fee_per_gas: 0,
+                to: self.address.into(),
+                value: U256::ZERO,
+                access_list: Default::default(),
+                input: Default::default(),
+            }),
+            self.magic_sig.into(),
+        )
+    }
+}
+
+impl SysBase for StorageWrite {
+    fn name() -> &'static str {
+        "StorageWrite"
+    }
+
+    fn description(&self) -> String {
+        format!("Write {} storage slots to {}", self.slots.len(), self.address)
+    }
+
+    fn has_nonce(&self) -> bool {
+        self.nonce.is_some()
+    }
+
+    fn populate_nonce(&mut self, nonce: u64) {
+        self.nonce = Some(nonce)
+    }
+
+    fn produce_transaction(&self) -> TransactionSigned {
+        self.make_transaction()
+    }
+
+    fn produce_log(&self) -> Log {
+        self.log.clone()
+    }
+
+    fn evm_sender(&self) -> Address {
+        MINTER_ADDRESS
+    }
+}
+
+impl SysAction for StorageWrite {
+    fn apply<Db, Insp, State>(
+        &self,
+        evm: &mut Trevm<Db, Insp, State>,
+    ) -> Result<(), EVMError<Db::Error>>
+    where
+        Db: Database + DatabaseCommit,
+        Insp: Inspector<Ctx<Db>>,
+    {
+        if let Some(code) = &self.code {
+            let existing = evm.try_read_code(self.address).map_err(EVMError::Database)?;
+            if existing.is_none_or(|c| c.is_empty()) {
+                evm.try_set_bytecode_unchecked(self.address, code.clone())
+                    .map_err(EVMError::Database)?;
+            }
+        }
+
+        for (slot, value) in &self.slots {
+            evm.try_set_storage_unchecked(self.address, *slot, *value)
+                .map_err(EVMError::Database)?;
+        }
+        Ok(())
+    }
+
+    fn produce_receipt(&self, cumulative_gas_used: u64) -> ReceiptEnvelope {
+        ReceiptEnvelope::Eip1559(
+            alloy::consensus::Receipt {
+                status: true.into(),
+                cumulative_gas_used: cumulative_gas_used.saturating_add(MIN_TRANSACTION_GAS),
+                logs: vec![self.produce_log()],
+            }
+            .with_bloom(),
+        )
+    }
+}
@@ -85,7 +85,7 @@ impl<'a> HostJournal<'a> {
 
 impl JournalEncode for HostJournal<'_> {
     fn serialized_size(&self) -> usize {
-        8 + 32 + self.journal.serialized_size()
+        self.meta.serialized_size() + self.journal.serialized_size()
     }
 
     fn encode(&self, buf: &mut dyn alloy::rlp::BufMut) {
@@ -174,6 +174,8 @@ pub(crate) mod test {
 
         let buf = original.encoded();
 
+        assert_eq!(buf.len(), original.serialized_size());
+
         let decoded = HostJournal::decode(&mut &buf[..]).unwrap();
         assert_eq!(original, decoded);
     }
@@ -0,0 +1,405 @@
+use crate::JournalMeta;
+use alloy::{
+    consensus::{proofs::calculate_receipt_root, Header, ReceiptEnvelope},
+    eips::eip2718::{Decodable2718, Encodable2718},
+    primitives::{keccak256, Bytes, B256},
+    rlp::BufMut,
+};
+use std::{borrow::Cow, sync::OnceLock};
+use trevm::journal::{BundleStateIndex, JournalDecode, JournalDecodeError, JournalEncode};
+
+/// Compression level used for zstd-compressed state diffs.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Maximum size of a decompressed state diff, 64 MiB. Compressed state diffs
+/// declaring a larger uncompressed size are rejected without being
+/// decompressed.
+pub const MAX_DECOMPRESSED_JOURNAL: usize = 64 * 1024 * 1024;
+
+/// Compression applied to the state diff section of a [`HostJournalV2`] when
+/// it is encoded.
+///
+/// Compression does not affect the journal hash, which is always computed
+/// over the uncompressed encoding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum JournalCompression {
+    /// The state diff is not compressed.
+    #[default]
+    None = 0,
+    /// The state diff is compressed with zstd.
+    Zstd = 1,
+}
+
+impl JournalEncode for JournalCompression {
+    fn serialized_size(&self) -> usize {
+        1
+    }
+
+    fn encode(&self, buf: &mut dyn BufMut) {
+        (*self as u8).encode(buf);
+    }
+}
+
+impl JournalDecode for JournalCompression {
+    fn decode(buf: &mut &[u8]) -> Result<Self, JournalDecodeError> {
+        let tag: u8 = JournalDecode::decode(buf)?;
+        match tag {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Zstd),
+            _ => Err(JournalDecodeError::InvalidTag {
+                ty_name: "JournalCompression",
+                tag,
+                max_expected: 1,
+            }),
+        }
+    }
+}
+
+/// Version 2 journal associated with a host block.
+///
+/// In addition to the [`JournalMeta`] and state diff carried by a
+/// [`HostJournal`], this contains the receipts of the block, so that
+/// receipts and logs may be indexed without re-executing the block. The
+/// header is expected to carry the transactions and receipts roots, as in a
+/// `SignetHeaderV2`.
+///
+/// The state diff section may be compressed when encoded, see
+/// [`JournalCompression`].
+///
+/// [`HostJournal`]: crate::HostJournal
+#[derive(Debug, Clone)]
+pub struct HostJournalV2<'a> {
+    /// The metadata
+    meta: JournalMeta<'a>,
+
+    /// The receipts of the block.
+    receipts: Cow<'a, [ReceiptEnvelope]>,
+
+    /// The changes.
+    journal: BundleStateIndex<'a>,
+
+    /// The compression used when encoding the state diff.
+    compression: JournalCompression,
+
+    /// The uncompressed serialized journal
+    serialized: OnceLock<Bytes>,
+
+    /// The compressed state diff, if compression is enabled.
+    compressed: OnceLock<Bytes>,
+
+    /// The hash of the uncompressed serialized journal
+    hash: OnceLock<B256>,
+}
+
+impl PartialEq for HostJournalV2<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.meta == other.meta && self.receipts == other.receipts && self.journal == other.journal
+    }
+}
+
+impl Eq for HostJournalV2<'_> {}
+
+impl<'a> HostJournalV2<'a> {
+    /// Create a new journal.
+    pub const fn new(
+        meta: JournalMeta<'a>,
+        receipts: Cow<'a, [ReceiptEnvelope]>,
+        journal: BundleStateIndex<'a>,
+    ) -> Self {
+        Self {
+            meta,
+            receipts,
+            journal,
+            compression: JournalCompression::None,
+            serialized: OnceLock::new(),
+            compressed: OnceLock::new(),
+            hash: OnceLock::new(),
+        }
+    }
+
+    /// Set the compression used when encoding the state diff.
+    pub fn with_compression(mut self, compression: JournalCompression) -> Self {
+        self.compression = compression;
+        self.compressed = OnceLock::new();
+        self
+    }
+
+    /// Deconstruct the `HostJournalV2` into its parts.
+    pub fn into_parts(self) -> (JournalMeta<'a>, Cow<'a, [ReceiptEnvelope]>, BundleStateIndex<'a>) {
+        (self.meta, self.receipts, self.journal)
+    }
+
+    /// Get the journal meta.
+    pub const fn meta(&self) -> &JournalMeta<'a> {
+        &self.meta
+    }
+
+    /// Get the receipts.
+    pub fn receipts(&self) -> &[ReceiptEnvelope] {
+        &self.receipts
+    }
+
+    /// Get the journal.
+    pub const fn journal(&self) -> &BundleStateIndex<'a> {
+        &self.journal
+    }
+
+    /// Get the compression used when encoding the state diff.
+    pub const fn compression(&self) -> JournalCompression {
+        self.compression
+    }
+
+    /// Get the host height.
+    pub const fn host_height(&self) -> u64 {
+        self.meta.host_height()
+    }
+
+    /// Get the previous journal hash.
+    pub const fn prev_journal_hash(&self) -> B256 {
+        self.meta.prev_journal_hash()
+    }
+
+    /// Get the rollup block header.
+    pub fn header(&self) -> &Header {
+        self.meta.header()
+    }
+
+    /// Get the rollup height.
+    pub fn rollup_height(&self) -> u64 {
+        self.meta.rollup_height()
+    }
+
+    /// Check that the receipts match the receipts root in the header.
+    pub fn receipts_root_matches(&self) -> bool {
+        calculate_receipt_root(&self.receipts) == self.header().receipts_root
+    }
+
+    /// Serialize the journal, without compression.
+    pub fn serialized(&self) -> &Bytes {
+        self.serialized.get_or_init(|| {
+            let mut buf = Vec::with_capacity(self.uncompressed_size());
+            self.encode_prefix(&mut buf);
+            JournalCompression::None.encode(&mut buf);
+            self.journal.encode(&mut buf);
+            buf.into()
+        })
+    }
+
+    /// Serialize and hash the journal. The hash is computed over the
+    /// uncompressed serialization.
+    pub fn journal_hash(&self) -> B256 {
+        *self.hash.get_or_init(|| keccak256(self.serialized()))
+    }
+
+    /// Get the zstd-compressed state diff.
+    fn compressed(&self) -> &Bytes {
+        self.compressed.get_or_init(|| {
+            zstd::bulk::compress(&self.journal.encoded(), ZSTD_LEVEL)
+                .expect("compressing into a vec is infallible")
+                .into()
+        })
+    }
+
+    /// Size of the receipts section.
+    fn receipts_size(&self) -> usize {
+        4 + self.receipts.iter().map(|r| 4 + r.encode_2718_len()).sum::<usize>()
+    }
+
+    /// Size of the uncompressed serialization.
+    fn uncompressed_size(&self) -> usize {
+        self.meta.serialized_size() + self.receipts_size() + 1 + self.journal.serialized_size()
+    }
+
+    /// Encode the meta and receipts.
+    fn encode_prefix(&self, buf: &mut dyn BufMut) {
+        self.meta.encode(buf);
+        (self.receipts.len() as u32).encode(buf);
+        for receipt in self.receipts.iter() {
+            (receipt.encode_2718_len() as u32).encode(buf);
+            receipt.encode_2718(buf);
+        }
+    }
+}
+
+impl JournalEncode for HostJournalV2<'_> {
+    fn serialized_size(&self) -> usize {
+        match self.compression {
+            JournalCompression::None => self.uncompressed_size(),
+            JournalCompression::Zstd => {
+                self.meta.serialized_size()
+                    + self.receipts_size()
+                    + 1
+                    + 4
+                    + 4
+                    + self.compressed().len()
+            }
+        }
+    }
+
+    fn encode(&self, buf: &mut dyn BufMut) {
+        match self.compression {
+            JournalCompression::None => buf.put_slice(self.serialized()),
+            JournalCompression::Zstd => {
+                self.encode_prefix(buf);
+                JournalCompression::Zstd.encode(buf);
+                (self.journal.serialized_size() as u32).encode(buf);
+                (self.compressed().len() as u32).encode(buf);
+                buf.put_slice(self.compressed());
+            }
+        }
+    }
+}
+
+/// Split `len` bytes off the front of the buffer.
+const fn take<'b>(
+    buf: &mut &'b [u8],
+    ty_name: &'static str,
+    len: usize,
+) -> Result<&'b [u8], JournalDecodeError> {
+    if buf.len() < len {
+        return Err(JournalDecodeError::Overrun { ty_name, expected: len, remaining: buf.len() });
+    }
+    let (taken, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(taken)
+}
+
+impl JournalDecode for HostJournalV2<'static> {
+    fn decode(buf: &mut &[u8]) -> Result<Self, JournalDecodeError> {
+        let original = *buf;
+
+        let meta = JournalMeta::decode(buf)?;
+
+        let count: u32 = JournalDecode::decode(buf)?;
+        let mut receipts = Vec::with_capacity(count.min(1024) as usize);
+        for _ in 0..count {
+            let len: u32 = JournalDecode::decode(buf)?;
+            let mut encoded = take(buf, "ReceiptEnvelope", len as usize)?;
+            let receipt = ReceiptEnvelope::decode_2718(&mut encoded)
+                .map_err(|e| JournalDecodeError::Rlp(e.into()))?;
+            receipts.push(receipt);
+        }
+
+        let compression = JournalCompression::decode(buf)?;
+        let journal = match compression {
+            JournalCompression::None => JournalDecode::decode(buf)?,
+            JournalCompression::Zstd => {
+                let uncompressed_len: u32 = JournalDecode::decode(buf)?;
+                let compressed_len: u32 = JournalDecode::decode(buf)?;
+                // The length is untrusted, and determines the size of the
+                // decompression buffer.
+                if uncompressed_len as usize > MAX_DECOMPRESSED_JOURNAL {
+                    return Err(JournalDecodeError::Rlp(alloy::rlp::Error::Custom(
+                        "compressed state diff is too large",
+                    )));
+                }
+                let compressed = take(buf, "BundleStateIndex", compressed_len as usize)?;
+                let decompressed = zstd::bulk::decompress(compressed, uncompressed_len as usize)
+                    .map_err(|_| {
+                        JournalDecodeError::Rlp(alloy::rlp::Error::Custom(
+                            "invalid zstd-compressed state diff",
+                        ))
+                    })?;
+                JournalDecode::decode(&mut decompressed.as_slice())?
+            }
+        };
+
+        let mut journal =
+            Self::new(meta, Cow::Owned(receipts), journal).with_compression(compression);
+
+        // The uncompressed encoding is the canonical form, so the bytes read
+        // may be reused for it.
+        if compression == JournalCompression::None {
+            let bytes_read = original.len() - buf.len();
+            let original = &original[..bytes_read];
+            journal.serialized = OnceLock::from(Bytes::copy_from_slice(original));
+            journal.hash = OnceLock::from(keccak256(original));
+        }
+
+        Ok(journal)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::host::test::make_state_diff;
+    use alloy::{
+        consensus::{Receipt, ReceiptWithBloom},
+        primitives::{Address, Log, LogData},
+    };
+
+    pub(crate) fn make_receipts() -> Vec<ReceiptEnvelope> {
+        let log = Log {
+            address: Address::repeat_byte(0x11),
+            data: LogData::new_unchecked(vec![B256::repeat_byte(0x22)], Bytes::from_static(b"hi")),
+        };
+        vec![
+            ReceiptEnvelope::Legacy(ReceiptWithBloom::from(Receipt {
+                status: true.into(),
+                cumulative_gas_used: 21_000,
+                logs: vec![],
+            })),
+            ReceiptEnvelope::Eip1559(ReceiptWithBloom::from(Receipt {
+                status: false.into(),
+                cumulative_gas_used: 80_000,
+                logs: vec![log],
+            })),
+        ]
+    }
+
+    pub(crate) fn make_journal_v2(compression: JournalCompression) -> HostJournalV2<'static> {
+        let receipts = make_receipts();
+        let header = Header {
+            number: 7,
+            receipts_root: calculate_receipt_root(&receipts),
+            ..Default::default()
+        };
+        HostJournalV2::new(
+            JournalMeta::new(31, B256::repeat_byte(0xaa), Cow::Owned(header)),
+            Cow::Owned(receipts),
+            make_state_diff(),
+        )
+        .with_compression(compression)
+    }
+
+    #[test]
+    fn roundtrip() {
+        for compression in [JournalCompression::None, JournalCompression::Zstd] {
+            let original = make_journal_v2(compression);
+            assert!(original.receipts_root_matches());
+
+            let buf = original.encoded();
+            assert_eq!(buf.len(), original.serialized_size());
+
+            let decoded = HostJournalV2::decode(&mut &buf[..]).unwrap();
+            assert_eq!(original, decoded);
+            assert_eq!(decoded.compression(), compression);
+            assert_eq!(original.journal_hash(), decoded.journal_hash());
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_decompression() {
+        let journal = make_journal_v2(JournalCompression::Zstd);
+        let mut buf = journal.encoded().to_vec();
+
+        // Overwrite the declared uncompressed length, which follows the
+        // compression tag.
+        let offset = buf.len() - journal.compressed().len() - 9;
+        assert_eq!(buf[offset], JournalCompression::Zstd as u8);
+        buf[offset + 1..offset + 5].copy_from_slice(&u32::MAX.to_be_bytes());
+
+        let err = HostJournalV2::decode(&mut &buf[..]).unwrap_err();
+        assert!(matches!(err, JournalDecodeError::Rlp(alloy::rlp::Error::Custom(_))));
+    }
+
+    #[test]
+    fn hash_ignores_compression() {
+        let plain = make_journal_v2(JournalCompression::None);
+        let compressed = make_journal_v2(JournalCompression::Zstd);
+        assert_ne!(plain.encoded(), compressed.encoded());
+        assert_eq!(plain.journal_hash(), compressed.journal_hash());
+    }
+}
@@ -18,12 +18,28 @@
 mod host;
 pub use host::HostJournal;
 
+mod host_v2;
+pub use host_v2::{HostJournalV2, JournalCompression, MAX_DECOMPRESSED_JOURNAL};
+
 mod meta;
 pub use meta::JournalMeta;
 
+mod replay;
+pub use replay::{
+    apply_changes, revert_changes, JournalReplay, ReplayError, StateRootInputs, StorageRootInputs,
+};
+
 mod set;
 pub use set::JournalSet;
 
+mod store;
+pub use store::{JournalStore, JournalStoreError, DEFAULT_MAX_SEGMENT_SIZE};
+
+pub mod sync;
+
+mod tree;
+pub use tree::{ForkChoice, JournalTree, JournalTreeError, LatestHostHeight, ReorgEvent};
+
 mod versions;
 pub use versions::Journal;
 
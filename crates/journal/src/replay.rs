@@ -0,0 +1,485 @@
+use crate::{Journal, JournalStream, GENESIS_JOURNAL_HASH};
+use alloy::primitives::{Address, B256, U256};
+use futures_util::{pin_mut, StreamExt};
+use std::collections::BTreeMap;
+use trevm::{
+    journal::{AcctDiff, BundleStateIndex, InfoOutcome},
+    revm::{
+        primitives::HashMap,
+        state::{Account, AccountInfo, EvmStorageSlot},
+        DatabaseCommit,
+    },
+};
+
+/// Errors that can occur while replaying journals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ReplayError {
+    /// The journal does not build on the latest replayed journal.
+    #[error("wrong prev_hash: current {latest_hash}, journal expected {in_journal}")]
+    WrongPrevHash {
+        /// The hash of the latest replayed journal.
+        latest_hash: B256,
+        /// The previous hash declared by the journal.
+        in_journal: B256,
+    },
+
+    /// Attempted to undo a journal that is not the latest replayed journal.
+    #[error("cannot undo journal {journal_hash}, latest is {latest_hash}")]
+    NotLatest {
+        /// The hash of the latest replayed journal.
+        latest_hash: B256,
+        /// The hash of the journal to be undone.
+        journal_hash: B256,
+    },
+}
+
+/// Post-state of a single account's storage, as needed to compute a storage
+/// root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageRootInputs {
+    /// True if all storage of the account was cleared before the `slots` were
+    /// written, i.e. the account was created or destroyed.
+    pub wiped: bool,
+    /// Changed slots and their present values. Zero values indicate deleted
+    /// slots.
+    pub slots: BTreeMap<U256, U256>,
+}
+
+/// The accumulated account and storage post-state of all replayed journals.
+///
+/// This contains every account and storage slot changed since replay began,
+/// with its latest value. Combined with the pre-replay state trie, it is
+/// sufficient to compute the resulting state root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateRootInputs {
+    /// Changed accounts. `None` indicates the account no longer exists.
+    pub accounts: BTreeMap<Address, Option<AccountInfo>>,
+    /// Changed storage, by account.
+    pub storage: BTreeMap<Address, StorageRootInputs>,
+}
+
+impl StateRootInputs {
+    /// True if no changes have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.storage.is_empty()
+    }
+
+    /// Record the changes to be committed to the database.
+    fn record(&mut self, changes: &HashMap<Address, Account>) {
+        for (address, account) in changes {
+            let storage = self.storage.entry(*address).or_default();
+            if account.is_selfdestructed() {
+                self.accounts.insert(*address, None);
+                storage.wiped = true;
+                storage.slots.clear();
+                continue;
+            }
+            if account.is_created() {
+                storage.wiped = true;
+                storage.slots.clear();
+            }
+
+            let mut info = account.info.clone();
+            info.code = None;
+            self.accounts.insert(*address, Some(info));
+            storage.slots.extend(account.storage.iter().map(|(k, v)| (*k, v.present_value())));
+
+            if !storage.wiped && storage.slots.is_empty() {
+                self.storage.remove(address);
+            }
+        }
+    }
+}
+
+/// Convert a state diff into changes that apply it to a database.
+///
+/// New contract bytecode is attached to the accounts that use it, so that
+/// databases store the code on commit.
+pub fn apply_changes(index: &BundleStateIndex<'_>) -> HashMap<Address, Account> {
+    index
+        .state
+        .iter()
+        .map(|(address, diff)| {
+            let mut info = diff.updated().into_owned();
+            info.code =
+                index.new_contracts.get(&info.code_hash).map(|code| code.clone().into_owned());
+
+            let mut account = Account::from(info);
+            account.storage = diff
+                .storage_diff
+                .iter()
+                .map(|(slot, value)| {
+                    (
+                        *slot,
+                        EvmStorageSlot::new_changed(
+                            value.previous_or_original_value,
+                            value.present_value,
+                            0,
+                        ),
+                    )
+                })
+                .collect();
+
+            match diff.outcome {
+                InfoOutcome::Created(_) => account.mark_created(),
+                InfoOutcome::Destroyed(_) => account.mark_selfdestruct(),
+                InfoOutcome::Diff { .. } => {}
+            }
+            account.mark_touch();
+
+            (*address, account)
+        })
+        .collect()
+}
+
+/// Convert a state diff into changes that undo it, restoring the state
+/// before the diff was applied.
+///
+/// Accounts are restored to the `old` side of the [`InfoOutcome`], and
+/// storage slots to their `previous_or_original_value`. Accounts that were
+/// created are destroyed. Bytecode of new contracts is not removed, as
+/// [`DatabaseCommit`] provides no way to do so. Unreferenced code does not
+/// affect execution.
+///
+/// Undoing a destroyed account restores its info and the slots listed in
+/// its storage diff only. The journal does not record the storage wiped by
+/// the selfdestruct, so any other slots of the account read as zero
+/// afterwards.
+pub fn revert_changes(index: &BundleStateIndex<'_>) -> HashMap<Address, Account> {
+    index.state.iter().map(|(address, diff)| (*address, revert_account(diff))).collect()
+}
+
+fn revert_account(diff: &AcctDiff<'_>) -> Account {
+    let Some(original) = diff.original() else {
+        // The account was created, so reverting it means deleting it.
+        let mut account = Account::from(AccountInfo::default());
+        account.mark_selfdestruct();
+        account.mark_touch();
+        return account;
+    };
+
+    let mut account = Account::from(original.into_owned());
+    account.storage = diff
+        .storage_diff
+        .iter()
+        .map(|(slot, value)| {
+            (
+                *slot,
+                EvmStorageSlot::new_changed(
+                    value.present_value,
+                    value.previous_or_original_value,
+                    0,
+                ),
+            )
+        })
+        .collect();
+    account.mark_touch();
+    account
+}
+
+/// Applies [`Journal`]s to a database in order, rebuilding state without
+/// re-executing blocks.
+///
+/// The replayer tracks the running journal hash, starting from
+/// [`GENESIS_JOURNAL_HASH`] by default, and rejects journals whose
+/// `prev_journal_hash` does not match it. Journals may be undone in reverse
+/// order with [`JournalReplay::undo`], e.g. during a host chain reorg.
+///
+/// The replayer also accumulates [`StateRootInputs`] for every change it
+/// commits, so that the caller can compute the resulting state root.
+#[derive(Debug, Clone)]
+pub struct JournalReplay<Db> {
+    /// The database.
+    db: Db,
+
+    /// The hash of the latest replayed journal.
+    latest_hash: B256,
+
+    /// The rollup height of the latest replayed journal.
+    latest_height: Option<u64>,
+
+    /// Accumulated post-state.
+    state: StateRootInputs,
+}
+
+impl<Db> JournalReplay<Db> {
+    /// Create a new replayer, starting from the genesis journal hash.
+    pub fn new(db: Db) -> Self {
+        Self::new_at(db, GENESIS_JOURNAL_HASH)
+    }
+
+    /// Create a new replayer, starting after the journal with the given hash.
+    /// The database must contain the state produced by that journal.
+    pub fn new_at(db: Db, latest_hash: B256) -> Self {
+        Self { db, latest_hash, latest_height: None, state: StateRootInputs::default() }
+    }
+
+    /// Get a reference to the database.
+    pub const fn db(&self) -> &Db {
+        &self.db
+    }
+
+    /// Get a mutable reference to the database.
+    pub const fn db_mut(&mut self) -> &mut Db {
+        &mut self.db
+    }
+
+    /// Get the hash of the latest replayed journal.
+    pub const fn latest_hash(&self) -> B256 {
+        self.latest_hash
+    }
+
+    /// Get the rollup height of the latest replayed journal, if any journal
+    /// has been replayed.
+    pub const fn latest_height(&self) -> Option<u64> {
+        self.latest_height
+    }
+
+    /// Get the accumulated post-state of all replayed journals.
+    pub const fn state_root_inputs(&self) -> &StateRootInputs {
+        &self.state
+    }
+
+    /// Deconstruct the replayer into the database and accumulated post-state.
+    pub fn into_parts(self) -> (Db, StateRootInputs) {
+        (self.db, self.state)
+    }
+}
+
+impl<Db: DatabaseCommit> JournalReplay<Db> {
+    /// Apply a journal to the database.
+    pub fn apply(&mut self, journal: &Journal<'_>) -> Result<(), ReplayError> {
+        if journal.prev_journal_hash() != self.latest_hash {
+            return Err(ReplayError::WrongPrevHash {
+                latest_hash: self.latest_hash,
+                in_journal: journal.prev_journal_hash(),
+            });
+        }
+
+        self.commit(apply_changes(journal.state_diff()));
+        self.latest_hash = journal.journal_hash();
+        self.latest_height = Some(journal.rollup_height());
+        Ok(())
+    }
+
+    /// Undo the latest applied journal, restoring the state before it.
+    ///
+    /// Storage wiped by selfdestructs in the journal is not restored. See
+    /// [`revert_changes`].
+    pub fn undo(&mut self, journal: &Journal<'_>) -> Result<(), ReplayError> {
+        if journal.journal_hash() != self.latest_hash {
+            return Err(ReplayError::NotLatest {
+                latest_hash: self.latest_hash,
+                journal_hash: journal.journal_hash(),
+            });
+        }
+
+        self.commit(revert_changes(journal.state_diff()));
+        self.latest_hash = journal.prev_journal_hash();
+        self.latest_height = journal.rollup_height().checked_sub(1);
+        Ok(())
+    }
+
+    /// Apply all journals produced by the stream, in order. Returns the number
+    /// of journals applied.
+    ///
+    /// Stops at the first journal that fails to apply. Journals before it
+    /// remain applied.
+    pub async fn apply_stream<'a>(
+        &mut self,
+        stream: impl JournalStream<'a>,
+    ) -> Result<usize, ReplayError> {
+        pin_mut!(stream);
+
+        let mut count = 0;
+        while let Some(journal) = stream.next().await {
+            self.apply(&journal)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        self.state.record(&changes);
+        self.db.commit(changes);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{host::test::make_state_diff, HostJournal, JournalMeta};
+    use alloy::{
+        consensus::Header,
+        primitives::{Bytes, KECCAK256_EMPTY},
+    };
+    use futures_util::FutureExt;
+    use std::borrow::Cow;
+    use trevm::revm::{
+        database::{states::StorageSlot, CacheDB, EmptyDB},
+        state::Bytecode,
+        Database,
+    };
+
+    const ACCT: Address = Address::repeat_byte(0x99);
+    const NEW_ACCT: Address = Address::repeat_byte(0x42);
+
+    fn journal(rollup: u64, prev_hash: B256, state: BundleStateIndex<'static>) -> Journal<'static> {
+        let meta = JournalMeta::new(
+            100 + rollup,
+            prev_hash,
+            Cow::Owned(Header { number: rollup, ..Default::default() }),
+        );
+        Journal::V1(HostJournal::new(meta, state))
+    }
+
+    // A diff that creates a new account with storage.
+    fn creation_diff() -> BundleStateIndex<'static> {
+        let mut bsi = BundleStateIndex::default();
+        bsi.state.insert(
+            NEW_ACCT,
+            AcctDiff {
+                outcome: InfoOutcome::Created(Cow::Owned(AccountInfo {
+                    balance: U256::from(5),
+                    nonce: 1,
+                    code_hash: KECCAK256_EMPTY,
+                    ..Default::default()
+                })),
+                storage_diff: BTreeMap::from_iter([(
+                    U256::from(1),
+                    Cow::Owned(StorageSlot {
+                        previous_or_original_value: U256::ZERO,
+                        present_value: U256::from(9),
+                    }),
+                )]),
+            },
+        );
+        bsi
+    }
+
+    // A diff that destroys `ACCT`, as seeded by `seeded_db`.
+    fn destruction_diff() -> BundleStateIndex<'static> {
+        let mut bsi = BundleStateIndex::default();
+        bsi.state.insert(
+            ACCT,
+            AcctDiff {
+                outcome: InfoOutcome::Destroyed(Cow::Owned(AccountInfo {
+                    balance: U256::from(38),
+                    nonce: 7,
+                    ..Default::default()
+                })),
+                storage_diff: BTreeMap::from_iter([(
+                    U256::MAX,
+                    Cow::Owned(StorageSlot {
+                        previous_or_original_value: U256::from(123456),
+                        present_value: U256::ZERO,
+                    }),
+                )]),
+            },
+        );
+        bsi
+    }
+
+    // Seed the database with the pre-state expected by `make_state_diff`.
+    fn seeded_db() -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            ACCT,
+            AccountInfo { balance: U256::from(38), nonce: 7, ..Default::default() },
+        );
+        db.insert_account_storage(ACCT, U256::MAX, U256::from(123456)).unwrap();
+        db
+    }
+
+    #[test]
+    fn apply_and_undo() {
+        let j0 = journal(0, GENESIS_JOURNAL_HASH, make_state_diff());
+        let j1 = journal(1, j0.journal_hash(), creation_diff());
+
+        let mut replay = JournalReplay::new(seeded_db());
+        replay.apply(&j0).unwrap();
+        replay.apply(&j1).unwrap();
+        assert_eq!(replay.latest_hash(), j1.journal_hash());
+        assert_eq!(replay.latest_height(), Some(1));
+
+        let db = replay.db_mut();
+        let info = db.basic(ACCT).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(23828839));
+        assert_eq!(info.nonce, 83);
+        assert_eq!(
+            db.code_by_hash(info.code_hash).unwrap(),
+            Bytecode::new_legacy(Bytes::from_static(b"world"))
+        );
+        assert_eq!(db.storage(ACCT, U256::MAX).unwrap(), U256::from(654321));
+        assert_eq!(db.basic(NEW_ACCT).unwrap().unwrap().balance, U256::from(5));
+        assert_eq!(db.storage(NEW_ACCT, U256::from(1)).unwrap(), U256::from(9));
+
+        let inputs = replay.state_root_inputs();
+        assert_eq!(inputs.accounts.len(), 2);
+        assert!(inputs.storage[&NEW_ACCT].wiped);
+        assert_eq!(inputs.storage[&ACCT].slots[&U256::MAX], U256::from(654321));
+
+        // undo must proceed from the latest journal
+        assert!(matches!(replay.undo(&j0), Err(ReplayError::NotLatest { .. })));
+        replay.undo(&j1).unwrap();
+        replay.undo(&j0).unwrap();
+        assert_eq!(replay.latest_hash(), GENESIS_JOURNAL_HASH);
+
+        let db = replay.db_mut();
+        let info = db.basic(ACCT).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(38));
+        assert_eq!(info.nonce, 7);
+        assert_eq!(db.storage(ACCT, U256::MAX).unwrap(), U256::from(123456));
+        assert_eq!(db.basic(NEW_ACCT).unwrap().unwrap_or_default(), AccountInfo::default());
+        assert_eq!(db.storage(NEW_ACCT, U256::from(1)).unwrap(), U256::ZERO);
+
+        assert_eq!(replay.state_root_inputs().accounts[&NEW_ACCT], None);
+    }
+
+    #[test]
+    fn undo_destroyed_restores_listed_slots_only() {
+        let mut db = seeded_db();
+        db.insert_account_storage(ACCT, U256::from(2), U256::from(77)).unwrap();
+
+        let j0 = journal(0, GENESIS_JOURNAL_HASH, destruction_diff());
+
+        let mut replay = JournalReplay::new(db);
+        replay.apply(&j0).unwrap();
+        assert_eq!(replay.db_mut().basic(ACCT).unwrap(), None);
+        assert_eq!(replay.db_mut().storage(ACCT, U256::MAX).unwrap(), U256::ZERO);
+
+        replay.undo(&j0).unwrap();
+        let db = replay.db_mut();
+        let info = db.basic(ACCT).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(38));
+        assert_eq!(info.nonce, 7);
+        assert_eq!(db.storage(ACCT, U256::MAX).unwrap(), U256::from(123456));
+
+        // Storage wiped by the selfdestruct, and not listed in the diff, is
+        // not restored.
+        assert_eq!(db.storage(ACCT, U256::from(2)).unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn rejects_wrong_prev_hash() {
+        let j0 = journal(0, B256::repeat_byte(1), make_state_diff());
+
+        let mut replay = JournalReplay::new(seeded_db());
+        assert!(matches!(replay.apply(&j0), Err(ReplayError::WrongPrevHash { .. })));
+        assert!(replay.state_root_inputs().is_empty());
+    }
+
+    #[test]
+    fn apply_stream() {
+        let j0 = journal(0, GENESIS_JOURNAL_HASH, make_state_diff());
+        let j1 = journal(1, j0.journal_hash(), creation_diff());
+        let j2_bad = journal(2, B256::ZERO, Default::default());
+
+        let mut replay = JournalReplay::new(seeded_db());
+        let stream = futures_util::stream::iter([j0, j1.clone(), j2_bad]);
+        let res = replay.apply_stream(stream).now_or_never().expect("stream is ready");
+
+        assert!(matches!(res, Err(ReplayError::WrongPrevHash { .. })));
+        assert_eq!(replay.latest_hash(), j1.journal_hash());
+    }
+}
@@ -165,6 +165,11 @@ impl<'a> JournalSet<'a> {
         self.journals.get(index)
     }
 
+    /// Iterate over the journals in the set, in ascending height order.
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, Journal<'a>> {
+        self.journals.iter()
+    }
+
     /// Returns the range of heights in the set. If the set is empty, returns
     /// `None`.
     pub fn range(&self) -> Option<RangeInclusive<u64>> {
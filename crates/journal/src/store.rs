@@ -0,0 +1,713 @@
+use crate::{Journal, GENESIS_JOURNAL_HASH};
+use alloy::primitives::B256;
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use trevm::journal::{JournalDecode, JournalDecodeError, JournalEncode};
+
+/// File extension used for journal segment files.
+const SEGMENT_EXTENSION: &str = "journal";
+
+/// Size of the length prefix of each record, in bytes.
+const RECORD_PREFIX_BYTES: u64 = 4;
+
+/// Default maximum size of a segment file, 64 MiB.
+pub const DEFAULT_MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Errors that can occur when opening or writing a [`JournalStore`].
+#[derive(Debug, thiserror::Error)]
+pub enum JournalStoreError {
+    /// An IO error occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// A complete record could not be decoded as a [`Journal`].
+    #[error("corrupt record in segment {segment} at offset {offset}: {source}")]
+    Decode {
+        /// The segment containing the record.
+        segment: u64,
+        /// The offset of the record within the segment.
+        offset: u64,
+        /// The decoding error.
+        source: JournalDecodeError,
+    },
+
+    /// A segment other than the last one ends with an incomplete record.
+    #[error("segment {segment} is truncated at offset {offset}")]
+    TruncatedSegment {
+        /// The segment containing the incomplete record.
+        segment: u64,
+        /// The offset of the incomplete record within the segment.
+        offset: u64,
+    },
+
+    /// The journal does not have the expected rollup height.
+    #[error("wrong height: actual {actual}, expected {expected}")]
+    WrongHeight {
+        /// The actual height of the journal.
+        actual: u64,
+        /// The expected height of the journal.
+        expected: u64,
+    },
+
+    /// The journal's host height is not after the latest host height.
+    #[error("wrong host height: actual {actual}, latest {latest}")]
+    WrongHostHeight {
+        /// The host height of the journal.
+        actual: u64,
+        /// The host height of the latest journal in the store.
+        latest: u64,
+    },
+
+    /// The journal's previous hash does not match the latest hash.
+    #[error("wrong prev_hash: current {latest_hash}, new journal expected {in_journal}")]
+    WrongPrevHash {
+        /// The latest hash of the store.
+        latest_hash: B256,
+        /// The previous hash declared by the journal.
+        in_journal: B256,
+    },
+
+    /// The encoded journal is too large to be stored in a single record.
+    #[error("journal of {0} bytes is too large to store")]
+    RecordTooLarge(usize),
+}
+
+/// Location and identifying information of a journal in the store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct JournalEntry {
+    /// The segment containing the journal.
+    segment: u64,
+    /// The offset of the record within the segment.
+    offset: u64,
+    /// The length of the encoded journal, excluding the length prefix.
+    len: u32,
+    /// The host height of the journal.
+    host_height: u64,
+    /// The rollup height of the journal.
+    rollup_height: u64,
+    /// The journal hash.
+    hash: B256,
+}
+
+impl JournalEntry {
+    /// The offset of the end of the record within the segment.
+    const fn end(&self) -> u64 {
+        self.offset + RECORD_PREFIX_BYTES + self.len as u64
+    }
+}
+
+/// A persistent, append-only store of [`Journal`]s.
+///
+/// Journals are stored in their [`JournalEncode`] format, in segment files
+/// within a directory. Each record is the journal's encoded length as a
+/// big-endian `u32`, followed by the encoded journal. When a segment reaches
+/// its maximum size, a new segment is started.
+///
+/// On [`JournalStore::open`] every record is decoded, and the
+/// `prev_journal_hash` chain is verified starting from
+/// [`GENESIS_JOURNAL_HASH`]. An incomplete record at the end of the last
+/// segment (e.g. from a crash during a write) is discarded. The store keeps an
+/// in-memory index by rollup height, host height and journal hash, and reads
+/// journals from disk on demand.
+///
+/// When the host chain reorgs, the store can be truncated back to a host or
+/// rollup height with [`JournalStore::truncate_to_host_height`] or
+/// [`JournalStore::truncate_to_rollup_height`].
+#[derive(Debug)]
+pub struct JournalStore {
+    /// The directory containing the segment files.
+    dir: PathBuf,
+
+    /// The maximum size of a segment file, in bytes.
+    max_segment_size: u64,
+
+    /// Index of journals, ordered by rollup height.
+    entries: Vec<JournalEntry>,
+
+    /// Map from journal hash to rollup height.
+    by_hash: HashMap<B256, u64>,
+
+    /// The segment that new journals are written to.
+    active_segment: u64,
+
+    /// The current size of the active segment.
+    active_size: u64,
+
+    /// Handle to the active segment, opened lazily.
+    writer: Option<File>,
+}
+
+impl JournalStore {
+    /// Open the store in the given directory, creating the directory if it
+    /// does not exist.
+    ///
+    /// All segments are read, and the journal hash chain is verified.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, JournalStoreError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let segments = list_segments(&dir)?;
+
+        let mut store = Self {
+            dir,
+            max_segment_size: DEFAULT_MAX_SEGMENT_SIZE,
+            entries: Vec::new(),
+            by_hash: HashMap::new(),
+            active_segment: segments.last().copied().unwrap_or_default(),
+            active_size: 0,
+            writer: None,
+        };
+
+        for (i, segment) in segments.iter().copied().enumerate() {
+            let is_last = i + 1 == segments.len();
+            store.load_segment(segment, is_last)?;
+        }
+
+        Ok(store)
+    }
+
+    /// Set the maximum size of a segment file. This affects only segments
+    /// written after the call.
+    pub const fn with_max_segment_size(mut self, max_segment_size: u64) -> Self {
+        self.max_segment_size = max_segment_size;
+        self
+    }
+
+    /// Get the directory containing the segment files.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Get the maximum size of a segment file.
+    pub const fn max_segment_size(&self) -> u64 {
+        self.max_segment_size
+    }
+
+    /// Returns the number of journals in the store.
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if the store is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the earliest rollup height in the store.
+    pub fn earliest_height(&self) -> Option<u64> {
+        self.entries.first().map(|e| e.rollup_height)
+    }
+
+    /// Returns the latest rollup height in the store.
+    pub fn latest_height(&self) -> Option<u64> {
+        self.entries.last().map(|e| e.rollup_height)
+    }
+
+    /// Returns the latest host height in the store.
+    pub fn latest_host_height(&self) -> Option<u64> {
+        self.entries.last().map(|e| e.host_height)
+    }
+
+    /// Returns the hash of the latest journal in the store, or
+    /// [`GENESIS_JOURNAL_HASH`] if the store is empty. The next journal
+    /// appended must have this as its `prev_journal_hash`.
+    pub fn latest_hash(&self) -> B256 {
+        self.entries.last().map(|e| e.hash).unwrap_or(GENESIS_JOURNAL_HASH)
+    }
+
+    /// Returns the range of rollup heights in the store. If the store is
+    /// empty, returns `None`.
+    pub fn range(&self) -> Option<RangeInclusive<u64>> {
+        Some(self.earliest_height()?..=self.latest_height()?)
+    }
+
+    /// True if the store contains a journal with the given hash.
+    pub fn contains_hash(&self, hash: B256) -> bool {
+        self.by_hash.contains_key(&hash)
+    }
+
+    /// Get the index of the entry with the rollup height, None if not present.
+    fn index_of(&self, rollup_height: u64) -> Option<usize> {
+        let start = self.earliest_height()?;
+        if rollup_height < start || rollup_height > self.latest_height()? {
+            return None;
+        }
+        Some((rollup_height - start) as usize)
+    }
+
+    /// Get the index of the entry with the host height, None if not present.
+    fn index_of_host_height(&self, host_height: u64) -> Option<usize> {
+        self.entries.binary_search_by_key(&host_height, |e| e.host_height).ok()
+    }
+
+    /// Get the journal at the rollup height, if it is within the store.
+    pub fn get_by_rollup_height(
+        &self,
+        rollup_height: u64,
+    ) -> Result<Option<Journal<'static>>, JournalStoreError> {
+        self.index_of(rollup_height).map(|idx| self.read_entry(&self.entries[idx])).transpose()
+    }
+
+    /// Get the journal at the host height, if it is within the store.
+    pub fn get_by_host_height(
+        &self,
+        host_height: u64,
+    ) -> Result<Option<Journal<'static>>, JournalStoreError> {
+        self.index_of_host_height(host_height)
+            .map(|idx| self.read_entry(&self.entries[idx]))
+            .transpose()
+    }
+
+    /// Get the journal with the hash, if it is within the store.
+    pub fn get_by_hash(&self, hash: B256) -> Result<Option<Journal<'static>>, JournalStoreError> {
+        self.by_hash
+            .get(&hash)
+            .and_then(|height| self.index_of(*height))
+            .map(|idx| self.read_entry(&self.entries[idx]))
+            .transpose()
+    }
+
+    /// Get the latest journal in the store.
+    pub fn tip(&self) -> Result<Option<Journal<'static>>, JournalStoreError> {
+        self.entries.last().map(|entry| self.read_entry(entry)).transpose()
+    }
+
+    /// Iterate over the journals in the given range of rollup heights. Heights
+    /// outside the store are skipped.
+    pub fn iter_range(
+        &self,
+        range: RangeInclusive<u64>,
+    ) -> impl Iterator<Item = Result<Journal<'static>, JournalStoreError>> + '_ {
+        self.entries
+            .iter()
+            .filter(move |e| range.contains(&e.rollup_height))
+            .map(|entry| self.read_entry(entry))
+    }
+
+    /// Append a journal to the store.
+    ///
+    /// The journal must be at the next rollup height, be at a later host
+    /// height than the latest journal, and have the latest hash as its
+    /// `prev_journal_hash`.
+    pub fn append(&mut self, journal: &Journal<'_>) -> Result<(), JournalStoreError> {
+        self.check_next(
+            journal.rollup_height(),
+            journal.host_height(),
+            journal.prev_journal_hash(),
+        )?;
+
+        let encoded = journal.encoded();
+        let len = u32::try_from(encoded.len())
+            .map_err(|_| JournalStoreError::RecordTooLarge(encoded.len()))?;
+        let record_size = RECORD_PREFIX_BYTES + len as u64;
+
+        // Start a new segment if this record would overflow the active one.
+        if self.active_size > 0 && self.active_size + record_size > self.max_segment_size {
+            self.writer = None;
+            self.active_segment += 1;
+            self.active_size = 0;
+        }
+
+        let mut record = Vec::with_capacity(record_size as usize);
+        record.extend_from_slice(&len.to_be_bytes());
+        record.extend_from_slice(&encoded);
+
+        let offset = self.active_size;
+        self.writer()?.write_all(&record)?;
+        self.active_size += record_size;
+
+        self.push_entry(JournalEntry {
+            segment: self.active_segment,
+            offset,
+            len,
+            host_height: journal.host_height(),
+            rollup_height: journal.rollup_height(),
+            hash: journal.journal_hash(),
+        });
+
+        Ok(())
+    }
+
+    /// Flush all written journals to disk.
+    pub fn sync(&self) -> Result<(), JournalStoreError> {
+        if let Some(writer) = &self.writer {
+            writer.sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Remove all journals with a host height greater than `host_height`.
+    /// Returns the number of journals removed.
+    ///
+    /// This is intended for use when the host chain reorgs.
+    pub fn truncate_to_host_height(
+        &mut self,
+        host_height: u64,
+    ) -> Result<usize, JournalStoreError> {
+        let idx = self.entries.partition_point(|e| e.host_height <= host_height);
+        self.truncate_from(idx)
+    }
+
+    /// Remove all journals with a rollup height greater than `rollup_height`.
+    /// Returns the number of journals removed.
+    pub fn truncate_to_rollup_height(
+        &mut self,
+        rollup_height: u64,
+    ) -> Result<usize, JournalStoreError> {
+        let idx = self.entries.partition_point(|e| e.rollup_height <= rollup_height);
+        self.truncate_from(idx)
+    }
+
+    /// Remove the entry at `idx` and all entries after it, along with their
+    /// records on disk.
+    fn truncate_from(&mut self, idx: usize) -> Result<usize, JournalStoreError> {
+        let Some(first_removed) = self.entries.get(idx).copied() else {
+            return Ok(0);
+        };
+
+        // Drop the handle before modifying files.
+        self.writer = None;
+
+        for segment in list_segments(&self.dir)? {
+            if segment > first_removed.segment {
+                fs::remove_file(self.segment_path(segment))?;
+            }
+        }
+
+        let file = OpenOptions::new().write(true).open(self.segment_path(first_removed.segment))?;
+        file.set_len(first_removed.offset)?;
+        file.sync_data()?;
+
+        self.active_segment = first_removed.segment;
+        self.active_size = first_removed.offset;
+
+        let removed = self.entries.split_off(idx);
+        for entry in &removed {
+            self.by_hash.remove(&entry.hash);
+        }
+
+        Ok(removed.len())
+    }
+
+    /// Check that a journal with the given properties may be appended.
+    fn check_next(
+        &self,
+        rollup_height: u64,
+        host_height: u64,
+        prev_journal_hash: B256,
+    ) -> Result<(), JournalStoreError> {
+        if let Some(latest) = self.entries.last() {
+            if rollup_height != latest.rollup_height + 1 {
+                return Err(JournalStoreError::WrongHeight {
+                    actual: rollup_height,
+                    expected: latest.rollup_height + 1,
+                });
+            }
+            if host_height <= latest.host_height {
+                return Err(JournalStoreError::WrongHostHeight {
+                    actual: host_height,
+                    latest: latest.host_height,
+                });
+            }
+        }
+
+        let latest_hash = self.latest_hash();
+        if prev_journal_hash != latest_hash {
+            return Err(JournalStoreError::WrongPrevHash {
+                latest_hash,
+                in_journal: prev_journal_hash,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn push_entry(&mut self, entry: JournalEntry) {
+        self.by_hash.insert(entry.hash, entry.rollup_height);
+        self.entries.push(entry);
+    }
+
+    /// Read and verify all records in a segment, adding them to the index.
+    fn load_segment(&mut self, segment: u64, is_last: bool) -> Result<(), JournalStoreError> {
+        let path = self.segment_path(segment);
+        let bytes = fs::read(&path)?;
+
+        let mut offset = 0u64;
+        loop {
+            let rest = &bytes[offset as usize..];
+            if rest.is_empty() {
+                break;
+            }
+
+            let len = match rest.get(..RECORD_PREFIX_BYTES as usize) {
+                Some(prefix) => u32::from_be_bytes(prefix.try_into().expect("checked length")),
+                None => break,
+            };
+            let Some(mut buf) =
+                rest.get(RECORD_PREFIX_BYTES as usize..RECORD_PREFIX_BYTES as usize + len as usize)
+            else {
+                break;
+            };
+
+            let journal = Journal::decode(&mut buf)
+                .map_err(|source| JournalStoreError::Decode { segment, offset, source })?;
+            self.check_next(
+                journal.rollup_height(),
+                journal.host_height(),
+                journal.prev_journal_hash(),
+            )?;
+
+            let entry = JournalEntry {
+                segment,
+                offset,
+                len,
+                host_height: journal.host_height(),
+                rollup_height: journal.rollup_height(),
+                hash: journal.journal_hash(),
+            };
+            offset = entry.end();
+            self.push_entry(entry);
+        }
+
+        // Any remaining bytes are an incomplete record.
+        if offset as usize != bytes.len() {
+            if !is_last {
+                return Err(JournalStoreError::TruncatedSegment { segment, offset });
+            }
+            let file = OpenOptions::new().write(true).open(&path)?;
+            file.set_len(offset)?;
+            file.sync_data()?;
+        }
+
+        if is_last {
+            self.active_segment = segment;
+            self.active_size = offset;
+        }
+
+        Ok(())
+    }
+
+    /// Read a journal from disk.
+    fn read_entry(&self, entry: &JournalEntry) -> Result<Journal<'static>, JournalStoreError> {
+        let mut file = File::open(self.segment_path(entry.segment))?;
+        file.seek(SeekFrom::Start(entry.offset + RECORD_PREFIX_BYTES))?;
+
+        let mut buf = vec![0u8; entry.len as usize];
+        file.read_exact(&mut buf)?;
+
+        Journal::decode(&mut buf.as_slice()).map_err(|source| JournalStoreError::Decode {
+            segment: entry.segment,
+            offset: entry.offset,
+            source,
+        })
+    }
+
+    /// Get the handle to the active segment, opening it if necessary.
+    fn writer(&mut self) -> Result<&mut File, JournalStoreError> {
+        if self.writer.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.segment_path(self.active_segment))?;
+            self.writer = Some(file);
+        }
+        Ok(self.writer.as_mut().expect("just set"))
+    }
+
+    fn segment_path(&self, segment: u64) -> PathBuf {
+        self.dir.join(format!("{segment:020}.{SEGMENT_EXTENSION}"))
+    }
+}
+
+/// List the segment ids in the directory, in ascending order.
+fn list_segments(dir: &Path) -> Result<Vec<u64>, JournalStoreError> {
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(SEGMENT_EXTENSION) {
+            continue;
+        }
+        if let Some(id) = path.file_stem().and_then(|s| s.to_str()).and_then(|s| s.parse().ok()) {
+            segments.push(id);
+        }
+    }
+    segments.sort_unstable();
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{host::test::make_state_diff, HostJournal, JournalMeta};
+    use alloy::consensus::Header;
+    use std::borrow::Cow;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("signet-journal-store-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn journal_at_heights(host: u64, rollup: u64, prev_hash: B256) -> Journal<'static> {
+        let meta = JournalMeta::new(
+            host,
+            prev_hash,
+            Cow::Owned(Header { number: rollup, ..Default::default() }),
+        );
+        Journal::V1(HostJournal::new(meta, make_state_diff()))
+    }
+
+    fn chain(len: u64) -> Vec<Journal<'static>> {
+        let mut prev = GENESIS_JOURNAL_HASH;
+        (0..len)
+            .map(|i| {
+                let journal = journal_at_heights(100 + i, i, prev);
+                prev = journal.journal_hash();
+                journal
+            })
+            .collect()
+    }
+
+    #[test]
+    fn append_and_reopen() {
+        let dir = temp_dir("reopen");
+        let journals = chain(5);
+
+        {
+            let mut store = JournalStore::open(&dir).unwrap().with_max_segment_size(512);
+            for journal in &journals {
+                store.append(journal).unwrap();
+            }
+            store.sync().unwrap();
+        }
+
+        // small segments should have forced rollover
+        assert!(list_segments(&dir).unwrap().len() > 1);
+
+        let store = JournalStore::open(&dir).unwrap();
+        assert_eq!(store.len(), 5);
+        assert_eq!(store.range(), Some(0..=4));
+        assert_eq!(store.latest_host_height(), Some(104));
+        assert_eq!(store.latest_hash(), journals[4].journal_hash());
+
+        assert_eq!(store.get_by_rollup_height(2).unwrap().as_ref(), Some(&journals[2]));
+        assert_eq!(store.get_by_host_height(103).unwrap().as_ref(), Some(&journals[3]));
+        assert_eq!(
+            store.get_by_hash(journals[1].journal_hash()).unwrap().as_ref(),
+            Some(&journals[1])
+        );
+        assert_eq!(store.get_by_rollup_height(5).unwrap(), None);
+        assert_eq!(store.tip().unwrap().as_ref(), Some(&journals[4]));
+
+        let ranged = store.iter_range(1..=3).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(ranged, journals[1..=3]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_inconsistent() {
+        let dir = temp_dir("inconsistent");
+        let journals = chain(2);
+        let mut store = JournalStore::open(&dir).unwrap();
+
+        // first journal must build on the genesis hash
+        assert!(matches!(
+            store.append(&journal_at_heights(100, 0, B256::repeat_byte(1))),
+            Err(JournalStoreError::WrongPrevHash { .. })
+        ));
+
+        store.append(&journals[0]).unwrap();
+        assert!(matches!(store.append(&journals[0]), Err(JournalStoreError::WrongHeight { .. })));
+        assert!(matches!(
+            store.append(&journal_at_heights(100, 1, journals[0].journal_hash())),
+            Err(JournalStoreError::WrongHostHeight { .. })
+        ));
+        store.append(&journals[1]).unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn truncate_on_reorg() {
+        let dir = temp_dir("truncate");
+        let journals = chain(6);
+
+        let mut store = JournalStore::open(&dir).unwrap().with_max_segment_size(512);
+        for journal in &journals {
+            store.append(journal).unwrap();
+        }
+
+        assert_eq!(store.truncate_to_host_height(102).unwrap(), 3);
+        assert_eq!(store.range(), Some(0..=2));
+        assert!(!store.contains_hash(journals[3].journal_hash()));
+        assert_eq!(store.truncate_to_host_height(102).unwrap(), 0);
+
+        // an alternate history can be written after truncation
+        let alt = journal_at_heights(104, 3, journals[2].journal_hash());
+        store.append(&alt).unwrap();
+        drop(store);
+
+        let mut store = JournalStore::open(&dir).unwrap();
+        assert_eq!(store.range(), Some(0..=3));
+        assert_eq!(store.tip().unwrap(), Some(alt));
+
+        assert_eq!(store.truncate_to_rollup_height(0).unwrap(), 3);
+        assert_eq!(store.latest_hash(), journals[0].journal_hash());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recovers_torn_write() {
+        let dir = temp_dir("torn");
+        let journals = chain(3);
+
+        let mut store = JournalStore::open(&dir).unwrap();
+        for journal in &journals {
+            store.append(journal).unwrap();
+        }
+        let path = store.segment_path(store.active_segment);
+        drop(store);
+
+        // simulate a crash partway through writing the last record
+        let size = fs::metadata(&path).unwrap().len();
+        OpenOptions::new().write(true).open(&path).unwrap().set_len(size - 10).unwrap();
+
+        let mut store = JournalStore::open(&dir).unwrap();
+        assert_eq!(store.range(), Some(0..=1));
+        store.append(&journals[2]).unwrap();
+        drop(store);
+
+        let store = JournalStore::open(&dir).unwrap();
+        assert_eq!(store.range(), Some(0..=2));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn detects_broken_chain() {
+        let dir = temp_dir("broken");
+        let journals = chain(2);
+        let bad = journal_at_heights(102, 2, B256::repeat_byte(0xab));
+
+        fs::create_dir_all(&dir).unwrap();
+        let mut bytes = Vec::new();
+        for journal in journals.iter().chain(std::iter::once(&bad)) {
+            let encoded = journal.encoded();
+            bytes.extend_from_slice(&(encoded.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(&encoded);
+        }
+        fs::write(dir.join(format!("{:020}.{SEGMENT_EXTENSION}", 0)), bytes).unwrap();
+
+        assert!(matches!(JournalStore::open(&dir), Err(JournalStoreError::WrongPrevHash { .. })));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use crate::Journal;
+use alloy::{primitives::B256, rlp::BufMut};
+use trevm::journal::{JournalDecode, JournalDecodeError, JournalEncode};
+
+const TAG_GET_BY_RANGE: u8 = 0;
+const TAG_GET_BY_HASH: u8 = 1;
+const TAG_JOURNALS: u8 = 2;
+const TAG_ANNOUNCEMENT: u8 = 3;
+
+/// Request for consecutive journals, starting at a rollup height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GetJournalsByRange {
+    /// The rollup height of the first journal requested.
+    pub start: u64,
+    /// The maximum number of journals requested.
+    pub count: u64,
+}
+
+impl GetJournalsByRange {
+    /// True if the rollup height is within the requested range.
+    pub const fn contains(&self, rollup_height: u64) -> bool {
+        rollup_height >= self.start && rollup_height - self.start < self.count
+    }
+}
+
+/// Request for a single journal, by journal hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GetJournalByHash {
+    /// The hash of the requested journal.
+    pub hash: B256,
+}
+
+/// Announcement of a peer's latest journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalHeadAnnouncement {
+    /// The host height of the journal.
+    pub host_height: u64,
+    /// The rollup height of the journal.
+    pub rollup_height: u64,
+    /// The journal hash.
+    pub journal_hash: B256,
+}
+
+impl From<&Journal<'_>> for JournalHeadAnnouncement {
+    fn from(journal: &Journal<'_>) -> Self {
+        Self {
+            host_height: journal.host_height(),
+            rollup_height: journal.rollup_height(),
+            journal_hash: journal.journal_hash(),
+        }
+    }
+}
+
+/// A request for journals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalRequest {
+    /// Request journals by range.
+    ByRange(GetJournalsByRange),
+    /// Request a journal by hash.
+    ByHash(GetJournalByHash),
+}
+
+impl From<GetJournalsByRange> for JournalRequest {
+    fn from(req: GetJournalsByRange) -> Self {
+        Self::ByRange(req)
+    }
+}
+
+impl From<GetJournalByHash> for JournalRequest {
+    fn from(req: GetJournalByHash) -> Self {
+        Self::ByHash(req)
+    }
+}
+
+/// A message of the journal sync protocol.
+///
+/// Requests carry an id chosen by the requester, which is echoed in the
+/// corresponding [`JournalMessage::Journals`] response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JournalMessage<'a> {
+    /// A request for journals.
+    Request {
+        /// The request id.
+        id: u64,
+        /// The request.
+        request: JournalRequest,
+    },
+    /// A response containing journals, in ascending height order.
+    Journals {
+        /// The id of the request this responds to.
+        id: u64,
+        /// The journals.
+        journals: Vec<Journal<'a>>,
+    },
+    /// An announcement of the sender's latest journal.
+    Announcement(JournalHeadAnnouncement),
+}
+
+impl JournalEncode for JournalMessage<'_> {
+    fn serialized_size(&self) -> usize {
+        1 + match self {
+            Self::Request { request: JournalRequest::ByRange(_), .. } => 8 + 8 + 8,
+            Self::Request { request: JournalRequest::ByHash(_), .. } => 8 + 32,
+            Self::Journals { journals, .. } => {
+                8 + 4 + journals.iter().map(|j| 4 + j.serialized_size()).sum::<usize>()
+            }
+            Self::Announcement(_) => 8 + 8 + 32,
+        }
+    }
+
+    fn encode(&self, buf: &mut dyn BufMut) {
+        match self {
+            Self::Request { id, request: JournalRequest::ByRange(req) } => {
+                TAG_GET_BY_RANGE.encode(buf);
+                id.encode(buf);
+                req.start.encode(buf);
+                req.count.encode(buf);
+            }
+            Self::Request { id, request: JournalRequest::ByHash(req) } => {
+                TAG_GET_BY_HASH.encode(buf);
+                id.encode(buf);
+                req.hash.encode(buf);
+            }
+            Self::Journals { id, journals } => {
+                TAG_JOURNALS.encode(buf);
+                id.encode(buf);
+                (journals.len() as u32).encode(buf);
+                for journal in journals {
+                    (journal.serialized_size() as u32).encode(buf);
+                    journal.encode(buf);
+                }
+            }
+            Self::Announcement(ann) => {
+                TAG_ANNOUNCEMENT.encode(buf);
+                ann.host_height.encode(buf);
+                ann.rollup_height.encode(buf);
+                ann.journal_hash.encode(buf);
+            }
+        }
+    }
+}
+
+impl JournalDecode for JournalMessage<'static> {
+    fn decode(buf: &mut &[u8]) -> Result<Self, JournalDecodeError> {
+        let tag: u8 = JournalDecode::decode(buf)?;
+        match tag {
+            TAG_GET_BY_RANGE => Ok(Self::Request {
+                id: JournalDecode::decode(buf)?,
+                request: JournalRequest::ByRange(GetJournalsByRange {
+                    start: JournalDecode::decode(buf)?,
+                    count: JournalDecode::decode(buf)?,
+                }),
+            }),
+            TAG_GET_BY_HASH => Ok(Self::Request {
+                id: JournalDecode::decode(buf)?,
+                request: JournalRequest::ByHash(GetJournalByHash {
+                    hash: JournalDecode::decode(buf)?,
+                }),
+            }),
+            TAG_JOURNALS => {
+                let id = JournalDecode::decode(buf)?;
+                let count: u32 = JournalDecode::decode(buf)?;
+                let mut journals = Vec::with_capacity(count.min(1024) as usize);
+                for _ in 0..count {
+                    let len: u32 = JournalDecode::decode(buf)?;
+                    if buf.len() < len as usize {
+                        return Err(JournalDecodeError::Overrun {
+                            ty_name: "Journal",
+                            expected: len as usize,
+                            remaining: buf.len(),
+                        });
+                    }
+                    let (mut encoded, rest) = buf.split_at(len as usize);
+                    *buf = rest;
+                    journals.push(Journal::decode(&mut encoded)?);
+                }
+                Ok(Self::Journals { id, journals })
+            }
+            TAG_ANNOUNCEMENT => Ok(Self::Announcement(JournalHeadAnnouncement {
+                host_height: JournalDecode::decode(buf)?,
+                rollup_height: JournalDecode::decode(buf)?,
+                journal_hash: JournalDecode::decode(buf)?,
+            })),
+            _ => Err(JournalDecodeError::InvalidTag {
+                ty_name: "JournalMessage",
+                tag,
+                max_expected: TAG_ANNOUNCEMENT,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{host::test::make_state_diff, HostJournal, JournalMeta};
+    use alloy::consensus::Header;
+    use std::borrow::Cow;
+
+    #[test]
+    fn roundtrip() {
+        let journal = Journal::V1(HostJournal::new(
+            JournalMeta::new(42, B256::repeat_byte(0x17), Cow::Owned(Header::default())),
+            make_state_diff(),
+        ));
+
+        let messages = [
+            JournalMessage::Request {
+                id: 1,
+                request: GetJournalsByRange { start: 5, count: 10 }.into(),
+            },
+            JournalMessage::Request {
+                id: 2,
+                request: GetJournalByHash { hash: B256::repeat_byte(3) }.into(),
+            },
+            JournalMessage::Journals { id: 3, journals: vec![journal.clone(), journal.clone()] },
+            JournalMessage::Journals { id: 4, journals: vec![] },
+            JournalMessage::Announcement((&journal).into()),
+        ];
+
+        for message in messages {
+            let buf = message.encoded();
+            assert_eq!(buf.len(), message.serialized_size());
+            let decoded = JournalMessage::decode(&mut &buf[..]).unwrap();
+            assert_eq!(message, decoded);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+//! Wire types and a sync state machine for sharing journals between nodes.
+
+mod messages;
+pub use messages::{
+    GetJournalByHash, GetJournalsByRange, JournalHeadAnnouncement, JournalMessage, JournalRequest,
+};
+
+mod syncer;
+pub use syncer::{JournalSyncer, SyncError, DEFAULT_BATCH_SIZE};
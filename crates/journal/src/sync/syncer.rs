@@ -0,0 +1,433 @@
+use crate::{
+    sync::{GetJournalsByRange, JournalHeadAnnouncement, JournalMessage, JournalRequest},
+    Journal, JournalSet, GENESIS_JOURNAL_HASH,
+};
+use alloy::primitives::{map::HashMap, B256};
+use std::hash::Hash;
+
+/// Default maximum number of journals requested or served per message.
+pub const DEFAULT_BATCH_SIZE: u64 = 64;
+
+/// Errors produced by the [`JournalSyncer`] when handling peer messages.
+///
+/// Any error other than [`SyncError::UnexpectedResponse`] indicates that the
+/// peer misbehaved, and results in the peer being dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum SyncError {
+    /// The response does not correspond to an in-flight request to the peer.
+    #[error("unexpected response with id {id}")]
+    UnexpectedResponse {
+        /// The id of the response.
+        id: u64,
+    },
+
+    /// The response contains a journal outside the requested range.
+    #[error("response {id} contains out-of-range journal at height {rollup_height}")]
+    OutOfRange {
+        /// The id of the response.
+        id: u64,
+        /// The rollup height of the journal.
+        rollup_height: u64,
+    },
+
+    /// The response contains a journal that does not extend the local chain.
+    #[error("journal at height {rollup_height} does not extend {expected_prev_hash}")]
+    BrokenChain {
+        /// The rollup height of the journal.
+        rollup_height: u64,
+        /// The hash the journal was expected to build on.
+        expected_prev_hash: B256,
+    },
+}
+
+/// A transport-agnostic state machine that syncs journals from peers into a
+/// [`JournalSet`].
+///
+/// The syncer does no IO. The caller is responsible for delivering messages
+/// received from peers to [`JournalSyncer::handle_message`], sending any
+/// reply it returns, and sending the requests produced by
+/// [`JournalSyncer::poll_request`].
+///
+/// Peers announce their latest journal with
+/// [`JournalMessage::Announcement`]. When a peer is ahead of the local chain,
+/// the syncer requests the missing range from it, one batch at a time. Each
+/// received journal must extend the local hash chain, or the peer is dropped.
+/// The syncer also serves requests from its own [`JournalSet`].
+///
+/// Peers are identified by any hashable id `P`, chosen by the transport.
+#[derive(Debug, Clone)]
+pub struct JournalSyncer<'a, P> {
+    /// The synced journals.
+    set: JournalSet<'a>,
+
+    /// The rollup height expected of the first journal, if the set has never
+    /// held a journal.
+    start_height: u64,
+
+    /// The previous hash expected of the first journal, if the set has never
+    /// held a journal.
+    anchor_hash: B256,
+
+    /// The latest announced head of each peer.
+    peers: HashMap<P, JournalHeadAnnouncement>,
+
+    /// The in-flight request, if any.
+    in_flight: Option<(u64, P, GetJournalsByRange)>,
+
+    /// The id of the next request.
+    next_id: u64,
+
+    /// Maximum number of journals requested or served per message.
+    batch_size: u64,
+}
+
+impl<'a, P> JournalSyncer<'a, P>
+where
+    P: Clone + Eq + Hash,
+{
+    /// Create a syncer with an empty set, syncing from the journal at
+    /// `start_height`, which must build on [`GENESIS_JOURNAL_HASH`].
+    pub fn new(start_height: u64) -> Self {
+        Self::with_anchor(start_height, GENESIS_JOURNAL_HASH)
+    }
+
+    /// Create a syncer with an empty set, syncing from the journal at
+    /// `start_height`, which must build on `anchor_hash`.
+    pub fn with_anchor(start_height: u64, anchor_hash: B256) -> Self {
+        Self {
+            set: JournalSet::new(),
+            start_height,
+            anchor_hash,
+            peers: Default::default(),
+            in_flight: None,
+            next_id: 0,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Create a syncer that extends an existing set. If the set has never
+    /// held a journal, syncing starts from height 0 and
+    /// [`GENESIS_JOURNAL_HASH`].
+    pub fn from_set(set: JournalSet<'a>) -> Self {
+        Self { set, ..Self::new(0) }
+    }
+
+    /// Set the maximum number of journals requested or served per message.
+    pub fn with_batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Get the synced journals.
+    pub const fn set(&self) -> &JournalSet<'a> {
+        &self.set
+    }
+
+    /// Get a mutable reference to the synced journals, e.g. to drain
+    /// processed journals.
+    pub const fn set_mut(&mut self) -> &mut JournalSet<'a> {
+        &mut self.set
+    }
+
+    /// Consume the syncer, returning the synced journals.
+    pub fn into_set(self) -> JournalSet<'a> {
+        self.set
+    }
+
+    /// The rollup height of the next journal to be synced.
+    pub fn next_height(&self) -> u64 {
+        self.set.latest_height().map(|h| h + 1).unwrap_or(self.start_height)
+    }
+
+    /// The hash that the next journal to be synced must build on.
+    pub fn latest_hash(&self) -> B256 {
+        self.set.latest_hash().unwrap_or(self.anchor_hash)
+    }
+
+    /// Announcement of the latest local journal, to be gossiped to peers.
+    pub fn announcement(&self) -> Option<JournalHeadAnnouncement> {
+        self.set.get_by_rollup_height(self.set.latest_height()?).map(Into::into)
+    }
+
+    /// True if no known peer is ahead of the local chain.
+    pub fn is_synced(&self) -> bool {
+        self.best_peer().is_none()
+    }
+
+    /// True if a request is awaiting a response.
+    pub const fn is_waiting(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// Record a peer's announced head.
+    pub fn on_announcement(&mut self, peer: P, announcement: JournalHeadAnnouncement) {
+        self.peers.insert(peer, announcement);
+    }
+
+    /// Forget a peer, e.g. when it disconnects. Any in-flight request to it
+    /// is abandoned.
+    pub fn remove_peer(&mut self, peer: &P) {
+        self.peers.remove(peer);
+        if self.in_flight.as_ref().is_some_and(|(_, p, _)| p == peer) {
+            self.in_flight = None;
+        }
+    }
+
+    /// Abandon the in-flight request, e.g. when it times out. The peer is
+    /// forgotten, and the range will be requested from another peer.
+    pub fn on_timeout(&mut self) {
+        if let Some((_, peer, _)) = self.in_flight.take() {
+            self.peers.remove(&peer);
+        }
+    }
+
+    /// The peer furthest ahead of the local chain, if any.
+    fn best_peer(&self) -> Option<(&P, &JournalHeadAnnouncement)> {
+        let next_height = self.next_height();
+        self.peers
+            .iter()
+            .filter(|(_, head)| head.rollup_height >= next_height)
+            .max_by_key(|(_, head)| head.rollup_height)
+    }
+
+    /// Produce the next request to send, if the syncer is behind a peer and
+    /// has no request in flight. Returns the peer to send it to, and the
+    /// message.
+    pub fn poll_request(&mut self) -> Option<(P, JournalMessage<'a>)> {
+        if self.in_flight.is_some() {
+            return None;
+        }
+
+        let (peer, head) = self.best_peer()?;
+        let start = self.next_height();
+        let count = (head.rollup_height - start + 1).min(self.batch_size);
+        let request = GetJournalsByRange { start, count };
+        let peer = peer.clone();
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.in_flight = Some((id, peer.clone(), request));
+
+        Some((peer, JournalMessage::Request { id, request: request.into() }))
+    }
+
+    /// Handle a message from a peer. Returns a reply to send to the peer, if
+    /// any.
+    ///
+    /// If the peer sent an invalid response, it is dropped and an error is
+    /// returned.
+    pub fn handle_message(
+        &mut self,
+        peer: P,
+        message: JournalMessage<'a>,
+    ) -> Result<Option<JournalMessage<'a>>, SyncError> {
+        match message {
+            JournalMessage::Request { id, request } => Ok(Some(self.serve(id, request))),
+            JournalMessage::Journals { id, journals } => {
+                self.on_journals(peer, id, journals).map(|_| None)
+            }
+            JournalMessage::Announcement(announcement) => {
+                self.on_announcement(peer, announcement);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Serve a request from the local set.
+    pub fn serve(&self, id: u64, request: JournalRequest) -> JournalMessage<'a> {
+        let journals = match request {
+            JournalRequest::ByRange(req) => {
+                let count = req.count.min(self.batch_size);
+                self.set
+                    .iter()
+                    .filter(|j| {
+                        GetJournalsByRange { start: req.start, count }.contains(j.rollup_height())
+                    })
+                    .cloned()
+                    .collect()
+            }
+            JournalRequest::ByHash(req) => {
+                self.set.iter().filter(|j| j.journal_hash() == req.hash).cloned().collect()
+            }
+        };
+        JournalMessage::Journals { id, journals }
+    }
+
+    /// Handle a response to a range request, appending the journals to the
+    /// set. Returns the number of journals appended.
+    pub fn on_journals(
+        &mut self,
+        peer: P,
+        id: u64,
+        journals: Vec<Journal<'a>>,
+    ) -> Result<usize, SyncError> {
+        let request = match &self.in_flight {
+            Some((in_flight_id, p, request)) if *in_flight_id == id && *p == peer => *request,
+            _ => return Err(SyncError::UnexpectedResponse { id }),
+        };
+        self.in_flight = None;
+
+        // The peer cannot serve the range it announced.
+        if journals.is_empty() {
+            self.peers.remove(&peer);
+            return Ok(0);
+        }
+
+        let res = self.append_all(id, request, journals);
+        if res.is_err() {
+            self.peers.remove(&peer);
+        }
+        res
+    }
+
+    fn append_all(
+        &mut self,
+        id: u64,
+        request: GetJournalsByRange,
+        journals: Vec<Journal<'a>>,
+    ) -> Result<usize, SyncError> {
+        let mut appended = 0;
+        for journal in journals {
+            let rollup_height = journal.rollup_height();
+            if !request.contains(rollup_height) {
+                return Err(SyncError::OutOfRange { id, rollup_height });
+            }
+
+            let expected_prev_hash = self.latest_hash();
+            if rollup_height != self.next_height()
+                || journal.prev_journal_hash() != expected_prev_hash
+            {
+                return Err(SyncError::BrokenChain { rollup_height, expected_prev_hash });
+            }
+
+            self.set
+                .try_append(journal)
+                .map_err(|_| SyncError::BrokenChain { rollup_height, expected_prev_hash })?;
+            appended += 1;
+        }
+        Ok(appended)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{sync::GetJournalByHash, HostJournal, JournalMeta};
+    use alloy::consensus::Header;
+    use std::{borrow::Cow, sync::mpsc};
+    use trevm::journal::{JournalDecode, JournalEncode};
+
+    fn chain(len: u64) -> Vec<Journal<'static>> {
+        let mut prev = GENESIS_JOURNAL_HASH;
+        (0..len)
+            .map(|i| {
+                let meta = JournalMeta::new(
+                    100 + i,
+                    prev,
+                    Cow::Owned(Header { number: i, ..Default::default() }),
+                );
+                let journal = Journal::V1(HostJournal::new(meta, Default::default()));
+                prev = journal.journal_hash();
+                journal
+            })
+            .collect()
+    }
+
+    fn server(journals: &[Journal<'static>]) -> JournalSyncer<'static, &'static str> {
+        let mut set = JournalSet::new();
+        for journal in journals {
+            set.try_append(journal.clone()).unwrap();
+        }
+        JournalSyncer::from_set(set)
+    }
+
+    /// Send a message over a channel as bytes, and decode it on the other
+    /// side.
+    fn transmit(
+        tx: &mpsc::Sender<Vec<u8>>,
+        rx: &mpsc::Receiver<Vec<u8>>,
+        message: JournalMessage<'_>,
+    ) -> JournalMessage<'static> {
+        tx.send(message.encoded().to_vec()).unwrap();
+        let bytes = rx.recv().unwrap();
+        JournalMessage::decode(&mut bytes.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn syncs_over_channels() {
+        let journals = chain(10);
+        let mut server = server(&journals);
+        let mut client = JournalSyncer::new(0).with_batch_size(3);
+
+        let (to_server, server_inbox) = mpsc::channel();
+        let (to_client, client_inbox) = mpsc::channel();
+
+        // server gossips its head
+        let announcement = JournalMessage::Announcement(server.announcement().unwrap());
+        let message = transmit(&to_client, &client_inbox, announcement);
+        assert_eq!(client.handle_message("server", message).unwrap(), None);
+        assert!(!client.is_synced());
+
+        let mut rounds = 0;
+        while let Some((peer, request)) = client.poll_request() {
+            assert_eq!(peer, "server");
+            assert!(client.poll_request().is_none());
+
+            let request = transmit(&to_server, &server_inbox, request);
+            let response = server.handle_message("client", request).unwrap().unwrap();
+            let response = transmit(&to_client, &client_inbox, response);
+            assert_eq!(client.handle_message("server", response).unwrap(), None);
+            rounds += 1;
+        }
+
+        assert_eq!(rounds, 4);
+        assert!(client.is_synced());
+        assert_eq!(client.set().range(), Some(0..=9));
+        assert_eq!(client.latest_hash(), journals[9].journal_hash());
+
+        // serving by hash
+        let reply = server.serve(7, GetJournalByHash { hash: journals[4].journal_hash() }.into());
+        assert_eq!(reply, JournalMessage::Journals { id: 7, journals: vec![journals[4].clone()] });
+    }
+
+    #[test]
+    fn drops_misbehaving_peer() {
+        let journals = chain(4);
+        let mut client = JournalSyncer::new(0);
+
+        client.on_announcement("honest", (&journals[3]).into());
+        client.on_announcement(
+            "liar",
+            JournalHeadAnnouncement { rollup_height: 9, ..(&journals[3]).into() },
+        );
+
+        // the liar is furthest ahead, so it is asked first
+        let Some((peer, JournalMessage::Request { id, .. })) = client.poll_request() else {
+            panic!("expected request");
+        };
+        assert_eq!(peer, "liar");
+
+        // responses from other peers, or with the wrong id, are unexpected
+        assert_eq!(
+            client.on_journals("honest", id, journals.clone()),
+            Err(SyncError::UnexpectedResponse { id })
+        );
+        assert_eq!(
+            client.on_journals("liar", id + 1, journals.clone()),
+            Err(SyncError::UnexpectedResponse { id: id + 1 })
+        );
+
+        // a journal that skips ahead does not extend the chain
+        assert!(matches!(
+            client.on_journals("liar", id, journals[1..].to_vec()),
+            Err(SyncError::BrokenChain { rollup_height: 1, .. })
+        ));
+        assert!(client.set().is_empty());
+
+        // the honest peer is asked next
+        let (peer, _) = client.poll_request().unwrap();
+        assert_eq!(peer, "honest");
+        client.on_timeout();
+        assert!(client.poll_request().is_none());
+    }
+}
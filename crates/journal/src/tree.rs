@@ -0,0 +1,504 @@
+use crate::{Journal, JournalSet, GENESIS_JOURNAL_HASH};
+use alloy::primitives::{map::HashMap, B256};
+use std::cmp::Ordering;
+
+/// A rule for choosing the canonical head among competing branches of a
+/// [`JournalTree`].
+pub trait ForkChoice {
+    /// Compare two candidate heads. The head that compares
+    /// [`Ordering::Greater`] is preferred. If the candidates are equal, the
+    /// current head is kept.
+    fn compare(&self, a: &Journal<'_>, b: &Journal<'_>) -> Ordering;
+}
+
+impl<F> ForkChoice for F
+where
+    F: Fn(&Journal<'_>, &Journal<'_>) -> Ordering,
+{
+    fn compare(&self, a: &Journal<'_>, b: &Journal<'_>) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// The default [`ForkChoice`] rule. Prefers the journal with the highest host
+/// height, then the highest rollup height.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatestHostHeight;
+
+impl ForkChoice for LatestHostHeight {
+    fn compare(&self, a: &Journal<'_>, b: &Journal<'_>) -> Ordering {
+        (a.host_height(), a.rollup_height()).cmp(&(b.host_height(), b.rollup_height()))
+    }
+}
+
+/// Errors that can occur when inserting into a [`JournalTree`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum JournalTreeError<'a> {
+    /// The journal's previous hash is not the root, or any journal in the
+    /// tree.
+    #[error("unknown parent: {prev_hash}")]
+    UnknownParent {
+        /// The previous hash declared by the journal.
+        prev_hash: B256,
+
+        /// The journal.
+        journal: Box<Journal<'a>>,
+    },
+
+    /// The journal is not at the height following its parent.
+    #[error("wrong height: actual {actual}, expected {expected}")]
+    WrongHeight {
+        /// The actual height of the journal.
+        actual: u64,
+
+        /// The expected height of the journal.
+        expected: u64,
+
+        /// The journal.
+        journal: Box<Journal<'a>>,
+    },
+}
+
+impl<'a> JournalTreeError<'a> {
+    /// Converts the error into a journal, discarding error info.
+    pub fn into_journal(self) -> Journal<'a> {
+        match self {
+            Self::UnknownParent { journal, .. } => *journal,
+            Self::WrongHeight { journal, .. } => *journal,
+        }
+    }
+}
+
+/// A change of the canonical head of a [`JournalTree`].
+///
+/// When the new head extends the old head, `removed` is empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReorgEvent<'a> {
+    /// The hash of the latest journal shared by the old and new canonical
+    /// chains. This may be the tree's root hash.
+    pub common_ancestor: B256,
+
+    /// Journals removed from the canonical chain, in ascending height order.
+    pub removed: Vec<Journal<'a>>,
+
+    /// Journals added to the canonical chain, in ascending height order.
+    pub added: Vec<Journal<'a>>,
+}
+
+impl ReorgEvent<'_> {
+    /// True if the event removes journals from the canonical chain, rather
+    /// than just extending it.
+    pub const fn is_reorg(&self) -> bool {
+        !self.removed.is_empty()
+    }
+}
+
+/// A tree of journals, holding competing branches keyed by journal hash.
+///
+/// All journals descend from a root hash, which is either
+/// [`GENESIS_JOURNAL_HASH`] or the hash of the latest finalized journal. The
+/// canonical head is chosen among the branches by a [`ForkChoice`] rule,
+/// [`LatestHostHeight`] by default. Inserting a journal that changes the
+/// canonical head produces a [`ReorgEvent`].
+///
+/// Journals more than `finality_depth` below the canonical head may be
+/// finalized with [`JournalTree::prune`], which discards all branches that
+/// do not descend from the finalized journal.
+#[derive(Debug, Clone)]
+pub struct JournalTree<'a, F = LatestHostHeight> {
+    /// Journals, keyed by journal hash.
+    journals: HashMap<B256, Journal<'a>>,
+
+    /// Child hashes, keyed by parent hash.
+    children: HashMap<B256, Vec<B256>>,
+
+    /// The hash all journals descend from.
+    root_hash: B256,
+
+    /// The rollup height of the root journal, if known.
+    root_height: Option<u64>,
+
+    /// The hash of the canonical head, if the tree is not empty.
+    head: Option<B256>,
+
+    /// Number of journals below the canonical head that are not yet final.
+    finality_depth: u64,
+
+    /// The fork choice rule.
+    fork_choice: F,
+}
+
+impl Default for JournalTree<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JournalTree<'_> {
+    /// Default number of journals below the canonical head that are not yet
+    /// final.
+    pub const DEFAULT_FINALITY_DEPTH: u64 = 64;
+
+    /// Create a new empty tree rooted at [`GENESIS_JOURNAL_HASH`].
+    pub fn new() -> Self {
+        Self::new_at(GENESIS_JOURNAL_HASH, None)
+    }
+
+    /// Create a new empty tree rooted at the journal with the given hash and
+    /// rollup height.
+    pub fn new_at(root_hash: B256, root_height: Option<u64>) -> Self {
+        Self::with_fork_choice(root_hash, root_height, LatestHostHeight)
+    }
+}
+
+impl<'a, F: ForkChoice> JournalTree<'a, F> {
+    /// Create a new empty tree with a custom [`ForkChoice`] rule.
+    pub fn with_fork_choice(root_hash: B256, root_height: Option<u64>, fork_choice: F) -> Self {
+        Self {
+            journals: Default::default(),
+            children: Default::default(),
+            root_hash,
+            root_height,
+            head: None,
+            finality_depth: JournalTree::DEFAULT_FINALITY_DEPTH,
+            fork_choice,
+        }
+    }
+
+    /// Set the finality depth.
+    pub const fn with_finality_depth(mut self, finality_depth: u64) -> Self {
+        self.finality_depth = finality_depth;
+        self
+    }
+
+    /// Get the finality depth.
+    pub const fn finality_depth(&self) -> u64 {
+        self.finality_depth
+    }
+
+    /// Get the root hash. All journals in the tree descend from it.
+    pub const fn root_hash(&self) -> B256 {
+        self.root_hash
+    }
+
+    /// Get the rollup height of the root journal, if known.
+    pub const fn root_height(&self) -> Option<u64> {
+        self.root_height
+    }
+
+    /// Returns the number of journals in the tree, across all branches.
+    pub fn len(&self) -> usize {
+        self.journals.len()
+    }
+
+    /// True if the tree is empty.
+    pub fn is_empty(&self) -> bool {
+        self.journals.is_empty()
+    }
+
+    /// True if the tree contains a journal with the given hash.
+    pub fn contains(&self, hash: B256) -> bool {
+        self.journals.contains_key(&hash)
+    }
+
+    /// Get the journal with the given hash.
+    pub fn get(&self, hash: B256) -> Option<&Journal<'a>> {
+        self.journals.get(&hash)
+    }
+
+    /// Get the canonical head, if the tree is not empty.
+    pub fn head(&self) -> Option<&Journal<'a>> {
+        self.head.and_then(|hash| self.journals.get(&hash))
+    }
+
+    /// Get the hash of the canonical head, or the root hash if the tree is
+    /// empty.
+    pub fn head_hash(&self) -> B256 {
+        self.head.unwrap_or(self.root_hash)
+    }
+
+    /// Get the hashes of the tips of all branches.
+    pub fn tips(&self) -> impl Iterator<Item = B256> + '_ {
+        self.journals.keys().copied().filter(|hash| !self.children.contains_key(hash))
+    }
+
+    /// Get the path of hashes from the root (exclusive) to the given journal
+    /// (inclusive), in ascending height order.
+    fn path_to(&self, mut hash: B256) -> Vec<B256> {
+        let mut path = Vec::new();
+        while let Some(journal) = self.journals.get(&hash) {
+            path.push(hash);
+            hash = journal.prev_journal_hash();
+        }
+        path.reverse();
+        path
+    }
+
+    /// Get the canonical chain, from the root to the head, in ascending
+    /// height order.
+    pub fn canonical_chain(&self) -> Vec<&Journal<'a>> {
+        self.head
+            .map(|head| self.path_to(head).into_iter().map(|hash| &self.journals[&hash]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Copy the canonical chain into a [`JournalSet`].
+    pub fn canonical_set(&self) -> JournalSet<'a> {
+        let mut set = JournalSet::with_capacity(self.journals.len());
+        for journal in self.canonical_chain() {
+            set.try_append(journal.clone()).expect("canonical chain is consistent");
+        }
+        set
+    }
+
+    /// Get the canonical journal at the given rollup height.
+    pub fn canonical_at(&self, rollup_height: u64) -> Option<&Journal<'a>> {
+        self.canonical_chain().into_iter().find(|j| j.rollup_height() == rollup_height)
+    }
+
+    /// Check that the journal may be inserted.
+    fn check_parent(&self, journal: Journal<'a>) -> Result<Journal<'a>, JournalTreeError<'a>> {
+        let prev_hash = journal.prev_journal_hash();
+
+        let expected = if prev_hash == self.root_hash {
+            self.root_height.map(|h| h + 1)
+        } else if let Some(parent) = self.journals.get(&prev_hash) {
+            Some(parent.rollup_height() + 1)
+        } else {
+            return Err(JournalTreeError::UnknownParent { prev_hash, journal: Box::new(journal) });
+        };
+
+        match expected {
+            Some(expected) if expected != journal.rollup_height() => {
+                Err(JournalTreeError::WrongHeight {
+                    actual: journal.rollup_height(),
+                    expected,
+                    journal: Box::new(journal),
+                })
+            }
+            _ => Ok(journal),
+        }
+    }
+
+    /// Insert a journal into the tree. Its parent must be the root or a
+    /// journal in the tree.
+    ///
+    /// If the canonical head changes, returns a [`ReorgEvent`] describing the
+    /// change. Inserting a journal that is already in the tree does nothing.
+    pub fn insert(
+        &mut self,
+        journal: Journal<'a>,
+    ) -> Result<Option<ReorgEvent<'a>>, JournalTreeError<'a>> {
+        let hash = journal.journal_hash();
+        if self.journals.contains_key(&hash) {
+            return Ok(None);
+        }
+        let journal = self.check_parent(journal)?;
+
+        let prev_hash = journal.prev_journal_hash();
+        self.children.entry(prev_hash).or_default().push(hash);
+        self.journals.insert(hash, journal);
+
+        let new_head = match self.head() {
+            None => true,
+            Some(head) => match self.fork_choice.compare(&self.journals[&hash], head) {
+                Ordering::Greater => true,
+                Ordering::Equal => prev_hash == self.head_hash(),
+                Ordering::Less => false,
+            },
+        };
+
+        Ok(new_head.then(|| self.set_head(hash)))
+    }
+
+    /// Set the canonical head, returning the resulting [`ReorgEvent`].
+    fn set_head(&mut self, new_head: B256) -> ReorgEvent<'a> {
+        let old_path = self.head.map(|head| self.path_to(head)).unwrap_or_default();
+        let new_path = self.path_to(new_head);
+
+        let shared = old_path.iter().zip(new_path.iter()).take_while(|(a, b)| a == b).count();
+        let common_ancestor = if shared == 0 { self.root_hash } else { new_path[shared - 1] };
+
+        let collect =
+            |path: &[B256]| path.iter().map(|hash| self.journals[hash].clone()).collect::<Vec<_>>();
+        let event = ReorgEvent {
+            common_ancestor,
+            removed: collect(&old_path[shared..]),
+            added: collect(&new_path[shared..]),
+        };
+
+        self.head = Some(new_head);
+        event
+    }
+
+    /// Finalize canonical journals more than `finality_depth` below the
+    /// canonical head, and remove all branches that do not descend from the
+    /// latest finalized journal.
+    ///
+    /// Returns the finalized journals, in ascending height order. The latest
+    /// finalized journal becomes the new root.
+    pub fn prune(&mut self) -> Vec<Journal<'a>> {
+        let Some(head) = self.head else {
+            return Vec::new();
+        };
+
+        let path = self.path_to(head);
+        let keep = (self.finality_depth as usize).saturating_add(1);
+        if path.len() <= keep {
+            return Vec::new();
+        }
+        let finalized = &path[..path.len() - keep];
+        let new_root = *finalized.last().expect("not empty");
+
+        // Collect all descendants of the new root.
+        let mut retained = HashMap::<B256, Journal<'a>>::default();
+        let mut queue = vec![new_root];
+        while let Some(parent) = queue.pop() {
+            for child in self.children.get(&parent).into_iter().flatten() {
+                queue.push(*child);
+                retained.insert(*child, self.journals.remove(child).expect("child is in tree"));
+            }
+        }
+
+        let finalized = finalized
+            .iter()
+            .map(|hash| self.journals.remove(hash).expect("canonical journal is in tree"))
+            .collect::<Vec<_>>();
+
+        self.root_height = finalized.last().map(Journal::rollup_height);
+        self.root_hash = new_root;
+        self.journals = retained;
+        self.children.retain(|parent, _| *parent == new_root || self.journals.contains_key(parent));
+
+        finalized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{HostJournal, JournalMeta};
+    use alloy::consensus::Header;
+    use std::borrow::Cow;
+
+    fn journal(host: u64, rollup: u64, prev_hash: B256, tag: u64) -> Journal<'static> {
+        // the tag makes journals at the same heights distinct
+        let meta = JournalMeta::new(
+            host,
+            prev_hash,
+            Cow::Owned(Header { number: rollup, timestamp: tag, ..Default::default() }),
+        );
+        Journal::V1(HostJournal::new(meta, Default::default()))
+    }
+
+    fn hashes(journals: &[Journal<'_>]) -> Vec<B256> {
+        journals.iter().map(Journal::journal_hash).collect()
+    }
+
+    #[test]
+    fn extends_and_reorgs() {
+        let mut tree = JournalTree::new();
+
+        let a0 = journal(100, 0, GENESIS_JOURNAL_HASH, 0);
+        let a1 = journal(101, 1, a0.journal_hash(), 0);
+        let a2 = journal(102, 2, a1.journal_hash(), 0);
+
+        let event = tree.insert(a0.clone()).unwrap().unwrap();
+        assert!(!event.is_reorg());
+        assert_eq!(event.common_ancestor, GENESIS_JOURNAL_HASH);
+        assert_eq!(hashes(&event.added), hashes(std::slice::from_ref(&a0)));
+
+        tree.insert(a1.clone()).unwrap().unwrap();
+        tree.insert(a2.clone()).unwrap().unwrap();
+        assert_eq!(tree.head_hash(), a2.journal_hash());
+
+        // A competing branch at a lower host height does not become canonical
+        let b1 = journal(101, 1, a0.journal_hash(), 1);
+        assert_eq!(tree.insert(b1.clone()).unwrap(), None);
+        assert_eq!(tree.head_hash(), a2.journal_hash());
+        assert_eq!(tree.tips().count(), 2);
+
+        // Until it overtakes the canonical branch
+        let b2 = journal(102, 2, b1.journal_hash(), 1);
+        assert_eq!(tree.insert(b2.clone()).unwrap(), None);
+        let b3 = journal(103, 3, b2.journal_hash(), 1);
+        let event = tree.insert(b3.clone()).unwrap().unwrap();
+        assert!(event.is_reorg());
+        assert_eq!(event.common_ancestor, a0.journal_hash());
+        assert_eq!(hashes(&event.removed), hashes(&[a1, a2]));
+        assert_eq!(hashes(&event.added), hashes(&[b1, b2, b3.clone()]));
+
+        let set = tree.canonical_set();
+        assert_eq!(set.range(), Some(0..=3));
+        assert_eq!(set.latest_hash(), Some(b3.journal_hash()));
+
+        // duplicate insert is a no-op
+        assert_eq!(tree.insert(b3).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_unknown_parent_and_wrong_height() {
+        let mut tree = JournalTree::new();
+        let a0 = journal(100, 0, GENESIS_JOURNAL_HASH, 0);
+        tree.insert(a0.clone()).unwrap();
+
+        let orphan = journal(101, 1, B256::repeat_byte(3), 0);
+        assert!(matches!(tree.insert(orphan), Err(JournalTreeError::UnknownParent { .. })));
+
+        let skipped = journal(101, 2, a0.journal_hash(), 0);
+        assert!(matches!(tree.insert(skipped), Err(JournalTreeError::WrongHeight { .. })));
+    }
+
+    #[test]
+    fn custom_fork_choice() {
+        // prefer the branch with the lowest host height at each rollup height
+        let rule = |a: &Journal<'_>, b: &Journal<'_>| {
+            a.rollup_height().cmp(&b.rollup_height()).then(b.host_height().cmp(&a.host_height()))
+        };
+        let mut tree = JournalTree::with_fork_choice(GENESIS_JOURNAL_HASH, None, rule);
+
+        let a0 = journal(105, 0, GENESIS_JOURNAL_HASH, 0);
+        let b0 = journal(100, 0, GENESIS_JOURNAL_HASH, 1);
+        tree.insert(a0).unwrap().unwrap();
+        let event = tree.insert(b0.clone()).unwrap().unwrap();
+        assert!(event.is_reorg());
+        assert_eq!(tree.head_hash(), b0.journal_hash());
+    }
+
+    #[test]
+    fn prune_below_finality() {
+        let mut tree = JournalTree::new().with_finality_depth(2);
+
+        let mut prev = GENESIS_JOURNAL_HASH;
+        let mut canonical = vec![];
+        for i in 0..5 {
+            let j = journal(100 + i, i, prev, 0);
+            prev = j.journal_hash();
+            tree.insert(j.clone()).unwrap();
+            canonical.push(j);
+        }
+        // A stale branch off journal 0
+        let stale = journal(101, 1, canonical[0].journal_hash(), 9);
+        tree.insert(stale.clone()).unwrap();
+        // A live branch off journal 3
+        let live = journal(104, 4, canonical[3].journal_hash(), 9);
+        tree.insert(live.clone()).unwrap();
+        assert_eq!(tree.len(), 7);
+
+        let finalized = tree.prune();
+        assert_eq!(hashes(&finalized), hashes(&canonical[..2]));
+        assert_eq!(tree.root_hash(), canonical[1].journal_hash());
+        assert_eq!(tree.root_height(), Some(1));
+        assert!(!tree.contains(stale.journal_hash()));
+        assert!(tree.contains(live.journal_hash()));
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.head_hash(), canonical[4].journal_hash());
+
+        // pruning again finalizes nothing
+        assert!(tree.prune().is_empty());
+
+        // journals must now build on the new root or its descendants
+        let below_root = journal(101, 1, canonical[0].journal_hash(), 7);
+        assert!(tree.insert(below_root).is_err());
+    }
+}
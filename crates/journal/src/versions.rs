@@ -1,6 +1,9 @@
-use crate::HostJournal;
-use alloy::{consensus::Header, primitives::B256};
-use trevm::journal::{JournalDecode, JournalDecodeError, JournalEncode};
+use crate::{HostJournal, HostJournalV2, JournalMeta};
+use alloy::{
+    consensus::{Header, ReceiptEnvelope},
+    primitives::B256,
+};
+use trevm::journal::{BundleStateIndex, JournalDecode, JournalDecodeError, JournalEncode};
 
 /// Journal versions.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -8,13 +11,32 @@ use trevm::journal::{JournalDecode, JournalDecodeError, JournalEncode};
 pub enum Journal<'a> {
     /// Version 1
     V1(HostJournal<'a>),
+    /// Version 2, with receipts and optional state diff compression.
+    V2(HostJournalV2<'a>),
 }
 
 impl<'a> Journal<'a> {
+    /// Get the journal version.
+    pub const fn version(&self) -> u8 {
+        match self {
+            Journal::V1(_) => 1,
+            Journal::V2(_) => 2,
+        }
+    }
+
+    /// Get the journal meta.
+    pub const fn meta(&self) -> &JournalMeta<'a> {
+        match self {
+            Journal::V1(journal) => journal.meta(),
+            Journal::V2(journal) => journal.meta(),
+        }
+    }
+
     /// Get the host height.
     pub const fn host_height(&self) -> u64 {
         match self {
             Journal::V1(journal) => journal.host_height(),
+            Journal::V2(journal) => journal.host_height(),
         }
     }
 
@@ -22,6 +44,7 @@ impl<'a> Journal<'a> {
     pub const fn prev_journal_hash(&self) -> B256 {
         match self {
             Journal::V1(journal) => journal.prev_journal_hash(),
+            Journal::V2(journal) => journal.prev_journal_hash(),
         }
     }
 
@@ -29,13 +52,56 @@ impl<'a> Journal<'a> {
     pub fn header(&self) -> &Header {
         match self {
             Journal::V1(journal) => journal.header(),
+            Journal::V2(journal) => journal.header(),
         }
     }
 
     /// Get a reference to the host journal.
+    ///
+    /// Use [`Journal::meta`] and [`Journal::state_diff`] to access the
+    /// contents of a journal of any version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a V1 journal. Use [`Journal::journal_v1`] or
+    /// [`Journal::journal_v2`] to access the journal of a specific version.
     pub const fn journal(&self) -> &HostJournal<'a> {
         match self {
             Journal::V1(journal) => journal,
+            Journal::V2(_) => panic!("not a V1 journal"),
+        }
+    }
+
+    /// Get a reference to the V1 host journal, if this is a V1 journal.
+    pub const fn journal_v1(&self) -> Option<&HostJournal<'a>> {
+        match self {
+            Journal::V1(journal) => Some(journal),
+            Journal::V2(_) => None,
+        }
+    }
+
+    /// Get a reference to the V2 host journal, if this is a V2 journal.
+    pub const fn journal_v2(&self) -> Option<&HostJournalV2<'a>> {
+        match self {
+            Journal::V1(_) => None,
+            Journal::V2(journal) => Some(journal),
+        }
+    }
+
+    /// Get the state diff.
+    pub const fn state_diff(&self) -> &BundleStateIndex<'a> {
+        match self {
+            Journal::V1(journal) => journal.journal(),
+            Journal::V2(journal) => journal.journal(),
+        }
+    }
+
+    /// Get the block receipts. V1 journals do not contain receipts, so this
+    /// is always empty for them.
+    pub fn receipts(&self) -> &[ReceiptEnvelope] {
+        match self {
+            Journal::V1(_) => &[],
+            Journal::V2(journal) => journal.receipts(),
         }
     }
 
@@ -43,6 +109,7 @@ impl<'a> Journal<'a> {
     pub fn journal_hash(&self) -> B256 {
         match self {
             Journal::V1(journal) => journal.journal_hash(),
+            Journal::V2(journal) => journal.journal_hash(),
         }
     }
 
@@ -50,24 +117,37 @@ impl<'a> Journal<'a> {
     pub fn rollup_height(&self) -> u64 {
         match self {
             Journal::V1(journal) => journal.rollup_height(),
+            Journal::V2(journal) => journal.rollup_height(),
         }
     }
 }
 
+impl<'a> From<HostJournal<'a>> for Journal<'a> {
+    fn from(journal: HostJournal<'a>) -> Self {
+        Journal::V1(journal)
+    }
+}
+
+impl<'a> From<HostJournalV2<'a>> for Journal<'a> {
+    fn from(journal: HostJournalV2<'a>) -> Self {
+        Journal::V2(journal)
+    }
+}
+
 impl JournalEncode for Journal<'_> {
     fn serialized_size(&self) -> usize {
         // 1 byte for the version
         1 + match self {
             Journal::V1(journal) => journal.serialized_size(),
+            Journal::V2(journal) => journal.serialized_size(),
         }
     }
 
     fn encode(&self, buf: &mut dyn alloy::rlp::BufMut) {
+        self.version().encode(buf);
         match self {
-            Journal::V1(journal) => {
-                1u8.encode(buf);
-                journal.encode(buf)
-            }
+            Journal::V1(journal) => journal.encode(buf),
+            Journal::V2(journal) => journal.encode(buf),
         }
     }
 }
@@ -77,10 +157,11 @@ impl JournalDecode for Journal<'static> {
         let version: u8 = JournalDecode::decode(buf)?;
         match version {
             1 => JournalDecode::decode(buf).map(Journal::V1),
+            2 => JournalDecode::decode(buf).map(Journal::V2),
             _ => Err(JournalDecodeError::InvalidTag {
                 ty_name: "Journal",
                 tag: version,
-                max_expected: 1,
+                max_expected: 2,
             }),
         }
     }
@@ -89,7 +170,10 @@ impl JournalDecode for Journal<'static> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{host::test::make_state_diff, JournalMeta};
+    use crate::{
+        host::test::make_state_diff, host_v2::test::make_journal_v2, JournalCompression,
+        JournalMeta,
+    };
     use std::borrow::Cow;
 
     #[test]
@@ -102,5 +186,21 @@ mod test {
         journal.encode(&mut buf);
         let decoded = Journal::decode(&mut &buf[..]).unwrap();
         assert_eq!(journal, decoded);
+        assert_eq!(decoded.journal().host_height(), 42);
+        assert!(decoded.journal_v2().is_none());
+    }
+
+    #[test]
+    fn roundtrip_v2() {
+        for compression in [JournalCompression::None, JournalCompression::Zstd] {
+            let journal = Journal::V2(make_journal_v2(compression));
+            let buf = journal.encoded();
+            assert_eq!(buf[0], 2);
+            let decoded = Journal::decode(&mut &buf[..]).unwrap();
+            assert_eq!(journal, decoded);
+            assert_eq!(decoded.receipts().len(), 2);
+            assert!(decoded.journal_v1().is_none());
+            assert_eq!(decoded.journal_v2().map(HostJournalV2::compression), Some(compression));
+        }
     }
 }
@@ -0,0 +1,65 @@
+//! Compares the throughput of running simulation jobs on a persistent
+//! [`SimPool`] against spawning a thread per job, as simulation rounds did
+//! before the pool was introduced.
+//!
+//! Each job hashes a buffer repeatedly, standing in for the CPU work of
+//! simulating an item.
+
+use alloy::primitives::keccak256;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use signet_sim::SimPool;
+use std::{hint::black_box, sync::mpsc};
+
+/// The number of hashes per job.
+const JOB_HASHES: usize = 500;
+
+/// The number of worker threads in the pool.
+const POOL_SIZE: usize = 8;
+
+fn job() {
+    let mut hash = keccak256([0u8; 32]);
+    for _ in 0..JOB_HASHES {
+        hash = keccak256(hash);
+    }
+    black_box(hash);
+}
+
+fn thread_per_job(items: usize) {
+    std::thread::scope(|scope| {
+        for _ in 0..items {
+            scope.spawn(job);
+        }
+    });
+}
+
+fn pool(pool: &SimPool, items: usize) {
+    let (done, done_rx) = mpsc::channel();
+    for _ in 0..items {
+        let done = done.clone();
+        pool.spawn(move || {
+            job();
+            let _ = done.send(());
+        });
+    }
+    drop(done);
+    done_rx.iter().for_each(drop);
+}
+
+fn bench_round(c: &mut Criterion) {
+    let sim_pool = SimPool::new(POOL_SIZE);
+    let mut group = c.benchmark_group("sim_round");
+
+    for items in [8, 64, 256] {
+        group.throughput(Throughput::Elements(items as u64));
+        group.bench_with_input(BenchmarkId::new("thread_per_job", items), &items, |b, &items| {
+            b.iter(|| thread_per_job(items))
+        });
+        group.bench_with_input(BenchmarkId::new("pool", items), &items, |b, &items| {
+            b.iter(|| pool(&sim_pool, items))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_round);
+criterion_main!(benches);
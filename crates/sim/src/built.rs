@@ -1,13 +1,17 @@
-use crate::{outcome::SimulatedItem, SimItem};
+use crate::{env::merge_cache, outcome::SimulatedItem, Refund, SimItem, REFUND_TX_GAS};
 use alloy::{
-    consensus::{transaction::Recovered, SidecarBuilder, SidecarCoder, TxEnvelope},
-    primitives::{keccak256, Bytes, B256},
+    consensus::{
+        transaction::Recovered, ReceiptEnvelope, SidecarBuilder, SidecarCoder, TxEnvelope,
+    },
+    primitives::{keccak256, Bytes, Log, B256},
 };
 use core::fmt;
 use signet_bundle::RecoveredBundle;
+use signet_types::{AggregateFills, AggregateOrders};
 use signet_zenith::{encode_txns, Alloy2718Coder};
 use std::sync::{Arc, OnceLock};
 use tracing::trace;
+use trevm::revm::database::Cache;
 
 /// A block that has been built by the simulator.
 #[derive(Clone, Default)]
@@ -27,6 +31,16 @@ pub struct BuiltBlock {
     /// The amount of host gas used by the block so far
     pub(crate) host_gas_used: u64,
 
+    /// Receipts of the simulated transactions in the block.
+    pub(crate) receipts: Vec<ReceiptEnvelope>,
+
+    /// The aggregates and state changes of the simulated items in the block,
+    /// created on first use so that [`Self::new`] is const.
+    pub(crate) state: OnceLock<BlockState>,
+
+    /// The refunds owed by the simulated bundles in the block.
+    pub(crate) refunds: Vec<Refund>,
+
     // -- Memoization fields --
     /// Memoized raw encoding of the block.
     pub(crate) raw_encoding: OnceLock<Bytes>,
@@ -41,11 +55,63 @@ impl fmt::Debug for BuiltBlock {
             .field("host_txns", &self.host_txns.len())
             .field("gas_used", &self.gas_used)
             .field("host_gas_used", &self.host_gas_used)
+            .field("receipts", &self.receipts.len())
+            .field("refunds", &self.refunds.len())
             .field("block_number", &self.block_number)
             .finish_non_exhaustive()
     }
 }
 
+/// The aggregate fills and orders, and the combined state changes, of the
+/// simulated items in a [`BuiltBlock`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BlockState {
+    /// The aggregate fills of the simulated items.
+    fills: AggregateFills,
+    /// The aggregate orders of the simulated items.
+    orders: AggregateOrders,
+    /// The combined rollup state changes of the simulated items.
+    rollup_state: Cache,
+    /// The combined host state changes of the simulated items.
+    host_state: Cache,
+}
+
+/// A checkpoint of a [`BuiltBlock`], to roll back to with
+/// [`BuiltBlock::rollback`].
+#[derive(Debug, Clone)]
+pub struct BlockCheckpoint {
+    host_txns: usize,
+    transactions: usize,
+    gas_used: u64,
+    host_gas_used: u64,
+    receipts: usize,
+    state: BlockState,
+    refunds: usize,
+}
+
+impl BlockCheckpoint {
+    /// Get the number of transactions in the block at the checkpoint.
+    pub const fn tx_count(&self) -> usize {
+        self.transactions
+    }
+
+    /// Get the amount of gas used by the block at the checkpoint.
+    pub const fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    /// Get the amount of host gas used by the block at the checkpoint.
+    pub const fn host_gas_used(&self) -> u64 {
+        self.host_gas_used
+    }
+
+    /// Get the amount of gas reserved for the refunds owed by the block at
+    /// the checkpoint.
+    pub const fn refund_gas(&self) -> u64 {
+        self.refunds as u64 * REFUND_TX_GAS
+    }
+}
+
 impl BuiltBlock {
     /// Create a new `BuiltBlock`
     pub const fn new(block_number: u64) -> Self {
@@ -55,6 +121,9 @@ impl BuiltBlock {
             block_number,
             gas_used: 0,
             host_gas_used: 0,
+            receipts: Vec::new(),
+            state: OnceLock::new(),
+            refunds: Vec::new(),
             raw_encoding: OnceLock::new(),
             hash: OnceLock::new(),
         }
@@ -96,6 +165,101 @@ impl BuiltBlock {
         self.host_txns.as_slice()
     }
 
+    /// Get the receipts of the simulated transactions in the block, in block
+    /// order. The cumulative gas used is relative to the first transaction
+    /// of the block.
+    ///
+    /// Transactions ingested directly with [`Self::ingest_tx`] or
+    /// [`Self::ingest_bundle`] have no receipts. Bundle transactions whose
+    /// state changes were discarded, e.g. reverting transactions with
+    /// insufficient fills, have no receipts either.
+    #[allow(clippy::missing_const_for_fn)] // false positive, const deref
+    pub fn receipts(&self) -> &[ReceiptEnvelope] {
+        &self.receipts
+    }
+
+    /// Get the logs emitted by the simulated transactions in the block, in
+    /// block order.
+    pub fn logs(&self) -> impl Iterator<Item = &Log> {
+        self.receipts.iter().flat_map(ReceiptEnvelope::logs)
+    }
+
+    /// Get the aggregate fills of the simulated items in the block.
+    pub fn fills(&self) -> &AggregateFills {
+        &self.state().fills
+    }
+
+    /// Get the aggregate orders of the simulated items in the block.
+    pub fn orders(&self) -> &AggregateOrders {
+        &self.state().orders
+    }
+
+    /// Get the combined rollup state changes of the simulated items in the
+    /// block.
+    pub fn rollup_state(&self) -> &Cache {
+        &self.state().rollup_state
+    }
+
+    /// Get the combined host state changes of the simulated items in the
+    /// block.
+    pub fn host_state(&self) -> &Cache {
+        &self.state().host_state
+    }
+
+    /// Get the aggregates and state changes of the simulated items.
+    fn state(&self) -> &BlockState {
+        self.state.get_or_init(BlockState::default)
+    }
+
+    /// Get a mutable reference to the aggregates and state changes of the
+    /// simulated items.
+    fn state_mut(&mut self) -> &mut BlockState {
+        self.state.get_or_init(BlockState::default);
+        self.state.get_mut().expect("initialized")
+    }
+
+    /// Get the refunds owed by the simulated bundles in the block, in block
+    /// order. See [`BlockBuild::with_refund_wallet`] to pay them.
+    ///
+    /// [`BlockBuild::with_refund_wallet`]: crate::BlockBuild::with_refund_wallet
+    #[allow(clippy::missing_const_for_fn)] // false positive, const deref
+    pub fn refunds(&self) -> &[Refund] {
+        &self.refunds
+    }
+
+    /// Get the amount of gas reserved for the refund transactions of the
+    /// refunds owed by the block.
+    pub const fn refund_gas(&self) -> u64 {
+        self.refunds.len() as u64 * REFUND_TX_GAS
+    }
+
+    /// Checkpoint the contents of the block.
+    pub fn checkpoint(&self) -> BlockCheckpoint {
+        BlockCheckpoint {
+            host_txns: self.host_txns.len(),
+            transactions: self.transactions.len(),
+            gas_used: self.gas_used,
+            host_gas_used: self.host_gas_used,
+            receipts: self.receipts.len(),
+            state: self.state().clone(),
+            refunds: self.refunds.len(),
+        }
+    }
+
+    /// Roll back to a checkpoint of this block, discarding the transactions,
+    /// receipts, aggregate fills and orders, and state changes of the items
+    /// ingested since.
+    pub fn rollback(&mut self, checkpoint: &BlockCheckpoint) {
+        self.unseal();
+        self.host_txns.truncate(checkpoint.host_txns);
+        self.transactions.truncate(checkpoint.transactions);
+        self.gas_used = checkpoint.gas_used;
+        self.host_gas_used = checkpoint.host_gas_used;
+        self.receipts.truncate(checkpoint.receipts);
+        self.state = OnceLock::from(checkpoint.state.clone());
+        self.refunds.truncate(checkpoint.refunds);
+    }
+
     /// Unseal the block
     pub(crate) fn unseal(&mut self) {
         self.raw_encoding.take();
@@ -126,18 +290,39 @@ impl BuiltBlock {
 
         self.unseal();
         // extend the transactions with the decoded transactions.
-        // As this builder does not provide bundles landing "top of block", its fine to just extend.
+        // Placement constraints are checked when items are accepted, in the
+        // order they are ingested, so it's fine to just extend.
         self.transactions.extend(bundle.drain_txns());
         self.host_txns.extend(bundle.drain_host_txns());
     }
 
-    /// Ingest a simulated item, extending the block.
+    /// Ingest a simulated item, extending the block, and collecting its
+    /// receipts, aggregate fills and orders, and state changes.
     pub fn ingest(&mut self, item: SimulatedItem) {
+        let gas_offset = self.gas_used;
         self.gas_used += item.gas_used;
         self.host_gas_used += item.host_gas_used;
 
+        self.receipts.extend(item.receipts.into_iter().map(|mut receipt| {
+            if let Some(receipt) = receipt.as_receipt_with_bloom_mut() {
+                receipt.receipt.cumulative_gas_used += gas_offset;
+            }
+            receipt
+        }));
+        let state = self.state_mut();
+        state.fills.absorb(&item.bundle_fills);
+        state.orders.absorb(&item.bundle_orders);
+        merge_cache(&mut state.rollup_state, &item.rollup_cache);
+        merge_cache(&mut state.host_state, &item.host_cache);
+        self.refunds.extend(item.refund);
+
         match item.item {
-            SimItem::Bundle(bundle) => self.ingest_bundle(Arc::unwrap_or_clone(bundle)),
+            SimItem::Bundle(bundle) => {
+                // Dropped transactions are not included in the block.
+                let mut bundle = Arc::unwrap_or_clone(bundle);
+                bundle.remove_txs(&item.dropped_txs);
+                self.ingest_bundle(bundle)
+            }
             SimItem::Tx(tx) => self.ingest_tx(Arc::unwrap_or_clone(tx)),
         }
     }
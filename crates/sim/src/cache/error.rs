@@ -1,3 +1,5 @@
+use alloy::primitives::Address;
+
 /// Possible errors that can occur when using the cache.
 #[derive(Debug, thiserror::Error)]
 pub enum CacheError {
@@ -5,6 +7,38 @@ pub enum CacheError {
     #[error("bundle has no replacement UUID")]
     BundleWithoutReplacementUuid,
 
+    /// The bundle update has a lower replacement nonce than a previous update
+    /// with the same replacement UUID.
+    #[error("stale update for bundle {uuid}: replacement nonce {nonce} is below {latest}")]
+    StaleBundleUpdate {
+        /// The replacement UUID of the bundle.
+        uuid: String,
+        /// The replacement nonce of the update.
+        nonce: u64,
+        /// The highest replacement nonce seen for the bundle.
+        latest: u64,
+    },
+
+    /// The transaction would replace a cached transaction with the same
+    /// sender and nonce, but does not sufficiently increase its fees.
+    #[error("replacement transaction underpriced: sender {sender}, nonce {nonce}")]
+    ReplacementUnderpriced {
+        /// The sender of the transaction.
+        sender: Address,
+        /// The nonce of the transaction.
+        nonce: u64,
+    },
+
+    /// The sender already has the maximum number of cached transactions, all
+    /// with lower nonces than the transaction.
+    #[error("too many transactions from sender {sender}: limit is {slots}")]
+    SenderSlotsFull {
+        /// The sender of the transaction.
+        sender: Address,
+        /// The maximum number of cached transactions per sender.
+        slots: usize,
+    },
+
     /// Error recovering a transaction.
     #[error(transparent)]
     TxRecover(#[from] alloy::consensus::crypto::RecoveryError),
@@ -7,8 +7,10 @@ use alloy::{
         transaction::{Recovered, SignerRecoverable},
         Transaction, TxEnvelope,
     },
-    primitives::{TxHash, U256},
+    eips::{Decodable2718, Encodable2718},
+    primitives::{Bytes, TxHash, U256},
 };
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use signet_bundle::{RecoveredBundle, SignetEthBundle, TxRequirement};
 use std::{
     borrow::{Borrow, Cow},
@@ -18,6 +20,10 @@ use std::{
 use tracing::{instrument, trace, trace_span};
 
 /// An item that can be simulated, wrapped in an Arc for cheap cloning.
+///
+/// Items are serialized as `{"bundle": {...}}`, with the bundle as a
+/// [`SignetEthBundle`], or as `{"tx": "0x..."}`, with the EIP-2718 encoding
+/// of the transaction. Signers are recovered when deserializing.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SimItem {
     /// A bundle to be simulated.
@@ -26,6 +32,35 @@ pub enum SimItem {
     Tx(Arc<Recovered<TxEnvelope>>),
 }
 
+/// The serialized form of a [`SimItem`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum SerializedItem {
+    Bundle(SignetEthBundle),
+    Tx(Bytes),
+}
+
+impl Serialize for SimItem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Bundle(bundle) => SerializedItem::Bundle(bundle.as_ref().into()),
+            Self::Tx(tx) => SerializedItem::Tx(tx.encoded_2718().into()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SimItem {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match SerializedItem::deserialize(deserializer)? {
+            SerializedItem::Bundle(bundle) => Self::try_from(bundle).map_err(D::Error::custom),
+            SerializedItem::Tx(tx) => TxEnvelope::decode_2718(&mut tx.as_ref())
+                .map_err(D::Error::custom)
+                .and_then(|tx| Self::try_from(tx).map_err(D::Error::custom)),
+        }
+    }
+}
+
 impl TryFrom<SignetEthBundle> for SimItem {
     type Error = CacheError;
 
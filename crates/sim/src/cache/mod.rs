@@ -8,7 +8,7 @@ mod state;
 pub use state::{AcctInfo, ProviderStateSource, StateSource};
 
 mod store;
-pub use store::SimCache;
+pub use store::{CacheEvent, SimCache, SimCacheSnapshot, DEFAULT_PRICE_BUMP, DEFAULT_SENDER_SLOTS};
 
 mod validity;
 pub use validity::{check_bundle_tx_list, SimItemValidity};
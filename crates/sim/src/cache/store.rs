@@ -1,11 +1,19 @@
-use crate::cache::{CacheError, SimIdentifier, SimItem, SimItemValidity, StateSource};
-use alloy::consensus::{transaction::Recovered, TxEnvelope};
+use crate::{
+    cache::{CacheError, SimIdentifier, SimItem, SimItemValidity, StateSource},
+    DefaultScoring, ScoringPolicy,
+};
+use alloy::{
+    consensus::{transaction::Recovered, Transaction, TxEnvelope},
+    primitives::Address,
+};
 use core::fmt;
 use lru::LruCache;
 use parking_lot::RwLock;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use signet_bundle::{RecoveredBundle, SignetEthBundle};
 use std::{
-    collections::{BTreeMap, HashSet},
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
     mem::MaybeUninit,
     num::NonZeroUsize,
     ops::Deref,
@@ -13,16 +21,51 @@ use std::{
 };
 use tracing::{instrument, Span};
 
+/// The default minimum fee increase, in percent, for a transaction to replace
+/// a cached transaction with the same sender and nonce.
+pub const DEFAULT_PRICE_BUMP: u64 = 10;
+
+/// The default maximum number of cached transactions per sender.
+pub const DEFAULT_SENDER_SLOTS: usize = 16;
+
 /// A cache for the simulator.
 ///
-/// This cache is used to store the items that are being simulated.
-#[derive(Clone)]
-pub struct SimCache {
+/// This cache is used to store the items that are being simulated. Items are
+/// ordered by the rank assigned by the cache's [`ScoringPolicy`] when they are
+/// added.
+///
+/// Bundles are identified by their replacement UUID. Adding a bundle with the
+/// same UUID as a cached bundle replaces it, and adding a bundle with the same
+/// UUID and no transactions cancels it. If bundles carry a
+/// [replacement nonce], updates with a lower nonce than a previous update for
+/// the same UUID are rejected as stale.
+///
+/// Transactions are identified by their hash. With [sender queues] enabled,
+/// transactions are instead queued per sender, by nonce. Only the lowest-nonce
+/// transaction of each sender is simulated, and the next transaction is
+/// promoted once it is removed from the cache, e.g. when it is included in the
+/// block. A transaction with the same sender and nonce as a cached transaction
+/// replaces it if it increases both the max fee and the priority fee by at
+/// least the [price bump], and is rejected otherwise. Each sender may have at
+/// most a fixed number of [queued transactions].
+///
+/// [replacement nonce]: signet_bundle::SignetEthBundle::replacement_nonce
+/// [sender queues]: Self::with_sender_queues
+/// [price bump]: Self::with_price_bump
+/// [queued transactions]: Self::with_sender_slots
+pub struct SimCache<P = DefaultScoring> {
     inner: Arc<RwLock<CacheStore>>,
     capacity: usize,
+    policy: Arc<P>,
+}
+
+impl<P> Clone for SimCache<P> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), capacity: self.capacity, policy: self.policy.clone() }
+    }
 }
 
-impl fmt::Debug for SimCache {
+impl<P> fmt::Debug for SimCache<P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SimCache").finish()
     }
@@ -37,12 +80,59 @@ impl Default for SimCache {
 impl SimCache {
     /// Create a new `SimCache` instance, with a default capacity of `100`.
     pub fn new() -> Self {
-        Self { inner: Arc::new(RwLock::new(CacheStore::new())), capacity: 100 }
+        Self::with_capacity(100)
     }
 
     /// Create a new `SimCache` instance with a given capacity.
     pub fn with_capacity(capacity: usize) -> Self {
-        Self { inner: Arc::new(RwLock::new(CacheStore::new())), capacity }
+        Self::with_policy(capacity, DefaultScoring)
+    }
+}
+
+impl<P: ScoringPolicy> SimCache<P> {
+    /// Create a new `SimCache` instance with a given capacity, ranking items
+    /// with the given [`ScoringPolicy`].
+    pub fn with_policy(capacity: usize, policy: P) -> Self {
+        Self { inner: Arc::new(RwLock::new(CacheStore::new())), capacity, policy: Arc::new(policy) }
+    }
+
+    /// Get a reference to the [`ScoringPolicy`] used to rank items.
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /// Queue transactions per sender, by nonce, replacing transactions with
+    /// the same sender and nonce by fee. Disabled by default.
+    ///
+    /// # Panics
+    ///
+    /// If the cache is not empty, as the cached transactions would not be
+    /// queued.
+    pub fn with_sender_queues(self) -> Self {
+        let mut inner = self.inner.write();
+        assert!(inner.items.is_empty(), "sender queues must be enabled on an empty cache");
+        inner.sender_queues = true;
+        drop(inner);
+        self
+    }
+
+    /// Set the minimum fee increase, in percent, for a transaction to replace
+    /// a cached transaction with the same sender and nonce, if
+    /// [sender queues] are enabled. Defaults to [`DEFAULT_PRICE_BUMP`].
+    ///
+    /// [sender queues]: Self::with_sender_queues
+    pub fn with_price_bump(self, price_bump: u64) -> Self {
+        self.inner.write().price_bump = price_bump;
+        self
+    }
+
+    /// Set the maximum number of cached transactions per sender, if
+    /// [sender queues] are enabled. Defaults to [`DEFAULT_SENDER_SLOTS`].
+    ///
+    /// [sender queues]: Self::with_sender_queues
+    pub fn with_sender_slots(self, sender_slots: NonZeroUsize) -> Self {
+        self.inner.write().sender_slots = sender_slots;
+        self
     }
 
     /// Fill a buffer with up to its capacity
@@ -74,8 +164,25 @@ impl SimCache {
     ///
     /// The state sources are used to validate the items against the current
     /// nonce and balance, to prevent simulating invalid items.
+    pub async fn read_best_valid<S, S2>(
+        &self,
+        n: usize,
+        source: &S,
+        host_source: &S2,
+    ) -> Result<Vec<(u128, SimItem)>, Box<dyn std::error::Error>>
+    where
+        S: StateSource,
+        S2: StateSource,
+    {
+        self.read_best_valid_with(n, source, host_source, |_, _| {}).await
+    }
+
+    /// Get up to the `n` best valid items in the cache, as
+    /// [`Self::read_best_valid`], calling `on_check` with the validity of each
+    /// item checked.
     #[instrument(
         level = "debug",
+        name = "read_best_valid",
         skip_all,
         fields(
             candidates_total = tracing::field::Empty,
@@ -85,11 +192,12 @@ impl SimCache {
             never_count = tracing::field::Empty,
         )
     )]
-    pub async fn read_best_valid<S, S2>(
+    pub(crate) async fn read_best_valid_with<S, S2>(
         &self,
         n: usize,
         source: &S,
         host_source: &S2,
+        mut on_check: impl FnMut(&SimItem, SimItemValidity),
     ) -> Result<Vec<(u128, SimItem)>, Box<dyn std::error::Error>>
     where
         S: StateSource,
@@ -118,6 +226,7 @@ impl SimCache {
             checked += 1;
 
             let validity = item.check(source, host_source).await?;
+            on_check(item, validity);
 
             match validity {
                 SimItemValidity::Now => valid.push((*rank, item.clone())),
@@ -142,9 +251,10 @@ impl SimCache {
         Ok(valid)
     }
 
-    /// Get the number of items in the cache.
+    /// Get the number of items in the cache, including transactions queued
+    /// behind lower nonces.
     pub fn len(&self) -> usize {
-        self.inner.read().items.len()
+        self.inner.read().len()
     }
 
     /// True if the cache is empty.
@@ -158,75 +268,404 @@ impl SimCache {
     }
 
     /// Remove an item by key.
+    ///
+    /// If the item is a transaction, the next transaction from its sender is
+    /// promoted.
     pub fn remove(&self, cache_rank: u128) -> Option<SimItem> {
         let mut inner = self.inner.write();
+        inner.record(|| CacheEvent::Remove { cache_rank });
+        inner.remove(cache_rank)
+    }
+
+    /// Remove an item by key, if it is the given item. This prevents removing
+    /// an item that replaced the given item while it was being simulated.
+    pub(crate) fn remove_item(&self, cache_rank: u128, item: &SimItem) -> Option<SimItem> {
+        let mut inner = self.inner.write();
+        if inner.items.get(&cache_rank) != Some(item) {
+            return None;
+        }
         inner.remove(cache_rank)
     }
 
     /// Remove an item by key, and prevent it from being re-added for a while.
     pub fn remove_and_disallow(&self, cache_rank: u128) -> Option<SimItem> {
         let mut inner = self.inner.write();
+        inner.record(|| CacheEvent::RemoveAndDisallow { cache_rank });
         inner.remove_and_disallow(cache_rank)
     }
 
+    /// Get a bundle by its replacement UUID.
+    pub fn get_bundle(&self, replacement_uuid: &str) -> Option<SimItem> {
+        let inner = self.inner.read();
+        inner.bundle_rank(replacement_uuid).and_then(|rank| inner.items.get(&rank).cloned())
+    }
+
     /// Add a bundle to the cache.
+    ///
+    /// If a bundle with the same replacement UUID is cached, it is replaced.
+    /// If the bundle has no transactions, the cached bundle is cancelled
+    /// instead.
+    ///
+    /// # Errors
+    ///
+    /// - [`CacheError::BundleWithoutReplacementUuid`] if the bundle has no
+    ///   replacement UUID.
+    /// - [`CacheError::StaleBundleUpdate`] if the bundle's replacement nonce
+    ///   is lower than that of a previous update.
+    /// - [`CacheError::BundleRecover`] if the bundle's transactions cannot be
+    ///   recovered.
     pub fn add_bundle(&self, bundle: SignetEthBundle, basefee: u64) -> Result<(), CacheError> {
-        if bundle.replacement_uuid().is_none() {
+        let Some(uuid) = bundle.replacement_uuid() else {
             // If the bundle does not have a replacement UUID, we cannot add it to the cache.
             return Err(CacheError::BundleWithoutReplacementUuid);
+        };
+
+        if bundle.txs().is_empty() {
+            let mut inner = self.inner.write();
+            return inner.cancel_bundle(uuid, bundle.replacement_nonce()).map(drop);
         }
 
         let item = SimItem::try_from(bundle)?;
-        let cache_rank = item.calculate_total_fee(basefee);
+        let cache_rank = self.policy.cache_rank(&item, basefee);
 
         let mut inner = self.inner.write();
-        inner.add_inner(cache_rank, item, self.capacity);
+        inner.add_bundle(cache_rank, item, self.capacity)
+    }
 
-        Ok(())
+    /// Cancel a bundle by its replacement UUID, returning it if it was
+    /// cached.
+    ///
+    /// Unlike adding an empty bundle via [`Self::add_bundle`], this ignores
+    /// replacement nonces.
+    pub fn cancel_bundle(&self, replacement_uuid: &str) -> Option<SimItem> {
+        let mut inner = self.inner.write();
+        inner.cancel_bundle(replacement_uuid, None).ok().flatten()
     }
 
     /// Add an iterator of bundles to the cache. This locks the cache only once
     ///
     /// Bundles added should have a valid replacement UUID. Bundles without a replacement UUID will be skipped.
+    /// Bundles replace and cancel cached bundles as in [`Self::add_bundle`].
+    /// Stale updates are skipped.
     pub fn add_bundles<I, Item>(&self, item: I, basefee: u64)
     where
         I: IntoIterator<Item = Item>,
         Item: Into<RecoveredBundle>,
     {
         let mut inner = self.inner.write();
-        inner.add_bundles(item, basefee, self.capacity);
+        inner.add_bundles(item, |item| self.policy.cache_rank(item, basefee), self.capacity);
     }
 
     /// Add a transaction to the cache.
+    ///
+    /// Transactions that cannot be added are skipped. See
+    /// [`Self::try_add_tx`].
     pub fn add_tx(&self, tx: Recovered<TxEnvelope>, basefee: u64) {
+        let _ = self.try_add_tx(tx, basefee);
+    }
+
+    /// Add a transaction to the cache.
+    ///
+    /// If [sender queues] are enabled and a transaction with the same sender
+    /// and nonce is cached, it is replaced.
+    ///
+    /// # Errors
+    ///
+    /// - [`CacheError::ReplacementUnderpriced`] if the transaction would
+    ///   replace a cached transaction, but does not bump its fees by the
+    ///   [price bump].
+    /// - [`CacheError::SenderSlotsFull`] if the sender has the maximum number
+    ///   of cached transactions, and they all have lower nonces.
+    ///
+    /// [sender queues]: Self::with_sender_queues
+    /// [price bump]: Self::with_price_bump
+    pub fn try_add_tx(&self, tx: Recovered<TxEnvelope>, basefee: u64) -> Result<(), CacheError> {
         let item = SimItem::from(tx);
-        let cache_rank = item.calculate_total_fee(basefee);
+        let cache_rank = self.policy.cache_rank(&item, basefee);
 
         let mut inner = self.inner.write();
-        inner.add_inner(cache_rank, item, self.capacity);
+        inner.add_tx(cache_rank, item, self.capacity)
     }
 
     /// Add an iterator of transactions to the cache. This locks the cache only once
+    ///
+    /// Transactions replace cached transactions as in [`Self::try_add_tx`].
+    /// Transactions that cannot be added are skipped.
     pub fn add_txs<I>(&self, item: I, basefee: u64)
     where
         I: IntoIterator<Item = Recovered<TxEnvelope>>,
     {
         let mut inner = self.inner.write();
-        inner.add_txs(item, basefee, self.capacity);
+        inner.add_txs(item, |item| self.policy.cache_rank(item, basefee), self.capacity);
+    }
+
+    /// Mark an item as included in the block. This removes cached
+    /// transactions whose nonces are used by the item's transactions, and
+    /// promotes the next transaction from each of their senders.
+    pub fn mark_included(&self, item: &SimItem) {
+        let mut inner = self.inner.write();
+        match item {
+            SimItem::Bundle(bundle) => {
+                bundle.txs().iter().for_each(|tx| inner.mark_included(tx.signer(), tx.nonce()))
+            }
+            SimItem::Tx(tx) => inner.mark_included(tx.signer(), tx.nonce()),
+        }
     }
 
     /// Clean the cache by removing bundles that are not valid in the current
     /// block.
     pub fn clean(&self, block_number: u64, block_timestamp: u64) {
         let mut inner = self.inner.write();
+        inner.record(|| CacheEvent::Clean { block_number, block_timestamp });
         inner.clean(self.capacity, block_number, block_timestamp);
     }
 
     /// Clear the cache.
     pub fn clear(&self) {
         let mut inner = self.inner.write();
+        inner.record(|| CacheEvent::Clear);
         inner.clear();
     }
+
+    /// Get the number of items added to the cache so far. This increases
+    /// whenever an item is added, and is used to detect new arrivals.
+    pub(crate) fn arrivals(&self) -> u64 {
+        self.inner.read().arrivals
+    }
+
+    /// Return items taken out of the cache by the block build, e.g. items
+    /// displaced from the block, with their previous rank. Items that were
+    /// replaced or re-added in the meantime are skipped. This is not
+    /// recorded, as the block build makes the same changes when replayed.
+    ///
+    /// Returns the number of items added.
+    pub(crate) fn reinsert(&self, items: impl IntoIterator<Item = (u128, SimItem)>) -> u64 {
+        let mut inner = self.inner.write();
+        let journal = inner.journal.take();
+        let arrivals = inner.arrivals;
+        for (cache_rank, item) in items {
+            if item.as_tx().is_some() {
+                // Underpriced replacements and txs over the sender limit are
+                // skipped.
+                let _ = inner.add_tx(cache_rank, item, self.capacity);
+            } else {
+                // Unlike `add_bundle`, this does not replace an update of
+                // the bundle.
+                inner.add_inner(cache_rank, item, self.capacity);
+            }
+        }
+        inner.journal = journal;
+        inner.arrivals - arrivals
+    }
+
+    /// Snapshot the contents of the cache, and start recording the changes
+    /// made to it, until [`Self::stop_recording`].
+    pub(crate) fn start_recording(&self) -> SimCacheSnapshot {
+        let mut inner = self.inner.write();
+        inner.journal = Some(Vec::new());
+        SimCacheSnapshot { store: inner.snapshot(), capacity: self.capacity }
+    }
+
+    /// Take the changes recorded since the last call.
+    pub(crate) fn take_events(&self) -> Vec<CacheEvent> {
+        self.inner.write().journal.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Stop recording changes to the cache.
+    pub(crate) fn stop_recording(&self) {
+        self.inner.write().journal = None;
+    }
+
+    /// Replace the contents of the cache with a snapshot.
+    pub(crate) fn restore(&self, snapshot: &SimCacheSnapshot) {
+        *self.inner.write() = snapshot.store.snapshot();
+    }
+
+    /// Apply recorded changes to the cache, with the capacity of the
+    /// snapshot they were recorded against.
+    pub(crate) fn apply_events(&self, events: &[CacheEvent], snapshot: &SimCacheSnapshot) {
+        let mut inner = self.inner.write();
+        for event in events {
+            inner.apply(event, snapshot.capacity);
+        }
+    }
+}
+
+/// A change made to a [`SimCache`] from outside of the block build, e.g. an
+/// item arriving, recorded to replay the build.
+///
+/// Items are recorded with the rank assigned by the cache's
+/// [`ScoringPolicy`] when they were added.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum CacheEvent {
+    /// A transaction was added.
+    AddTx {
+        /// The rank assigned to the transaction.
+        cache_rank: u128,
+        /// The transaction.
+        item: SimItem,
+    },
+    /// A bundle was added.
+    AddBundle {
+        /// The rank assigned to the bundle.
+        cache_rank: u128,
+        /// The bundle.
+        item: SimItem,
+    },
+    /// A bundle was cancelled by replacement UUID.
+    CancelBundle {
+        /// The replacement UUID of the bundle.
+        replacement_uuid: String,
+        /// The replacement nonce of the cancellation, if any.
+        replacement_nonce: Option<u64>,
+    },
+    /// An item was removed by rank.
+    Remove {
+        /// The rank of the item.
+        cache_rank: u128,
+    },
+    /// An item was removed by rank, and disallowed.
+    RemoveAndDisallow {
+        /// The rank of the item.
+        cache_rank: u128,
+    },
+    /// The cache was cleaned for a block.
+    Clean {
+        /// The block number.
+        block_number: u64,
+        /// The block timestamp.
+        block_timestamp: u64,
+    },
+    /// The cache was cleared.
+    Clear,
+}
+
+/// A snapshot of the contents of a [`SimCache`], including the transactions
+/// queued behind lower nonces, and the identifiers of disallowed items.
+#[derive(Debug)]
+pub struct SimCacheSnapshot {
+    store: CacheStore,
+    capacity: usize,
+}
+
+impl Clone for SimCacheSnapshot {
+    fn clone(&self) -> Self {
+        Self { store: self.store.snapshot(), capacity: self.capacity }
+    }
+}
+
+/// The serialized form of a [`SimCacheSnapshot`]. The identifiers of the
+/// items are not serialized, as they are recomputed from the items, and LRU
+/// caches are serialized from least to most recently used.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SerializedSnapshot {
+    capacity: usize,
+    items: Vec<(u128, SimItem)>,
+    queued: Vec<(u128, SimItem)>,
+    disallowed: Vec<SimItem>,
+    replacement_nonces: Vec<(String, u64)>,
+    sender_queues: bool,
+    price_bump: u64,
+    sender_slots: NonZeroUsize,
+    arrivals: u64,
+}
+
+impl From<&SimCacheSnapshot> for SerializedSnapshot {
+    fn from(snapshot: &SimCacheSnapshot) -> Self {
+        let store = &snapshot.store;
+        Self {
+            capacity: snapshot.capacity,
+            items: store.items.iter().map(|(rank, item)| (*rank, item.clone())).collect(),
+            queued: store
+                .senders
+                .values()
+                .flat_map(BTreeMap::values)
+                .map(|queued| (queued.cache_rank, queued.item.clone()))
+                .collect(),
+            disallowed: store.disallowed.iter().rev().map(|(_, item)| item.clone()).collect(),
+            replacement_nonces: store
+                .replacement_nonces
+                .iter()
+                .rev()
+                .map(|(uuid, nonce)| (uuid.clone(), *nonce))
+                .collect(),
+            sender_queues: store.sender_queues,
+            price_bump: store.price_bump,
+            sender_slots: store.sender_slots,
+            arrivals: store.arrivals,
+        }
+    }
+}
+
+impl From<SerializedSnapshot> for SimCacheSnapshot {
+    fn from(snapshot: SerializedSnapshot) -> Self {
+        let mut store = CacheStore::new();
+
+        for (cache_rank, item) in snapshot.items {
+            store.seen.insert(item.identifier_owned(), cache_rank);
+            store.items.insert(cache_rank, item);
+        }
+        for (cache_rank, item) in snapshot.queued {
+            let tx = item.as_tx().expect("queued items are txs");
+            let (sender, nonce) = (tx.signer(), tx.nonce());
+            store.seen.entry(item.identifier_owned()).or_insert(cache_rank);
+            store.senders.entry(sender).or_default().insert(nonce, QueuedTx { cache_rank, item });
+        }
+        for item in snapshot.disallowed {
+            store.disallowed.put(item.identifier_owned(), item);
+        }
+        for (uuid, nonce) in snapshot.replacement_nonces {
+            store.replacement_nonces.put(uuid, nonce);
+        }
+        store.sender_queues = snapshot.sender_queues;
+        store.price_bump = snapshot.price_bump;
+        store.sender_slots = snapshot.sender_slots;
+        store.arrivals = snapshot.arrivals;
+
+        Self { store, capacity: snapshot.capacity }
+    }
+}
+
+impl Serialize for SimCacheSnapshot {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedSnapshot::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SimCacheSnapshot {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SerializedSnapshot::deserialize(deserializer).map(Into::into)
+    }
+}
+
+impl SimCacheSnapshot {
+    /// Get the number of items in the snapshot, including transactions
+    /// queued behind lower nonces.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// True if the snapshot is empty.
+    pub fn is_empty(&self) -> bool {
+        self.store.items.is_empty()
+    }
+
+    /// Get the capacity of the snapshotted cache.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// A transaction in a sender's queue.
+#[derive(Debug, Clone)]
+struct QueuedTx {
+    /// The item's cache_rank. For transactions parked behind a lower nonce,
+    /// this is the rank it will be promoted at.
+    cache_rank: u128,
+    /// The transaction.
+    item: SimItem,
 }
 
 /// Internal cache data, meant to be protected by a lock.
@@ -235,12 +674,38 @@ struct CacheStore {
     items: BTreeMap<u128, SimItem>,
 
     /// Key is the unique identifier for the [`SimItem`] - the UUID for
-    /// bundles, tx hash for transactions.
-    seen: HashSet<SimIdentifier<'static>>,
+    /// bundles, tx hash for transactions. Value is the item's cache_rank.
+    seen: HashMap<SimIdentifier<'static>, u128>,
 
     /// Identifiers of items that have been removed from the cache, as
-    /// they will never be valid again
-    disallowed: LruCache<SimIdentifier<'static>, ()>,
+    /// they will never be valid again, and the removed items. A bundle with
+    /// the same UUID but different contents is not disallowed, as it is a
+    /// replacement.
+    disallowed: LruCache<SimIdentifier<'static>, SimItem>,
+
+    /// The highest replacement nonce seen for each bundle replacement UUID.
+    replacement_nonces: LruCache<String, u64>,
+
+    /// Whether transactions are queued per sender.
+    sender_queues: bool,
+
+    /// Cached transactions of each sender, by nonce, if `sender_queues` is
+    /// set. The first transaction in each queue is in `items`, the rest are
+    /// parked until it is removed.
+    senders: HashMap<Address, BTreeMap<u64, QueuedTx>>,
+
+    /// The minimum fee increase, in percent, for replacing a transaction.
+    price_bump: u64,
+
+    /// The maximum number of cached transactions per sender.
+    sender_slots: NonZeroUsize,
+
+    /// Changes made to the cache from outside of the block build, if they
+    /// are being recorded.
+    journal: Option<Vec<CacheEvent>>,
+
+    /// The number of items added to the cache.
+    arrivals: u64,
 }
 
 impl fmt::Debug for CacheStore {
@@ -253,79 +718,384 @@ impl CacheStore {
     fn new() -> Self {
         Self {
             items: BTreeMap::new(),
-            seen: HashSet::new(),
+            seen: HashMap::new(),
             disallowed: LruCache::new(NonZeroUsize::new(128).unwrap()),
+            replacement_nonces: LruCache::new(NonZeroUsize::new(1024).unwrap()),
+            sender_queues: false,
+            senders: HashMap::new(),
+            price_bump: DEFAULT_PRICE_BUMP,
+            sender_slots: NonZeroUsize::new(DEFAULT_SENDER_SLOTS).unwrap(),
+            journal: None,
+            arrivals: 0,
         }
     }
 
-    /// Add an item to the cache.
-    fn add_inner(&mut self, mut cache_rank: u128, item: SimItem, capacity: usize) {
-        // If the item is disallowed, we don't add it
-        if self.disallowed.contains(&item.identifier_owned()) {
-            return;
+    /// Copy the contents of the store, without the journal.
+    fn snapshot(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+            seen: self.seen.clone(),
+            disallowed: self.disallowed.clone(),
+            replacement_nonces: self.replacement_nonces.clone(),
+            sender_queues: self.sender_queues,
+            senders: self.senders.clone(),
+            price_bump: self.price_bump,
+            sender_slots: self.sender_slots,
+            journal: None,
+            arrivals: self.arrivals,
         }
+    }
 
-        // Check if we've already seen this item - if so, don't add it
-        if !self.seen.insert(item.identifier_owned()) {
-            return;
+    /// Record a change, if changes are being recorded.
+    fn record(&mut self, event: impl FnOnce() -> CacheEvent) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(event());
+        }
+    }
+
+    /// Apply a recorded change.
+    fn apply(&mut self, event: &CacheEvent, capacity: usize) {
+        // Changes that failed when recorded fail again, and are ignored.
+        match event {
+            CacheEvent::AddTx { cache_rank, item } => {
+                let _ = self.add_tx(*cache_rank, item.clone(), capacity);
+            }
+            CacheEvent::AddBundle { cache_rank, item } => {
+                let _ = self.add_bundle(*cache_rank, item.clone(), capacity);
+            }
+            CacheEvent::CancelBundle { replacement_uuid, replacement_nonce } => {
+                let _ = self.cancel_bundle(replacement_uuid, *replacement_nonce);
+            }
+            CacheEvent::Remove { cache_rank } => {
+                self.remove(*cache_rank);
+            }
+            CacheEvent::RemoveAndDisallow { cache_rank } => {
+                self.remove_and_disallow(*cache_rank);
+            }
+            CacheEvent::Clean { block_number, block_timestamp } => {
+                self.clean(capacity, *block_number, *block_timestamp);
+            }
+            CacheEvent::Clear => self.clear(),
         }
+    }
+
+    /// Get the number of items in the cache, including parked transactions.
+    fn len(&self) -> usize {
+        self.items.len() + self.senders.values().map(|queue| queue.len() - 1).sum::<usize>()
+    }
+
+    /// Check if the item is disallowed or has already been seen.
+    fn is_known(&self, identifier: &SimIdentifier<'static>, item: &SimItem) -> bool {
+        self.disallowed.peek(identifier).is_some_and(|disallowed| disallowed == item)
+            || self.seen.contains_key(identifier)
+    }
 
-        // If it has the same cache_rank, we decrement (prioritizing earlier items)
+    /// Get the first free cache_rank at or below the given one. If it has the
+    /// same cache_rank as another item, we decrement (prioritizing earlier
+    /// items).
+    fn free_rank(&self, mut cache_rank: u128) -> u128 {
         while self.items.contains_key(&cache_rank) && cache_rank != 0 {
             cache_rank = cache_rank.saturating_sub(1);
         }
+        cache_rank
+    }
+
+    /// Get the cache_rank of a bundle by its replacement UUID.
+    fn bundle_rank(&self, replacement_uuid: &str) -> Option<u128> {
+        self.seen.get(SimIdentifier::bundle(Cow::Borrowed(replacement_uuid)).as_bytes()).copied()
+    }
+
+    /// Add an item to the cache.
+    fn add_inner(&mut self, cache_rank: u128, item: SimItem, capacity: usize) {
+        let identifier = item.identifier_owned();
+
+        // If the item is disallowed or already seen, we don't add it
+        if self.is_known(&identifier, &item) {
+            return;
+        }
+
+        let cache_rank = self.free_rank(cache_rank);
 
         if self.items.len() >= capacity {
             // If we are at capacity, we need to remove the lowest score
-            if let Some((_, item)) = self.items.pop_first() {
-                self.seen.remove(&item.identifier_owned());
+            self.evict_lowest();
+        }
+
+        self.seen.insert(identifier, cache_rank);
+        self.items.insert(cache_rank, item);
+        self.arrivals += 1;
+    }
+
+    /// Remove the lowest-score item. If it is a transaction, the rest of its
+    /// sender's queue is removed too, as it cannot be valid without it.
+    fn evict_lowest(&mut self) {
+        let Some((_, item)) = self.items.pop_first() else { return };
+        self.seen.remove(item.identifier().as_bytes());
+
+        if let Some(tx) = item.as_tx() {
+            for queued in
+                self.senders.remove(&tx.signer()).into_iter().flat_map(BTreeMap::into_values)
+            {
+                self.seen.remove(queued.item.identifier().as_bytes());
+            }
+        }
+    }
+
+    /// Add a transaction to its sender's queue, replacing any transaction
+    /// with the same nonce. Without sender queues, the transaction is added
+    /// like any other item.
+    fn add_tx(
+        &mut self,
+        cache_rank: u128,
+        item: SimItem,
+        capacity: usize,
+    ) -> Result<(), CacheError> {
+        self.record(|| CacheEvent::AddTx { cache_rank, item: item.clone() });
+        let identifier = item.identifier_owned();
+
+        // If the item is disallowed or already seen, we don't add it
+        if self.is_known(&identifier, &item) {
+            return Ok(());
+        }
+
+        if !self.sender_queues {
+            self.add_inner(cache_rank, item, capacity);
+            return Ok(());
+        }
+
+        let tx = item.as_tx().expect("SimItem is not a Tx");
+        let (sender, nonce) = (tx.signer(), tx.nonce());
+        let queue = self.senders.get(&sender);
+
+        if let Some(existing) = queue.and_then(|queue| queue.get(&nonce)) {
+            let existing = existing.item.as_tx().expect("queued items are txs");
+            if !is_fee_bump(existing, tx, self.price_bump) {
+                return Err(CacheError::ReplacementUnderpriced { sender, nonce });
+            }
+            self.dequeue(sender, nonce);
+        } else if let Some(queue) = queue.filter(|queue| queue.len() >= self.sender_slots.get()) {
+            // Make room by dropping the highest nonce, if this tx precedes it.
+            let highest = *queue.last_key_value().expect("queues are not empty").0;
+            if nonce > highest {
+                return Err(CacheError::SenderSlotsFull { sender, slots: self.sender_slots.get() });
+            }
+            self.dequeue(sender, highest);
+        }
+
+        self.enqueue(cache_rank, item, identifier, capacity);
+        Ok(())
+    }
+
+    /// Insert a transaction into its sender's queue. If it has the lowest
+    /// nonce, it is added to `items`, parking the previous first transaction.
+    fn enqueue(
+        &mut self,
+        cache_rank: u128,
+        item: SimItem,
+        identifier: SimIdentifier<'static>,
+        capacity: usize,
+    ) {
+        let tx = item.as_tx().expect("SimItem is not a Tx");
+        let (sender, nonce) = (tx.signer(), tx.nonce());
+        self.arrivals += 1;
+
+        match self.senders.get(&sender).and_then(|queue| queue.first_key_value()) {
+            Some((&first, _)) if first < nonce => {
+                // Park the tx behind the lower nonce.
+                self.seen.insert(identifier, cache_rank);
+                self.senders
+                    .get_mut(&sender)
+                    .expect("checked above")
+                    .insert(nonce, QueuedTx { cache_rank, item });
+                return;
+            }
+            Some((_, first)) => {
+                // Park the previous first tx, if it is in `items`.
+                if self.items.get(&first.cache_rank) == Some(&first.item) {
+                    self.items.remove(&first.cache_rank);
+                }
+            }
+            None => {
+                if self.items.len() >= capacity {
+                    // If we are at capacity, we need to remove the lowest score
+                    self.evict_lowest();
+                }
             }
         }
 
-        self.items.insert(cache_rank, item.clone());
+        self.senders.entry(sender).or_default().insert(nonce, QueuedTx { cache_rank, item });
+        self.promote(sender);
     }
 
-    fn add_bundles<I, T>(&mut self, item: I, basefee: u64, capacity: usize)
+    /// Remove a transaction from its sender's queue, and from `items` if it
+    /// is there. This does not promote the next transaction.
+    fn dequeue(&mut self, sender: Address, nonce: u64) -> Option<QueuedTx> {
+        let queue = self.senders.get_mut(&sender)?;
+        let queued = queue.remove(&nonce)?;
+        if queue.is_empty() {
+            self.senders.remove(&sender);
+        }
+
+        self.seen.remove(queued.item.identifier().as_bytes());
+        if self.items.get(&queued.cache_rank) == Some(&queued.item) {
+            self.items.remove(&queued.cache_rank);
+        }
+        Some(queued)
+    }
+
+    /// Add the first transaction in the sender's queue to `items`.
+    fn promote(&mut self, sender: Address) {
+        let Some((_, first)) = self.senders.get(&sender).and_then(BTreeMap::first_key_value) else {
+            return;
+        };
+        if self.items.get(&first.cache_rank) == Some(&first.item) {
+            return;
+        }
+
+        let cache_rank = self.free_rank(first.cache_rank);
+        let item = first.item.clone();
+
+        let mut first =
+            self.senders.get_mut(&sender).and_then(BTreeMap::first_entry).expect("checked above");
+        first.get_mut().cache_rank = cache_rank;
+        self.seen.insert(item.identifier_owned(), cache_rank);
+        self.items.insert(cache_rank, item);
+    }
+
+    /// Remove the sender's transactions with nonces up to and including the
+    /// included nonce, and promote the next transaction.
+    fn mark_included(&mut self, sender: Address, nonce: u64) {
+        let Some(queue) = self.senders.get(&sender) else { return };
+        let included = queue.range(..=nonce).map(|(nonce, _)| *nonce).collect::<Vec<_>>();
+        if included.is_empty() {
+            return;
+        }
+
+        for nonce in included {
+            self.dequeue(sender, nonce);
+        }
+        self.promote(sender);
+    }
+
+    /// Check the replacement nonce of a bundle update, and record it if it is
+    /// not stale.
+    fn check_replacement_nonce(
+        &mut self,
+        replacement_uuid: &str,
+        nonce: Option<u64>,
+    ) -> Result<(), CacheError> {
+        // Updates without a nonce are never stale.
+        let Some(nonce) = nonce else { return Ok(()) };
+
+        if let Some(&latest) = self.replacement_nonces.peek(replacement_uuid) {
+            if nonce < latest {
+                return Err(CacheError::StaleBundleUpdate {
+                    uuid: replacement_uuid.to_owned(),
+                    nonce,
+                    latest,
+                });
+            }
+        }
+        self.replacement_nonces.put(replacement_uuid.to_owned(), nonce);
+        Ok(())
+    }
+
+    /// Add a bundle to the cache, replacing any bundle with the same
+    /// replacement UUID.
+    fn add_bundle(
+        &mut self,
+        cache_rank: u128,
+        item: SimItem,
+        capacity: usize,
+    ) -> Result<(), CacheError> {
+        self.record(|| CacheEvent::AddBundle { cache_rank, item: item.clone() });
+        let bundle = item.as_bundle().expect("SimItem is not a Bundle");
+        let uuid = bundle.replacement_uuid().expect("checked on creation");
+        self.check_replacement_nonce(uuid, bundle.replacement_nonce())?;
+
+        if let Some(existing) = self.bundle_rank(uuid) {
+            // Resubmitting the same bundle is a no-op.
+            if self.items.get(&existing) == Some(&item) {
+                return Ok(());
+            }
+            self.remove(existing);
+        }
+
+        self.add_inner(cache_rank, item, capacity);
+        Ok(())
+    }
+
+    /// Cancel a bundle by its replacement UUID, returning it if it was
+    /// cached.
+    fn cancel_bundle(
+        &mut self,
+        replacement_uuid: &str,
+        nonce: Option<u64>,
+    ) -> Result<Option<SimItem>, CacheError> {
+        self.record(|| CacheEvent::CancelBundle {
+            replacement_uuid: replacement_uuid.to_owned(),
+            replacement_nonce: nonce,
+        });
+        self.check_replacement_nonce(replacement_uuid, nonce)?;
+        Ok(self.bundle_rank(replacement_uuid).and_then(|rank| self.remove(rank)))
+    }
+
+    fn add_bundles<I, T>(&mut self, item: I, rank: impl Fn(&SimItem) -> u128, capacity: usize)
     where
         I: IntoIterator<Item = T>,
         T: Into<RecoveredBundle>,
     {
         for item in item.into_iter() {
             let item = item.into();
+            if item.txs().is_empty() {
+                if let Some(uuid) = item.replacement_uuid() {
+                    // Stale cancellations are skipped.
+                    let _ = self.cancel_bundle(uuid, item.replacement_nonce());
+                }
+                continue;
+            }
+
             let Ok(item) = SimItem::try_from(item) else {
                 // Skip invalid bundles
                 continue;
             };
-            let cache_rank = item.calculate_total_fee(basefee);
-            self.add_inner(cache_rank, item, capacity);
+            let cache_rank = rank(&item);
+            // Stale updates are skipped.
+            let _ = self.add_bundle(cache_rank, item, capacity);
         }
     }
 
-    fn add_txs<I>(&mut self, item: I, basefee: u64, capacity: usize)
+    fn add_txs<I>(&mut self, item: I, rank: impl Fn(&SimItem) -> u128, capacity: usize)
     where
         I: IntoIterator<Item = Recovered<TxEnvelope>>,
     {
         for item in item.into_iter() {
             let item = SimItem::from(item);
-            let cache_rank = item.calculate_total_fee(basefee);
-            self.add_inner(cache_rank, item, capacity);
+            let cache_rank = rank(&item);
+            // Underpriced replacements and txs over the sender limit are
+            // skipped.
+            let _ = self.add_tx(cache_rank, item, capacity);
         }
     }
 
-    /// Remove an item by key. This will also remove it from the seen set.
+    /// Remove an item by key. This will also remove it from the seen set. If
+    /// the item is a transaction, the next transaction from its sender is
+    /// promoted.
     fn remove(&mut self, cache_rank: u128) -> Option<SimItem> {
-        if let Some(item) = self.items.remove(&cache_rank) {
-            self.seen.remove(item.identifier().as_bytes());
-            Some(item)
-        } else {
-            None
+        let item = self.items.remove(&cache_rank)?;
+        self.seen.remove(item.identifier().as_bytes());
+
+        if let Some(tx) = item.as_tx() {
+            self.dequeue(tx.signer(), tx.nonce());
+            self.promote(tx.signer());
         }
+        Some(item)
     }
     /// Remove an item by key, and prevent it from being re-added for a while.
     /// This will also remove it from the seen set.
     fn remove_and_disallow(&mut self, cache_rank: u128) -> Option<SimItem> {
         self.remove(cache_rank).inspect(|item| {
-            self.disallowed.put(item.identifier_owned(), ());
+            self.disallowed.put(item.identifier_owned(), item.clone());
         })
     }
 
@@ -359,7 +1129,7 @@ impl CacheStore {
                 }
 
                 if never {
-                    self.disallowed.put(item.identifier_owned(), ());
+                    self.disallowed.put(item.identifier_owned(), item.clone());
                 }
 
                 now
@@ -372,9 +1142,19 @@ impl CacheStore {
     fn clear(&mut self) {
         self.items.clear();
         self.seen.clear();
+        self.senders.clear();
     }
 }
 
+/// Check if the replacement increases both the max fee and the priority fee
+/// of the existing transaction by at least `price_bump` percent.
+fn is_fee_bump(existing: &TxEnvelope, replacement: &TxEnvelope, price_bump: u64) -> bool {
+    let bumped = |fee: u128| fee.saturating_mul(100 + price_bump as u128) / 100;
+
+    replacement.max_fee_per_gas() >= bumped(existing.max_fee_per_gas())
+        && replacement.priority_fee_or_price() >= bumped(existing.priority_fee_or_price())
+}
+
 #[cfg(test)]
 mod test {
 
@@ -448,25 +1228,342 @@ mod test {
         assert_eq!(cache.get(100), None);
     }
 
+    #[test]
+    fn bundle_replacement() {
+        const UUID: &str = "fbcbb9ce-2bef-4587-9c5f-61f606ca0a1a";
+        let cache = SimCache::with_capacity(10);
+
+        let original = invalid_bundle_with_score(100, 1, UUID.to_string());
+        let replacement = invalid_bundle_with_score(100, 2, UUID.to_string());
+
+        cache.add_bundles([original.clone()], 0);
+        assert_eq!(cache.get_bundle(UUID), Some(original.clone().try_into().unwrap()));
+
+        // Resubmitting the same bundle is a no-op.
+        cache.add_bundles([original], 0);
+        assert_eq!(cache.len(), 1);
+
+        // A different bundle with the same UUID replaces the original.
+        cache.add_bundles([replacement.clone()], 0);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(100), None);
+        assert_eq!(cache.get(200), Some(replacement.clone().try_into().unwrap()));
+        assert_eq!(cache.get_bundle(UUID), Some(replacement.try_into().unwrap()));
+
+        // An empty bundle with the same UUID cancels it.
+        cache.add_bundles([bundle_with_nonce(vec![], UUID, None)], 0);
+        assert!(cache.is_empty());
+        assert_eq!(cache.get_bundle(UUID), None);
+    }
+
+    #[test]
+    fn bundle_replacement_nonce() {
+        const UUID: &str = "39637ce4-5f33-4eb6-8893-8cc325a6cca3";
+        let cache = SimCache::with_capacity(10);
+        let tx = |mpfpg| vec![invalid_tx_with_score(100, mpfpg)];
+
+        let first = bundle_with_nonce(tx(1), UUID, Some(1));
+        let second = bundle_with_nonce(tx(2), UUID, Some(2));
+        let stale = bundle_with_nonce(tx(3), UUID, Some(1));
+
+        cache.add_bundles([first, second.clone()], 0);
+        assert_eq!(cache.get_bundle(UUID), Some(second.clone().try_into().unwrap()));
+
+        // A stale update does not replace the newer bundle.
+        cache.add_bundles([stale.clone()], 0);
+        assert_eq!(cache.get_bundle(UUID), Some(second.try_into().unwrap()));
+        assert!(matches!(
+            cache.inner.write().add_bundle(300, stale.try_into().unwrap(), 10),
+            Err(CacheError::StaleBundleUpdate { nonce: 1, latest: 2, .. })
+        ));
+
+        // Stale cancellations are ignored, and cancellations stop stale
+        // updates from re-adding the bundle.
+        cache.add_bundles([bundle_with_nonce(vec![], UUID, Some(1))], 0);
+        assert_eq!(cache.len(), 1);
+        cache.add_bundles([bundle_with_nonce(vec![], UUID, Some(3))], 0);
+        assert!(cache.is_empty());
+        cache.add_bundles([bundle_with_nonce(tx(4), UUID, Some(2))], 0);
+        assert!(cache.is_empty());
+
+        // Updates without a nonce are never stale.
+        let unsequenced = bundle_with_nonce(tx(5), UUID, None);
+        cache.add_bundles([unsequenced.clone()], 0);
+        assert_eq!(cache.get_bundle(UUID), Some(unsequenced.try_into().unwrap()));
+    }
+
+    #[test]
+    fn disallowed_bundle_replacement() {
+        const UUID: &str = "1c008717-b187-4e53-9601-25435f5fe8b7";
+        let cache = SimCache::with_capacity(10);
+
+        let original = invalid_bundle_with_score(100, 1, UUID.to_string());
+        let replacement = invalid_bundle_with_score(100, 2, UUID.to_string());
+
+        cache.add_bundles([original.clone()], 0);
+        cache.remove_and_disallow(100);
+
+        // The disallowed bundle cannot be re-added, but a replacement can.
+        cache.add_bundles([original], 0);
+        assert!(cache.is_empty());
+        cache.add_bundles([replacement.clone()], 0);
+        assert_eq!(cache.get_bundle(UUID), Some(replacement.try_into().unwrap()));
+    }
+
+    #[test]
+    fn remove_replaced_item() {
+        const UUID: &str = "d5e1a5f0-2b4c-4a56-9d9e-8b1f2a3c4d5e";
+        let cache = SimCache::with_capacity(10);
+
+        // Both bundles have the same cache rank.
+        let original = bundle_with_nonce(vec![invalid_tx_with_score(100, 1)], UUID, None);
+        let replacement = bundle_with_nonce(vec![invalid_tx_with_score(50, 2)], UUID, None);
+        let original: SimItem = original.try_into().unwrap();
+
+        cache.add_bundles([original.as_bundle().unwrap().clone()], 0);
+        cache.add_bundles([replacement.clone()], 0);
+
+        // Removing the original does not remove its replacement.
+        assert_eq!(cache.remove_item(100, &original), None);
+        let replacement: SimItem = replacement.try_into().unwrap();
+        assert_eq!(cache.remove_item(100, &replacement), Some(replacement));
+    }
+
+    #[test]
+    fn tx_replacement() {
+        let cache = SimCache::with_capacity(10).with_sender_queues();
+
+        let original = tx_from(1, 0, 100, 10);
+        cache.try_add_tx(original.clone(), 0).unwrap();
+
+        // Resubmitting the same tx is a no-op.
+        cache.try_add_tx(original, 0).unwrap();
+        assert_eq!(cache.len(), 1);
+
+        // Both fees must be bumped by at least 10%.
+        assert!(matches!(
+            cache.try_add_tx(tx_from(1, 0, 109, 20), 0),
+            Err(CacheError::ReplacementUnderpriced { nonce: 0, .. })
+        ));
+        assert!(matches!(
+            cache.try_add_tx(tx_from(1, 0, 200, 10), 0),
+            Err(CacheError::ReplacementUnderpriced { nonce: 0, .. })
+        ));
+
+        let replacement = tx_from(1, 0, 110, 11);
+        cache.try_add_tx(replacement.clone(), 0).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.read_best(10), vec![(1100, replacement.into())]);
+
+        // Parked txs are replaced in place.
+        cache.try_add_tx(tx_from(1, 1, 100, 10), 0).unwrap();
+        let parked_replacement = tx_from(1, 1, 110, 11);
+        cache.try_add_tx(parked_replacement.clone(), 0).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        cache.remove(1100);
+        assert_eq!(cache.read_best(10), vec![(1100, parked_replacement.into())]);
+    }
+
+    #[test]
+    fn nonce_queue() {
+        let cache = SimCache::with_capacity(10).with_sender_queues();
+
+        let txs =
+            (0..4).map(|nonce| tx_from(1, nonce, 100, 10 + nonce as u128)).collect::<Vec<_>>();
+
+        // Only the lowest nonce is simulated.
+        cache.add_txs([txs[1].clone(), txs[2].clone()], 0);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.read_best(10), vec![(1100, txs[1].clone().into())]);
+
+        // A lower nonce parks the previous first tx.
+        cache.try_add_tx(txs[0].clone(), 0).unwrap();
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.read_best(10), vec![(1000, txs[0].clone().into())]);
+
+        // Removing the first tx promotes the next.
+        cache.remove(1000);
+        assert_eq!(cache.read_best(10), vec![(1100, txs[1].clone().into())]);
+
+        // Including a later nonce removes the txs up to it.
+        cache.try_add_tx(txs[3].clone(), 0).unwrap();
+        cache.mark_included(&txs[2].clone().into());
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.read_best(10), vec![(1300, txs[3].clone().into())]);
+
+        // Including a bundle promotes the txs queued behind its nonces.
+        let bundle = bundle_with_nonce(vec![txs[3].clone()], "bundle", None);
+        cache.mark_included(&bundle.try_into().unwrap());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn sender_slots() {
+        let cache = SimCache::with_capacity(10)
+            .with_sender_queues()
+            .with_sender_slots(NonZeroUsize::new(2).unwrap());
+
+        cache.add_txs([tx_from(1, 0, 100, 10), tx_from(1, 2, 100, 10)], 0);
+        assert!(matches!(
+            cache.try_add_tx(tx_from(1, 3, 100, 10), 0),
+            Err(CacheError::SenderSlotsFull { slots: 2, .. })
+        ));
+
+        // A lower nonce takes the slot of the highest nonce.
+        cache.try_add_tx(tx_from(1, 1, 100, 10), 0).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(matches!(
+            cache.try_add_tx(tx_from(1, 2, 100, 10), 0),
+            Err(CacheError::SenderSlotsFull { slots: 2, .. })
+        ));
+
+        // Other senders are unaffected.
+        cache.try_add_tx(tx_from(2, 0, 100, 20), 0).unwrap();
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "sender queues must be enabled on an empty cache")]
+    fn sender_queues_on_populated_cache() {
+        let cache = SimCache::with_capacity(10);
+        cache.add_tx(tx_from(1, 0, 100, 10), 0);
+        let _ = cache.with_sender_queues();
+    }
+
+    #[test]
+    fn evict_sender_queue() {
+        let cache = SimCache::with_capacity(1).with_sender_queues();
+
+        cache.add_txs([tx_from(1, 0, 100, 10), tx_from(1, 1, 100, 10)], 0);
+        assert_eq!(cache.len(), 2);
+
+        // Evicting the first tx evicts the txs queued behind it.
+        let tx = tx_from(2, 0, 100, 20);
+        cache.try_add_tx(tx.clone(), 0).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.read_best(10), vec![(2000, tx.into())]);
+    }
+
+    #[test]
+    fn record_and_replay() {
+        const UUID: &str = "4b3a1c7e-52d4-4a61-9c1b-0e0b7f6a2d5e";
+        let cache = SimCache::with_capacity(2).with_sender_queues();
+        cache.try_add_tx(tx_from(1, 0, 100, 10), 0).unwrap();
+
+        let snapshot = cache.start_recording();
+        cache.add_txs([tx_from(1, 1, 100, 10), tx_from(2, 0, 100, 20)], 0);
+        cache.add_bundles([invalid_bundle_with_score(100, 30, UUID.to_string())], 0);
+        cache.remove(2000);
+        let events = cache.take_events();
+        cache.stop_recording();
+        cache.try_add_tx(tx_from(3, 0, 100, 40), 0).unwrap();
+        assert_eq!(events.len(), 4);
+        assert!(cache.take_events().is_empty());
+
+        // Replaying the events against the snapshot reproduces the cache,
+        // with the capacity of the snapshot, so that adding the bundle evicts
+        // the queue of the first sender.
+        let replayed = SimCache::with_capacity(10);
+        replayed.restore(&snapshot);
+        assert_eq!(replayed.read_best(10), vec![(1000, tx_from(1, 0, 100, 10).into())]);
+        replayed.apply_events(&events, &snapshot);
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(
+            replayed.read_best(10),
+            vec![(3000, invalid_bundle_with_score(100, 30, UUID.to_string()).try_into().unwrap())]
+        );
+    }
+
+    #[test]
+    fn snapshot_serde() {
+        const UUID: &str = "0f6b1a3e-8c2d-4e5f-9a7b-6c5d4e3f2a1b";
+        // Signers are recovered when deserializing, so the txs are signed.
+        let cache = SimCache::with_capacity(10).with_sender_queues();
+        cache.add_txs([signed_tx(1, 0, 10), signed_tx(1, 1, 10), signed_tx(2, 0, 20)], 0);
+        cache.add_bundles([bundle_with_nonce(vec![signed_tx(3, 0, 30)], UUID, Some(1))], 0);
+        cache.remove_and_disallow(2000);
+
+        let snapshot = cache.start_recording();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let deserialized: SimCacheSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+
+        let restored = SimCache::with_capacity(10);
+        restored.restore(&deserialized);
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored.read_best(10), cache.read_best(10));
+        assert_eq!(restored.arrivals(), cache.arrivals());
+
+        // The queues, disallowed items and replacement nonces are restored.
+        restored.add_txs([signed_tx(2, 0, 20)], 0);
+        restored.add_bundles([bundle_with_nonce(vec![signed_tx(3, 0, 40)], UUID, Some(0))], 0);
+        restored.remove(1000);
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.get(1000), Some(signed_tx(1, 1, 10).into()));
+    }
+
+    #[test]
+    fn reinsert_displaced_items() {
+        const UUID: &str = "4b3a1c7e-52d4-4a61-9c1b-0e0b7f6a2d5e";
+        let cache = SimCache::with_capacity(10).with_sender_queues();
+        let tx: SimItem = tx_from(1, 0, 100, 10).into();
+        let bundle: SimItem =
+            invalid_bundle_with_score(100, 30, UUID.to_string()).try_into().unwrap();
+        cache.add_txs([tx_from(1, 0, 100, 10), tx_from(1, 1, 100, 10)], 0);
+        cache.add_bundles([invalid_bundle_with_score(100, 30, UUID.to_string())], 0);
+        assert_eq!(cache.arrivals(), 3);
+
+        // Include the transaction and the bundle, then update the bundle.
+        cache.mark_included(&tx);
+        cache.remove(3000);
+        let update = bundle_with_nonce(vec![tx_from(2, 0, 100, 40)], UUID, None);
+        cache.add_bundles([update.clone()], 0);
+        assert_eq!(cache.arrivals(), 4);
+
+        // The transaction parks its successor, and the update is kept.
+        assert_eq!(cache.reinsert([(1000, tx.clone()), (3000, bundle)]), 1);
+        assert_eq!(cache.arrivals(), 5);
+        assert_eq!(cache.len(), 3);
+        let best = cache.read_best(10);
+        assert_eq!(best.len(), 2);
+        assert_eq!(best[0].1, update.try_into().unwrap());
+        assert_eq!(best[1].1, tx);
+    }
+
     fn invalid_bundle_with_score(
         gas_limit: u64,
         mpfpg: u128,
         replacement_uuid: String,
     ) -> signet_bundle::RecoveredBundle {
         let tx = invalid_tx_with_score(gas_limit, mpfpg);
+        bundle_with_nonce(vec![tx], &replacement_uuid, None)
+    }
+
+    fn bundle_with_nonce(
+        txs: Vec<Recovered<alloy::consensus::TxEnvelope>>,
+        replacement_uuid: &str,
+        replacement_nonce: Option<u64>,
+    ) -> signet_bundle::RecoveredBundle {
+        let mut extra_fields = alloy::serde::OtherFields::default();
+        if let Some(nonce) = replacement_nonce {
+            extra_fields
+                .insert_value(signet_bundle::REPLACEMENT_NONCE_FIELD.to_owned(), nonce)
+                .unwrap();
+        }
         signet_bundle::RecoveredBundle::new_unchecked(
-            vec![tx],
+            txs,
             vec![],
             1,
             Some(2),
             Some(3),
             vec![],
-            Some(replacement_uuid.clone()),
+            Some(replacement_uuid.to_owned()),
             vec![],
             None,
             None,
             vec![],
-            Default::default(),
+            extra_fields,
         )
     }
 
@@ -485,6 +1582,24 @@ mod test {
         )
     }
 
+    fn tx_from(
+        sender: u8,
+        nonce: u64,
+        max_fee_per_gas: u128,
+        mpfpg: u128,
+    ) -> Recovered<alloy::consensus::TxEnvelope> {
+        let tx =
+            alloy::consensus::TxEip1559 { nonce, max_fee_per_gas, ..build_alloy_tx(100, mpfpg) };
+
+        Recovered::new_unchecked(
+            TxEnvelope::Eip1559(alloy::consensus::Signed::new_unhashed(
+                tx,
+                alloy::signers::Signature::test_signature(),
+            )),
+            Address::with_last_byte(sender),
+        )
+    }
+
     fn invalid_tx_with_score_and_hash(
         gas_limit: u64,
         mpfpg: u128,
@@ -502,6 +1617,24 @@ mod test {
         )
     }
 
+    fn signed_tx(key: u8, nonce: u64, mpfpg: u128) -> Recovered<alloy::consensus::TxEnvelope> {
+        use alloy::{
+            consensus::SignableTransaction,
+            signers::{local::PrivateKeySigner, SignerSync},
+        };
+
+        let signer =
+            PrivateKeySigner::from_bytes(&alloy::primitives::B256::repeat_byte(key)).unwrap();
+        let tx = alloy::consensus::TxEip1559 {
+            nonce,
+            max_fee_per_gas: 100,
+            ..build_alloy_tx(100, mpfpg)
+        };
+        let signature = signer.sign_hash_sync(&tx.signature_hash()).unwrap();
+
+        Recovered::new_unchecked(tx.into_signed(signature).into(), signer.address())
+    }
+
     fn build_alloy_tx(gas_limit: u64, mpfpg: u128) -> alloy::consensus::TxEip1559 {
         alloy::consensus::TxEip1559 {
             gas_limit,
@@ -10,7 +10,10 @@ use tracing::{trace, trace_span};
 /// These are ordered from least to most valid. An item that is `Never` valid
 /// is always invalid, an item that is `Future` valid may become valid in the
 /// future, and an item that is `Now` valid is currently valid.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
 pub enum SimItemValidity {
     /// The item is invalid and should not be simulated.
     Never,
@@ -54,6 +57,11 @@ impl Display for SimItemValidity {
 /// Validates nonces sequentially, building a per-signer nonce cache so that
 /// multiple transactions from the same signer are checked with incrementing
 /// nonces. The first transaction's balance is also checked.
+///
+/// Droppable transactions that fail these checks are skipped, as the bundle
+/// is simulated without them. If every transaction is skipped, the list is
+/// [`SimItemValidity::Future`] valid if any of them may become valid, and
+/// [`SimItemValidity::Never`] valid otherwise.
 pub async fn check_bundle_tx_list<S>(
     items: impl Iterator<Item = TxRequirement>,
     source: &S,
@@ -68,34 +76,24 @@ where
     // bundle.
 
     let mut nonce_cache: BTreeMap<Address, u64> = BTreeMap::new();
-    let mut items = items.peekable();
-
-    // Peek to perform the balance check for the first tx
-    if let Some(first) = items.peek() {
-        let info = source.account_details(&first.signer).await?;
-
-        // check balance for the first tx is sufficient
-        if first.balance > info.balance {
-            trace!(
-                required = %first.balance,
-                available = %info.balance,
-                signer = %first.signer,
-                "insufficient balance",
-            );
-            return Ok(SimItemValidity::Future);
-        }
-
-        // Cache the nonce. This will be used for the first tx.
-        nonce_cache.insert(first.signer, info.nonce);
-    }
+    // Whether a transaction passed the checks.
+    let mut passed = false;
+    // The best validity of the dropped transactions, if all are dropped.
+    let mut dropped: Option<SimItemValidity> = None;
 
     for requirement in items {
-        let state_nonce = match nonce_cache.get(&requirement.signer) {
-            Some(cached_nonce) => *cached_nonce,
-            None => {
-                let nonce = source.nonce(&requirement.signer).await?;
-                nonce_cache.insert(requirement.signer, nonce);
-                nonce
+        // The balance is checked for the first tx that is not dropped.
+        let (state_nonce, balance) = if !passed {
+            let info = source.account_details(&requirement.signer).await?;
+            (*nonce_cache.entry(requirement.signer).or_insert(info.nonce), Some(info.balance))
+        } else {
+            match nonce_cache.get(&requirement.signer) {
+                Some(cached_nonce) => (*cached_nonce, None),
+                None => {
+                    let nonce = source.nonce(&requirement.signer).await?;
+                    nonce_cache.insert(requirement.signer, nonce);
+                    (nonce, None)
+                }
             }
         };
 
@@ -104,17 +102,27 @@ where
             signer = %requirement.signer,
             item_nonce = requirement.nonce,
             expected_nonce = state_nonce,
+            droppable = requirement.droppable,
         )
         .entered();
 
-        if requirement.nonce < state_nonce {
-            trace!("nonce too low");
-            return Ok(SimItemValidity::Never);
-        }
-        if requirement.nonce > state_nonce {
-            trace!("nonce too high");
-            return Ok(SimItemValidity::Future);
+        let validity = match balance {
+            Some(balance) if requirement.balance > balance => {
+                trace!(required = %requirement.balance, available = %balance, "insufficient balance");
+                SimItemValidity::Future
+            }
+            _ => check_nonce(requirement.nonce, state_nonce),
+        };
+
+        if !validity.is_valid_now() {
+            if !requirement.droppable {
+                return Ok(validity);
+            }
+            trace!(%validity, "dropping transaction");
+            dropped = dropped.max(Some(validity));
+            continue;
         }
+        passed = true;
 
         // Increment the cached nonce for the next transaction from this
         // signer. Map _must_ have the entry as we just either loaded or
@@ -122,6 +130,22 @@ where
         nonce_cache.entry(requirement.signer).and_modify(|n| *n += 1);
     }
 
-    // All transactions passed
-    Ok(SimItemValidity::Now)
+    // All transactions passed, or were dropped
+    match dropped {
+        Some(validity) if !passed => Ok(validity),
+        _ => Ok(SimItemValidity::Now),
+    }
+}
+
+/// Check a transaction nonce against the state nonce.
+fn check_nonce(nonce: u64, state_nonce: u64) -> SimItemValidity {
+    if nonce < state_nonce {
+        trace!("nonce too low");
+        return SimItemValidity::Never;
+    }
+    if nonce > state_nonce {
+        trace!("nonce too high");
+        return SimItemValidity::Future;
+    }
+    SimItemValidity::Now
 }
@@ -1,5 +1,5 @@
 use crate::{InnerDb, SimDb, TimeLimited};
-use signet_evm::{signet_precompiles, EvmNeedsTx, OrderDetector, SignetLayered};
+use signet_evm::{signet_host_evm_with_inspector, EvmNeedsTx};
 use signet_types::constants::SignetSystemConstants;
 use std::{marker::PhantomData, sync::Arc};
 use tokio::time::Instant;
@@ -13,7 +13,7 @@ use trevm::{
         inspector::{Inspector, NoOpInspector},
         DatabaseRef,
     },
-    Block, Cfg, TrevmBuilder,
+    Block, Cfg,
 };
 
 /// A host simulation environment.
@@ -111,18 +111,9 @@ where
         let db = self.sim_db();
         let inspector = Layered::new(TimeLimit::new(finish_by - Instant::now()), Insp::default());
 
-        // We layer on a order detector specific to the host environment.
-        let inspector =
-            SignetLayered::new(inspector, OrderDetector::for_host(self.constants.clone()));
-
-        // This is the same code as `signet_evm::signet_evm_with_inspector`, but
-        // we need to build the EVM manually to insert our layered inspector,
-        // as the shortcut will insert a rollup order detector.
-        TrevmBuilder::new()
-            .with_db(db)
-            .with_insp(inspector)
-            .with_precompiles(signet_precompiles())
-            .build_trevm()
+        // The host EVM uses an order detector specific to the host
+        // environment.
+        signet_host_evm_with_inspector(db, inspector, self.constants.clone())
             .fill_cfg(&self.cfg)
             .fill_block(&self.block)
     }
@@ -134,18 +125,24 @@ where
     Insp: Inspector<Ctx<SimDb<Db>>> + Default + Sync,
 {
     /// Accepts a cache from the simulation and extends the database with it.
+    ///
+    /// Storage of accounts already in the database cache is merged, rather
+    /// than replaced.
     pub fn accept_cache(
         &mut self,
         cache: Cache,
     ) -> Result<(), <InnerDb<Db> as TryCachingDb>::Error> {
-        self.db_mut().try_extend(cache)
+        self.accept_cache_ref(&cache)
     }
 
     /// Accepts a cache from the simulation and extends the database with it.
+    ///
+    /// Storage of accounts already in the database cache is merged, rather
+    /// than replaced.
     pub fn accept_cache_ref(
         &mut self,
         cache: &Cache,
     ) -> Result<(), <InnerDb<Db> as TryCachingDb>::Error> {
-        self.db_mut().try_extend_ref(cache)
+        self.db_mut().try_cache_mut().map(|db_cache| super::merge_cache(db_cache, cache))
     }
 }
@@ -1,6 +1,12 @@
 mod host;
 pub use host::HostEnv;
 
+mod pool;
+pub use pool::SimPool;
+
+mod position;
+pub(crate) use position::BlockPosition;
+
 mod rollup;
 pub use rollup::RollupEnv;
 
@@ -8,4 +14,34 @@ mod shared;
 pub use shared::SharedSimEnv;
 
 mod sim_env;
-pub use sim_env::SimEnv;
+pub use sim_env::{SimCheckpoint, SimEnv};
+
+use trevm::revm::database::{AccountState, Cache};
+
+/// Merge the state changes of a simulation into a cache.
+///
+/// Unlike [`TryCachingDb::try_extend_ref`], this merges the storage of
+/// accounts in both caches, so that changes to different slots of an account
+/// by separate simulations are all kept.
+///
+/// [`TryCachingDb::try_extend_ref`]: trevm::db::TryCachingDb::try_extend_ref
+pub(crate) fn merge_cache(target: &mut Cache, changes: &Cache) {
+    for (address, account) in &changes.accounts {
+        let existing = target.accounts.entry(*address).or_default();
+        existing.info = account.info.clone();
+
+        if matches!(account.account_state, AccountState::StorageCleared | AccountState::NotExisting)
+        {
+            existing.storage = account.storage.clone();
+            existing.account_state = account.account_state.clone();
+        } else {
+            existing.storage.extend(account.storage.iter().map(|(k, v)| (*k, *v)));
+            if !existing.account_state.is_storage_cleared() {
+                existing.account_state = account.account_state.clone();
+            }
+        }
+    }
+    target.contracts.extend(changes.contracts.iter().map(|(k, v)| (*k, v.clone())));
+    target.logs.extend(changes.logs.iter().cloned());
+    target.block_hashes.extend(changes.block_hashes.iter().map(|(k, v)| (*k, *v)));
+}
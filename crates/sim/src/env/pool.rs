@@ -0,0 +1,103 @@
+use core::fmt;
+use parking_lot::Mutex;
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{mpsc, Arc},
+    thread,
+};
+use tracing::warn;
+
+/// A job run by the pool.
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// A persistent pool of simulation worker threads.
+///
+/// The pool runs a fixed number of threads, which are reused across
+/// simulation rounds, so that the number of threads used for simulation is
+/// bounded regardless of the number of items. The pool is cheap to clone, and
+/// may be shared across [`SharedSimEnv`]s, e.g. to reuse the same threads for
+/// successive blocks. The threads exit once all clones are dropped.
+///
+/// [`SharedSimEnv`]: crate::SharedSimEnv
+#[derive(Clone)]
+pub struct SimPool {
+    jobs: mpsc::Sender<Job>,
+    size: usize,
+}
+
+impl fmt::Debug for SimPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimPool").field("size", &self.size).finish_non_exhaustive()
+    }
+}
+
+impl SimPool {
+    /// Create a new pool with `size` worker threads. At least one thread is
+    /// spawned.
+    ///
+    /// # Panics
+    ///
+    /// If the threads cannot be spawned.
+    pub fn new(size: usize) -> Self {
+        let size = size.max(1);
+        let (jobs, rx) = mpsc::channel::<Job>();
+        let rx = Arc::new(Mutex::new(rx));
+
+        for i in 0..size {
+            let rx = rx.clone();
+            thread::Builder::new()
+                .name(format!("signet-sim-{i}"))
+                .spawn(move || loop {
+                    // The lock is released before the job is run.
+                    let Ok(job) = rx.lock().recv() else { break };
+                    if catch_unwind(AssertUnwindSafe(job)).is_err() {
+                        warn!("simulation job panicked");
+                    }
+                })
+                .expect("failed to spawn simulation thread");
+        }
+
+        Self { jobs, size }
+    }
+
+    /// Get the number of worker threads.
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Queue a job to be run by the next free worker.
+    pub fn spawn(&self, job: impl FnOnce() + Send + 'static) {
+        // The workers only exit once the sender is dropped.
+        let _ = self.jobs.send(Box::new(job));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn runs_all_jobs() {
+        let pool = SimPool::new(2);
+        let count = Arc::new(AtomicUsize::new(0));
+        let (done, done_rx) = mpsc::channel();
+
+        for i in 0..16 {
+            let count = count.clone();
+            let done = done.clone();
+            pool.spawn(move || {
+                // A panicking job does not stop the worker.
+                if i == 3 {
+                    panic!("job panicked");
+                }
+                count.fetch_add(1, Ordering::Relaxed);
+                done.send(()).unwrap();
+            });
+        }
+        drop(done);
+
+        assert_eq!(done_rx.iter().count(), 15);
+        assert_eq!(count.load(Ordering::Relaxed), 15);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{RejectionReason, SimItem};
+use alloy::primitives::TxHash;
+use signet_bundle::BundlePlacement;
+
+/// The transactions added to the block so far, used to check the placement
+/// constraints of bundles.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BlockPosition {
+    tx_hashes: Vec<TxHash>,
+}
+
+impl BlockPosition {
+    /// Create a position at the top of an empty block.
+    pub(crate) const fn new() -> Self {
+        Self { tx_hashes: Vec::new() }
+    }
+
+    /// Get the number of transactions in the block.
+    pub(crate) const fn len(&self) -> u64 {
+        self.tx_hashes.len() as u64
+    }
+
+    /// Check if the item's placement pins it to the current end of the
+    /// block, i.e. it is a top of block bundle and the block is empty, or it
+    /// follows the last transaction of the block.
+    pub(crate) fn anchors(&self, item: &SimItem) -> bool {
+        match item.as_bundle().and_then(|bundle| bundle.placement()) {
+            Some(BundlePlacement::TopOfBlock) => self.tx_hashes.is_empty(),
+            Some(BundlePlacement::After(hash)) => self.tx_hashes.last() == Some(&hash),
+            _ => false,
+        }
+    }
+
+    /// Check that the item's placement is satisfied if it is appended to the
+    /// block.
+    pub(crate) fn check(&self, item: &SimItem) -> Result<(), RejectionReason> {
+        let Some(placement) = item.as_bundle().and_then(|bundle| bundle.placement()) else {
+            return Ok(());
+        };
+        let position = self.len();
+
+        match placement {
+            BundlePlacement::TopOfBlock if position == 0 => Ok(()),
+            BundlePlacement::TopOfBlock => {
+                Err(RejectionReason::PlacementUnsatisfiable { placement, position })
+            }
+            BundlePlacement::After(hash) if self.tx_hashes.last() == Some(&hash) => Ok(()),
+            // Once the transaction is in the block, the position after it is
+            // taken.
+            BundlePlacement::After(hash) if self.tx_hashes.contains(&hash) => {
+                Err(RejectionReason::PlacementUnsatisfiable { placement, position })
+            }
+            BundlePlacement::After(_) => {
+                Err(RejectionReason::PlacementPending { placement, position })
+            }
+            BundlePlacement::MinPosition(min) if position >= min => Ok(()),
+            BundlePlacement::MinPosition(_) => {
+                Err(RejectionReason::PlacementPending { placement, position })
+            }
+        }
+    }
+
+    /// Append the transactions of the item to the block, except the dropped
+    /// ones.
+    pub(crate) fn append(&mut self, item: &SimItem, dropped: &[TxHash]) {
+        match item {
+            SimItem::Bundle(bundle) => self.tx_hashes.extend(
+                bundle
+                    .txs()
+                    .iter()
+                    .map(|tx| *tx.inner().tx_hash())
+                    .filter(|hash| !dropped.contains(hash)),
+            ),
+            SimItem::Tx(tx) => self.tx_hashes.push(*tx.inner().tx_hash()),
+        }
+    }
+}
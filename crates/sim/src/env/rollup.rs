@@ -1,5 +1,5 @@
 use crate::{InnerDb, SimDb, TimeLimited};
-use signet_evm::EvmNeedsTx;
+use signet_evm::{EthereumHardfork, EvmNeedsTx, PrecompileRegistry};
 use signet_types::{
     constants::SignetSystemConstants, AggregateFills, AggregateOrders, MarketError,
 };
@@ -13,11 +13,21 @@ use trevm::{
         context::{BlockEnv, CfgEnv},
         database::{Cache, CacheDB},
         inspector::NoOpInspector,
+        primitives::hardfork::SpecId,
         DatabaseRef, Inspector,
     },
     Block, Cfg,
 };
 
+/// Get the [`SpecId`] for the block, from the rollup hardfork schedule.
+fn spec_id_for(constants: &SignetSystemConstants, block: &BlockEnv) -> SpecId {
+    EthereumHardfork::spec_id_in_schedule(
+        constants.forks(),
+        block.number.saturating_to(),
+        block.timestamp.saturating_to(),
+    )
+}
+
 /// A rollup simulation environment.
 #[derive(Debug)]
 pub struct RollupEnv<Db, Insp = NoOpInspector> {
@@ -28,6 +38,8 @@ pub struct RollupEnv<Db, Insp = NoOpInspector> {
     cfg: CfgEnv,
     block: BlockEnv,
 
+    precompiles: PrecompileRegistry,
+
     _pd: PhantomData<fn() -> Insp>,
 }
 
@@ -39,6 +51,7 @@ impl<Db, Insp> Clone for RollupEnv<Db, Insp> {
             fill_state: self.fill_state.clone(),
             cfg: self.cfg.clone(),
             block: self.block.clone(),
+            precompiles: self.precompiles.clone(),
             _pd: PhantomData,
         }
     }
@@ -46,6 +59,9 @@ impl<Db, Insp> Clone for RollupEnv<Db, Insp> {
 
 impl<Db, Insp> RollupEnv<Db, Insp> {
     /// Create a new rollup environment.
+    ///
+    /// The [`SpecId`] in the cfg is overridden by the rollup hardfork
+    /// schedule in the constants, at the block's height and timestamp.
     pub fn new<C, B>(db: Db, constants: SignetSystemConstants, cfg_ref: &C, block_ref: &B) -> Self
     where
         C: Cfg,
@@ -55,6 +71,7 @@ impl<Db, Insp> RollupEnv<Db, Insp> {
         cfg_ref.fill_cfg_env(&mut cfg);
         let mut block = BlockEnv::default();
         block_ref.fill_block_env(&mut block);
+        cfg.spec = spec_id_for(&constants, &block);
 
         Self {
             db: Arc::new(CacheDB::new(db)),
@@ -62,10 +79,24 @@ impl<Db, Insp> RollupEnv<Db, Insp> {
             fill_state: AggregateFills::default(),
             cfg,
             block,
+            precompiles: PrecompileRegistry::default(),
             _pd: PhantomData,
         }
     }
 
+    /// Set the registry of Signet precompiles. This should match the
+    /// registry used to execute the block. Defaults to
+    /// [`PrecompileRegistry::default`].
+    pub fn with_precompile_registry(mut self, precompiles: PrecompileRegistry) -> Self {
+        self.precompiles = precompiles;
+        self
+    }
+
+    /// Get the registry of Signet precompiles.
+    pub const fn precompile_registry(&self) -> &PrecompileRegistry {
+        &self.precompiles
+    }
+
     /// Get a reference to the inner database.
     pub const fn db(&self) -> &InnerDb<Db> {
         &self.db
@@ -110,6 +141,11 @@ impl<Db, Insp> RollupEnv<Db, Insp> {
         &mut self.cfg
     }
 
+    /// Get the [`SpecId`] of the block, from the rollup hardfork schedule.
+    pub fn spec_id(&self) -> SpecId {
+        spec_id_for(&self.constants, &self.block)
+    }
+
     /// Get a reference to the [`BlockEnv`].
     pub const fn block(&self) -> &BlockEnv {
         &self.block
@@ -138,9 +174,12 @@ where
 
         let inspector = Layered::new(TimeLimit::new(finish_by - Instant::now()), Insp::default());
 
-        signet_evm::signet_evm_with_inspector(db, inspector, self.constants.clone())
+        let mut evm = signet_evm::signet_evm_with_inspector(db, inspector, self.constants.clone())
             .fill_cfg(&self.cfg)
-            .fill_block(&self.block)
+            .fill_block(&self.block);
+        evm.set_spec_id(self.spec_id());
+        self.precompiles.apply(&mut evm);
+        evm
     }
 }
 
@@ -150,18 +189,24 @@ where
     Insp: Inspector<Ctx<SimDb<Db>>> + Default + Sync,
 {
     /// Accepts a cache from the simulation and extends the database with it.
+    ///
+    /// Storage of accounts already in the database cache is merged, rather
+    /// than replaced.
     pub fn accept_cache(
         &mut self,
         cache: Cache,
     ) -> Result<(), <InnerDb<Db> as TryCachingDb>::Error> {
-        self.db_mut().try_extend(cache)
+        self.accept_cache_ref(&cache)
     }
 
     /// Accepts a cache from the simulation and extends the database with it.
+    ///
+    /// Storage of accounts already in the database cache is merged, rather
+    /// than replaced.
     pub fn accept_cache_ref(
         &mut self,
         cache: &Cache,
     ) -> Result<(), <InnerDb<Db> as TryCachingDb>::Error> {
-        self.db_mut().try_extend_ref(cache)
+        self.db_mut().try_cache_mut().map(|db_cache| super::merge_cache(db_cache, cache))
     }
 }
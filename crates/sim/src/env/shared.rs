@@ -1,12 +1,19 @@
 use crate::{
-    cache::StateSource, env::RollupEnv, outcome::SimulatedItem, AcctInfo, HostEnv, SimCache, SimDb,
-    SimEnv,
+    cache::StateSource, env::RollupEnv, AcctInfo, BuildReport, DefaultScoring, HostEnv,
+    RejectionReason, ScoringPolicy, SimAttempt, SimCache, SimCheckpoint, SimDb, SimEnv, SimItem,
+    SimOutcomeWithCache, SimPool, SimulatedItem, StateFootprint,
 };
 use alloy::primitives::Address;
 use core::fmt;
-use std::{ops::Deref, sync::Arc};
-use tokio::{select, sync::watch};
-use tracing::{debug, debug_span, instrument, trace, warn, Span};
+use std::{
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::{select, sync::mpsc};
+use tracing::{debug, debug_span, instrument, trace, trace_span, warn, Span};
 use trevm::{
     db::TryCachingDb,
     helpers::Ctx,
@@ -45,46 +52,63 @@ impl<S: StateSource> StateSource for CachedAsyncSource<'_, S> {
 /// A simulation environment.
 ///
 /// Contains enough information to run a simulation.
-pub struct SharedSimEnv<RuDb, HostDb, RuInsp = NoOpInspector, HostInsp = NoOpInspector> {
-    inner: Arc<SimEnv<RuDb, HostDb, RuInsp, HostInsp>>,
+pub struct SharedSimEnv<
+    RuDb,
+    HostDb,
+    RuInsp = NoOpInspector,
+    HostInsp = NoOpInspector,
+    P = DefaultScoring,
+> {
+    inner: Arc<SimEnv<RuDb, HostDb, RuInsp, HostInsp, P>>,
+
+    /// The pool of threads running simulations.
+    pool: SimPool,
 }
 
-impl<RuDb, HostDb, RuInsp, HostInsp> fmt::Debug for SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp> {
+impl<RuDb, HostDb, RuInsp, HostInsp, P> fmt::Debug
+    for SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp, P>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SharedSimEnv")
             .field("finish_by", &self.inner.finish_by())
             .field("concurrency_limit", &self.inner.concurrency_limit())
+            .field("pool", &self.pool)
             .finish_non_exhaustive()
     }
 }
 
-impl<RuDb, HostDb, RuInsp, HostInsp> Deref for SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp> {
-    type Target = SimEnv<RuDb, HostDb, RuInsp, HostInsp>;
+impl<RuDb, HostDb, RuInsp, HostInsp, P> Deref for SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp, P> {
+    type Target = SimEnv<RuDb, HostDb, RuInsp, HostInsp, P>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
     }
 }
 
-impl<RuDb, HostDb, RuInsp, HostInsp> From<SimEnv<RuDb, HostDb, RuInsp, HostInsp>>
-    for SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp>
+impl<RuDb, HostDb, RuInsp, HostInsp, P> From<SimEnv<RuDb, HostDb, RuInsp, HostInsp, P>>
+    for SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp, P>
 where
     RuDb: DatabaseRef + Send + Sync + 'static,
     RuInsp: Inspector<Ctx<SimDb<RuDb>>> + Default + Sync + 'static,
     HostDb: DatabaseRef + Send + Sync + 'static,
     HostInsp: Inspector<Ctx<SimDb<HostDb>>> + Default + Sync + 'static,
+    P: ScoringPolicy,
 {
-    fn from(inner: SimEnv<RuDb, HostDb, RuInsp, HostInsp>) -> Self {
-        Self { inner: Arc::new(inner) }
+    /// Create a shared environment, with a [`SimPool`] of
+    /// [`SimEnv::concurrency_limit`] threads.
+    fn from(inner: SimEnv<RuDb, HostDb, RuInsp, HostInsp, P>) -> Self {
+        let pool = SimPool::new(inner.concurrency_limit());
+        Self { inner: Arc::new(inner), pool }
     }
 }
 
-impl<RuDb, HostDb, RuInsp, HostInsp> SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp>
+impl<RuDb, HostDb, RuInsp, HostInsp, P> SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp, P>
 where
     RuDb: DatabaseRef + Send + Sync + 'static,
     RuInsp: Inspector<Ctx<SimDb<RuDb>>> + Default + Sync + 'static,
     HostDb: DatabaseRef + Send + Sync + 'static,
     HostInsp: Inspector<Ctx<SimDb<HostDb>>> + Default + Sync + 'static,
+    P: ScoringPolicy,
 {
     /// Creates a new `SimEnv` instance.
     pub fn new(
@@ -92,13 +116,25 @@ where
         host: HostEnv<HostDb, HostInsp>,
         finish_by: tokio::time::Instant,
         concurrency_limit: usize,
-        sim_items: SimCache,
+        sim_items: SimCache<P>,
     ) -> Self {
         SimEnv::new(rollup, host, finish_by, concurrency_limit, sim_items).into()
     }
 
+    /// Set the [`SimPool`] used to run simulations, e.g. to share a pool
+    /// across environments.
+    pub fn with_pool(mut self, pool: SimPool) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// Get a reference to the [`SimPool`] used to run simulations.
+    pub const fn pool(&self) -> &SimPool {
+        &self.pool
+    }
+
     /// Get a reference the simulation cache used by this builder.
-    pub fn sim_items(&self) -> &SimCache {
+    pub fn sim_items(&self) -> &SimCache<P> {
         self.inner.sim_items()
     }
 
@@ -122,7 +158,42 @@ where
         Arc::get_mut(&mut self.inner).expect("sims dropped already").host_mut()
     }
 
-    /// Run a simulation round, returning the best item.
+    /// Checkpoint the outcomes accepted so far. See [`SimEnv::checkpoint`].
+    pub fn checkpoint(&self) -> SimCheckpoint {
+        self.inner.checkpoint()
+    }
+
+    /// Roll back to a checkpoint, discarding the outcomes accepted since.
+    /// See [`SimEnv::rollback`].
+    pub fn rollback(&mut self, checkpoint: &SimCheckpoint) {
+        self.env_mut().rollback(checkpoint).expect("sims dropped already");
+    }
+
+    /// Check if the simulation environment is shared with running
+    /// simulations, e.g. of a round cut short by the deadline.
+    pub(crate) fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.inner) > 1
+    }
+
+    /// Get a mutable reference to the simulation environment.
+    pub(crate) fn env_mut(&mut self) -> &mut SimEnv<RuDb, HostDb, RuInsp, HostInsp, P> {
+        Arc::get_mut(&mut self.inner).expect("sims dropped already")
+    }
+
+    /// Run a simulation round, returning the items to add to the block, in
+    /// order.
+    ///
+    /// Outcomes are accepted in descending score order, skipping outcomes
+    /// that conflict with those already accepted in the round, i.e. that read
+    /// state they wrote (see [`StateFootprint`]). Skipped items remain in the
+    /// cache, and are simulated again in a later round.
+    ///
+    /// Bundles with a [`BundlePlacement`] are only accepted at a position of
+    /// the block that satisfies it. Bundles placed at the current end of the
+    /// block are accepted first, and bundles whose placement can no longer
+    /// be met are removed from the cache.
+    ///
+    /// [`BundlePlacement`]: signet_bundle::BundlePlacement
     ///
     /// Preflight validity checks (nonce/balance) are performed asynchronously
     /// using the provided [`StateSource`]s. This avoids the tokio I/O
@@ -134,7 +205,79 @@ where
         max_host_gas: u64,
         async_ru_source: &AS,
         async_host_source: &AH,
-    ) -> Option<SimulatedItem>
+    ) -> Vec<SimulatedItem>
+    where
+        AS: StateSource,
+        AH: StateSource,
+    {
+        self.sim_round_inner(
+            max_gas,
+            max_host_gas,
+            async_ru_source,
+            async_host_source,
+            None,
+            Vec::new(),
+            None,
+            |_| {},
+        )
+        .await
+    }
+
+    /// Run a simulation round as [`Self::sim_round`], recording the
+    /// preflight checks, simulations and inclusions of the round in the
+    /// report.
+    pub async fn sim_round_reported<AS, AH>(
+        &mut self,
+        max_gas: u64,
+        max_host_gas: u64,
+        async_ru_source: &AS,
+        async_host_source: &AH,
+        report: &mut BuildReport,
+    ) -> Vec<SimulatedItem>
+    where
+        AS: StateSource,
+        AH: StateSource,
+    {
+        report.start_round();
+        self.sim_round_inner(
+            max_gas,
+            max_host_gas,
+            async_ru_source,
+            async_host_source,
+            Some(report),
+            Vec::new(),
+            None,
+            |_| {},
+        )
+        .await
+    }
+
+    /// Run a simulation round, recording it in the report if any.
+    ///
+    /// `displaced` items, taken out of the block by a rollback, are simulated
+    /// along with the best items of the cache. They are not in the cache, and
+    /// are not checked for validity beforehand.
+    ///
+    /// Items that fail simulation, or whose placement can no longer be met,
+    /// are removed from the cache. If `removed` is set, they are pushed to it
+    /// instead, for the caller to remove them once the round is kept, e.g.
+    /// by a re-pack that may be discarded.
+    ///
+    /// `simulated` is called with the cache once the simulations of the
+    /// round are done, before their outcomes are accepted. It is not called
+    /// if there is nothing to simulate.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn sim_round_inner<AS, AH>(
+        &mut self,
+        max_gas: u64,
+        max_host_gas: u64,
+        async_ru_source: &AS,
+        async_host_source: &AH,
+        mut report: Option<&mut BuildReport>,
+        displaced: Vec<(u128, SimItem)>,
+        mut removed: Option<&mut Vec<(u128, SimItem)>>,
+        simulated: impl FnOnce(&SimCache<P>),
+    ) -> Vec<SimulatedItem>
     where
         AS: StateSource,
         AH: StateSource,
@@ -146,6 +289,7 @@ where
             items_to_simulate = tracing::field::Empty,
             items_simulated_ok = tracing::field::Empty,
             items_simulated_err = tracing::field::Empty,
+            items_accepted = tracing::field::Empty,
         )
         .or_current();
 
@@ -161,88 +305,272 @@ where
             fallback: async_host_source,
         };
 
-        let active_sim = match self
+        let mut active_sim = match self
             .inner
             .sim_items()
-            .read_best_valid(self.inner.concurrency_limit(), &ru_source, &host_source)
+            .read_best_valid_with(
+                self.inner.concurrency_limit(),
+                &ru_source,
+                &host_source,
+                |item, validity| {
+                    if let Some(report) = report.as_deref_mut() {
+                        report.record_preflight(item, validity);
+                    }
+                },
+            )
             .await
         {
             Ok(items) => items,
             Err(error) => {
                 warn!(%error, "preflight validity check failed");
-                return None;
+                return Vec::new();
             }
         };
 
+        let displaced_items: Vec<SimItem> =
+            displaced.iter().map(|(_, item)| item.clone()).collect();
+        active_sim.extend(displaced);
         span.record("items_to_simulate", active_sim.len());
 
         if active_sim.is_empty() {
-            return None;
+            return Vec::new();
         }
 
-        // These will be moved into the blocking task.
-        let scope_span = span.clone();
-        let this = self.inner.clone();
-        let (best_tx, mut best_watcher) = watch::channel(None);
+        // Queue a simulation job per item on the worker pool. Jobs check the
+        // cancellation flag before starting, so that queued items are skipped
+        // once the round times out or is dropped.
+        let remove_failed = removed.is_none();
+        let cancel = CancelOnDrop::default();
+        let (candidates, mut candidates_rx) = mpsc::unbounded_channel();
+        let outer = trace_span!(parent: &span, "sim_thread", candidates = active_sim.len());
 
-        // Spawn a blocking task to run the simulations.
-        let sim_task = tokio::task::spawn_blocking(move || {
-            scope_span.in_scope(|| this.sim_round(max_gas, max_host_gas, best_tx, active_sim))
-        });
+        for (cache_rank, item) in active_sim {
+            let this = self.inner.clone();
+            let candidates = candidates.clone();
+            let cancelled = cancel.flag();
+            let outer = outer.clone();
+            self.pool.spawn(move || {
+                let finish_by = this.finish_by();
+                let outcome = (!cancelled.load(Ordering::Relaxed)
+                    && tokio::time::Instant::now() < finish_by)
+                    .then(|| {
+                        let identifier = item.identifier();
+                        let _ig = trace_span!(parent: &outer, "sim_task", %identifier).entered();
+                        this.sim_item(max_gas, max_host_gas, cache_rank, &item)
+                    });
+                if remove_failed && matches!(outcome, Some(Err(_))) {
+                    this.sim_items().remove_item(cache_rank, &item);
+                }
+                // Release the environment before reporting, so that it is
+                // unshared once all reports are received.
+                drop(this);
+                if let Some(outcome) = outcome {
+                    let _ = candidates.send((cache_rank, outcome, item));
+                }
+            });
+        }
+        drop(candidates);
+
+        // Collect the outcomes in score order, breaking ties by cache rank.
+        let mut outcomes: Vec<(SimOutcomeWithCache, SimItem)> = Vec::new();
+        let mut counts = SimRoundCounts::default();
+        let collect = async {
+            while let Some((cache_rank, candidate, item)) = candidates_rx.recv().await {
+                let candidate = match candidate {
+                    Ok(candidate) => candidate,
+                    Err(attempt) => {
+                        counts.err += 1;
+                        if let Some(report) = report.as_deref_mut() {
+                            report.record_attempt(&item, attempt);
+                        }
+                        if let Some(removed) = removed.as_deref_mut() {
+                            removed.push((cache_rank, item));
+                        }
+                        continue;
+                    }
+                };
+                counts.ok += 1;
+                let key = (candidate.score, candidate.cache_rank);
+                let index = outcomes.partition_point(|(c, _)| (c.score, c.cache_rank) > key);
+                outer.in_scope(|| {
+                    trace!(
+                        score = %candidate.score,
+                        cache_rank = candidate.cache_rank,
+                        position = index,
+                        "Received candidate"
+                    )
+                });
+                outcomes.insert(index, (candidate, item));
+            }
+        };
 
         // Either simulation is done, or we time out
-        let sim_counts = select! {
+        select! {
             _ = tokio::time::sleep_until(self.finish_by()) => {
                 span.in_scope(|| trace!("Sim round timed out"));
-                None
             },
-            result = sim_task => {
+            _ = collect => {
                 span.in_scope(|| trace!("Sim round done"));
-                result.ok()
             },
         };
 
-        if let Some(counts) = sim_counts {
-            span.record("items_simulated_ok", counts.ok);
-            span.record("items_simulated_err", counts.err);
-        }
-
-        let _guard = span.entered();
+        span.record("items_simulated_ok", counts.ok);
+        span.record("items_simulated_err", counts.err);
 
-        // Check what the current best outcome is.
-        let best = best_watcher.borrow_and_update();
-        trace!(score = %best.as_ref().map(|candidate| candidate.score).unwrap_or_default(), "Read outcome from channel");
-        let outcome = best.as_ref()?;
+        // Skip the queued jobs, and wait for the running ones to stop. Running
+        // simulations are stopped by the time limit at the deadline. Late
+        // outcomes are discarded, but rejected items are removed from the
+        // cache, and are reported.
+        drop(cancel);
+        while let Some((cache_rank, candidate, item)) = candidates_rx.recv().await {
+            let Err(attempt) = candidate else { continue };
+            if let Some(report) = report.as_deref_mut() {
+                report.record_attempt(&item, attempt);
+            }
+            if let Some(removed) = removed.as_deref_mut() {
+                removed.push((cache_rank, item));
+            }
+        }
+        simulated(self.inner.sim_items());
 
-        // Remove the item from the cache.
-        let item = self.sim_items().remove(outcome.cache_rank)?;
+        let span = span.entered();
+        trace!(outcomes = outcomes.len(), "Read outcomes from channel");
 
         // We can expect here as all of our simulations are done and cleaned up.
         let inner = Arc::get_mut(&mut self.inner).expect("sims dropped already");
 
-        // Accept the cache from the simulation.
-        inner.rollup_mut().accept_cache_ref(&outcome.rollup_cache).ok()?;
-        // Accept the host cache from the simulation.
-        inner.host_mut().accept_cache_ref(&outcome.host_cache).ok()?;
-        // Accept the aggregate fills and orders.
-        inner
-            .rollup_mut()
-            .accept_aggregates(&outcome.bundle_fills, &outcome.bundle_orders)
-            .expect("checked during simulation");
-
-        debug!(
-            score = %outcome.score,
-            gas_used = outcome.gas_used,
-            host_gas_used = outcome.host_gas_used,
-            identifier = %item.identifier(),
-            "Selected simulated item",
-        );
-
-        Some(SimulatedItem {
-            gas_used: outcome.gas_used,
-            host_gas_used: outcome.host_gas_used,
-            score: outcome.score,
-            item,
-        })
+        // Items placed at the current end of the block, e.g. top of block
+        // bundles in an empty block, are accepted first, as they were
+        // simulated against the state at that position. The sort is stable,
+        // keeping them in score order.
+        outcomes.sort_by_key(|(_, item)| !inner.position().anchors(item));
+
+        let mut committed = StateFootprint::default();
+        let mut gas_used = 0u64;
+        let mut host_gas_used = 0u64;
+        let mut accepted = Vec::new();
+
+        for (outcome, item) in outcomes {
+            let identifier = item.identifier();
+
+            let rejection = if let Err(rejection) = inner.position().check(&item) {
+                trace!(%identifier, %rejection, "Outcome placement not satisfied");
+                Some(rejection)
+            } else if outcome.footprint.conflicts_with(&committed) {
+                trace!(%identifier, "Outcome conflicts with accepted outcomes");
+                Some(RejectionReason::Conflict)
+            } else if gas_used + outcome.block_gas() > max_gas
+                || host_gas_used + outcome.host_gas_used > max_host_gas
+            {
+                trace!(%identifier, "Outcome exceeds remaining gas");
+                Some(RejectionReason::RoundGasExceeded)
+            } else if inner
+                .rollup_env()
+                .fill_state()
+                .check_ru_tx_events(&outcome.bundle_fills, &outcome.bundle_orders)
+                .is_err()
+            {
+                trace!(%identifier, "Outcome fills insufficient after accepted outcomes");
+                Some(RejectionReason::InsufficientFills)
+            } else if !displaced_items.contains(&item)
+                && inner.sim_items().remove_item(outcome.cache_rank, &item).is_none()
+            {
+                // If the item was replaced or cancelled during the round, the
+                // outcome is discarded.
+                Some(RejectionReason::Replaced)
+            } else {
+                None
+            };
+
+            if let Some(report) = report.as_deref_mut() {
+                report.record_attempt(&item, SimAttempt::new(&outcome, rejection.clone()));
+            }
+            if let Some(rejection) = rejection {
+                // The placement can't be met later in the block.
+                if matches!(rejection, RejectionReason::PlacementUnsatisfiable { .. }) {
+                    match removed.as_deref_mut() {
+                        Some(removed) => removed.push((outcome.cache_rank, item)),
+                        None => {
+                            inner.sim_items().remove_item(outcome.cache_rank, &item);
+                        }
+                    }
+                }
+                continue;
+            }
+
+            if inner.accept_outcome(&outcome, &item).is_err() {
+                break;
+            }
+            committed.extend(&outcome.footprint);
+            gas_used += outcome.block_gas();
+            host_gas_used += outcome.host_gas_used;
+
+            debug!(
+                score = %outcome.score,
+                gas_used = outcome.gas_used,
+                host_gas_used = outcome.host_gas_used,
+                %identifier,
+                "Selected simulated item",
+            );
+
+            if let Some(report) = report.as_deref_mut() {
+                report.record_included(&item);
+            }
+
+            // The outcome's profit was credited to the committed beneficiary
+            // balance, which replaces the simulated one in the state changes.
+            let mut rollup_cache = outcome.rollup_cache;
+            let beneficiary = inner.rollup_env().block().beneficiary;
+            if let (Some(acct), Some(committed)) = (
+                rollup_cache.accounts.get_mut(&beneficiary),
+                inner.rollup_env().db().cache().accounts.get(&beneficiary),
+            ) {
+                acct.info.balance = committed.info.balance;
+            }
+
+            accepted.push(SimulatedItem {
+                gas_used: outcome.gas_used,
+                host_gas_used: outcome.host_gas_used,
+                score: outcome.score,
+                item,
+                refund: outcome.refund,
+                receipts: outcome.receipts,
+                dropped_txs: outcome.dropped_txs,
+                bundle_fills: outcome.bundle_fills,
+                bundle_orders: outcome.bundle_orders,
+                rollup_cache,
+                host_cache: outcome.host_cache,
+            });
+        }
+
+        span.record("items_accepted", accepted.len());
+        accepted
+    }
+}
+
+/// Counts of items from a simulation round.
+#[derive(Debug, Clone, Copy, Default)]
+struct SimRoundCounts {
+    /// Items that simulated successfully.
+    ok: u32,
+    /// Items that failed or were rejected.
+    err: u32,
+}
+
+/// A cancellation flag for the jobs of a simulation round, set when the
+/// round ends or is dropped.
+#[derive(Debug, Default)]
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl CancelOnDrop {
+    /// Get a handle to the flag.
+    fn flag(&self) -> Arc<AtomicBool> {
+        self.0.clone()
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
     }
 }
@@ -1,22 +1,25 @@
-use crate::{env::RollupEnv, HostEnv, SimCache, SimDb, SimItem, SimOutcomeWithCache};
-use alloy::{consensus::TxEnvelope, hex};
+use crate::{
+    env::{BlockPosition, RollupEnv},
+    AccessSet, DefaultScoring, HostEnv, Refund, RejectionReason, ScoringPolicy, SimAttempt,
+    SimCache, SimDb, SimItem, SimOutcomeWithCache, SimulatedItem, StateFootprint,
+};
+use alloy::{
+    consensus::{transaction::Recovered, Receipt, ReceiptEnvelope, TxEnvelope},
+    hex,
+    primitives::U256,
+};
 use core::fmt;
 use signet_bundle::{RecoveredBundle, SignetEthBundleDriver, SignetEthBundleError};
 use signet_evm::SignetInspector;
-use signet_types::constants::SignetSystemConstants;
-use std::{
-    borrow::Cow,
-    sync::{
-        atomic::{AtomicU32, Ordering},
-        Arc,
-    },
-};
-use tokio::sync::{mpsc, watch};
-use tracing::{debug, instrument, trace, trace_span};
+use signet_types::{constants::SignetSystemConstants, AggregateFills};
+use std::borrow::Cow;
+use tracing::{debug, instrument, trace};
 use trevm::{
+    db::{ArcUpgradeError, TryCachingDb},
     helpers::Ctx,
     revm::{
         context::result::{EVMError, ExecutionResult},
+        database::Cache,
         inspector::NoOpInspector,
         DatabaseRef, Inspector,
     },
@@ -24,7 +27,13 @@ use trevm::{
 };
 
 /// A simulation environment.
-pub struct SimEnv<RuDb, HostDb, RuInsp = NoOpInspector, HostInsp = NoOpInspector> {
+pub struct SimEnv<
+    RuDb,
+    HostDb,
+    RuInsp = NoOpInspector,
+    HostInsp = NoOpInspector,
+    P = DefaultScoring,
+> {
     /// The rollup environment.
     rollup: RollupEnv<RuDb, RuInsp>,
 
@@ -32,16 +41,33 @@ pub struct SimEnv<RuDb, HostDb, RuInsp = NoOpInspector, HostInsp = NoOpInspector
     host: HostEnv<HostDb, HostInsp>,
 
     /// The cache of items to simulate.
-    sim_items: SimCache,
+    sim_items: SimCache<P>,
 
     /// The instant by which the simulation should finish.
     finish_by: tokio::time::Instant,
 
     /// The maximum number of concurrent simulations to run.
     concurrency_limit: usize,
+
+    /// The transactions of the accepted outcomes.
+    position: BlockPosition,
+}
+
+/// A checkpoint of the outcomes accepted by a [`SimEnv`], to roll back to
+/// with [`SimEnv::rollback`].
+#[derive(Debug, Clone)]
+pub struct SimCheckpoint {
+    /// The committed rollup state.
+    rollup: Cache,
+    /// The committed host state.
+    host: Cache,
+    /// The rollup fill state.
+    fill_state: AggregateFills,
+    /// The transactions of the accepted outcomes.
+    position: BlockPosition,
 }
 
-impl<RuDb, HostDb, RuInsp, HostInsp> fmt::Debug for SimEnv<RuDb, HostDb, RuInsp, HostInsp> {
+impl<RuDb, HostDb, RuInsp, HostInsp, P> fmt::Debug for SimEnv<RuDb, HostDb, RuInsp, HostInsp, P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SimEnv")
             .field("finish_by", &self.finish_by)
@@ -50,16 +76,23 @@ impl<RuDb, HostDb, RuInsp, HostInsp> fmt::Debug for SimEnv<RuDb, HostDb, RuInsp,
     }
 }
 
-impl<RuDb, HostDb, RuInsp, HostInsp> SimEnv<RuDb, HostDb, RuInsp, HostInsp> {
+impl<RuDb, HostDb, RuInsp, HostInsp, P> SimEnv<RuDb, HostDb, RuInsp, HostInsp, P> {
     /// Create a new `SimEnv` instance.
     pub const fn new(
         rollup: RollupEnv<RuDb, RuInsp>,
         host: HostEnv<HostDb, HostInsp>,
         finish_by: tokio::time::Instant,
         concurrency_limit: usize,
-        sim_items: SimCache,
+        sim_items: SimCache<P>,
     ) -> Self {
-        Self { rollup, host, finish_by, concurrency_limit, sim_items }
+        Self {
+            rollup,
+            host,
+            finish_by,
+            concurrency_limit,
+            sim_items,
+            position: BlockPosition::new(),
+        }
     }
 
     /// Get a reference to the rollup environment.
@@ -88,7 +121,7 @@ impl<RuDb, HostDb, RuInsp, HostInsp> SimEnv<RuDb, HostDb, RuInsp, HostInsp> {
     }
 
     /// Get a reference to the cache of items to simulate.
-    pub const fn sim_items(&self) -> &SimCache {
+    pub const fn sim_items(&self) -> &SimCache<P> {
         &self.sim_items
     }
 
@@ -106,14 +139,25 @@ impl<RuDb, HostDb, RuInsp, HostInsp> SimEnv<RuDb, HostDb, RuInsp, HostInsp> {
     pub const fn concurrency_limit(&self) -> usize {
         self.concurrency_limit
     }
+
+    /// Set the concurrency limit.
+    pub const fn set_concurrency_limit(&mut self, concurrency_limit: usize) {
+        self.concurrency_limit = concurrency_limit;
+    }
+
+    /// Get the position in the block of the next accepted outcome.
+    pub(crate) const fn position(&self) -> &BlockPosition {
+        &self.position
+    }
 }
 
-impl<RuDb, HostDb, RuInsp, HostInsp> SimEnv<RuDb, HostDb, RuInsp, HostInsp>
+impl<RuDb, HostDb, RuInsp, HostInsp, P> SimEnv<RuDb, HostDb, RuInsp, HostInsp, P>
 where
     RuDb: DatabaseRef<Error: 'static> + Send + Sync,
     RuInsp: Inspector<Ctx<SimDb<RuDb>>> + Default + Sync,
     HostDb: DatabaseRef<Error: 'static> + Send + Sync,
     HostInsp: Inspector<Ctx<SimDb<HostDb>>> + Default + Sync,
+    P: ScoringPolicy,
 {
     /// Simulates a transaction in the context of a block.
     ///
@@ -124,8 +168,9 @@ where
         &self,
         cache_rank: u128,
         transaction: &TxEnvelope,
+        finish_by: tokio::time::Instant,
     ) -> Result<SimOutcomeWithCache, SignetEthBundleError<SimDb<RuDb>>> {
-        let trevm = self.rollup.create_evm(self.finish_by);
+        let trevm = self.rollup.create_evm(finish_by);
 
         // Get the initial beneficiary balance
         let beneficiary = trevm.beneficiary();
@@ -156,6 +201,20 @@ where
 
                 self.rollup.fill_state().check_ru_tx_events(&bundle_fills, &bundle_orders)?;
 
+                let receipt = Receipt {
+                    status: success.into(),
+                    cumulative_gas_used: gas_used,
+                    logs: trevm.result().logs().to_vec(),
+                };
+                let receipt = ReceiptEnvelope::from_typed(transaction.tx_type(), receipt);
+
+                // The beneficiary's balance is excluded, as fee payments from
+                // separate outcomes are added up when they are accepted.
+                let footprint = StateFootprint::new(
+                    AccessSet::from_state(&trevm.result_and_state().state, Some(beneficiary)),
+                    AccessSet::default(),
+                );
+
                 // We will later commit these to the trevm DB when the
                 // SimOutcome is accepted.
                 let cache = trevm.accept_state().into_db().into_cache();
@@ -165,11 +224,11 @@ where
                     .get(&beneficiary)
                     .map(|acct| acct.info.balance)
                     .unwrap_or_default();
-                let score = beneficiary_balance.saturating_sub(initial_beneficiary_balance);
+                let profit = beneficiary_balance.saturating_sub(initial_beneficiary_balance);
 
                 trace!(
                     gas_used,
-                    score = %score,
+                    profit = %profit,
                     reverted = !success,
                     halted,
                     halt_reason = ?if halted { halt_reason } else { None },
@@ -180,13 +239,19 @@ where
                 // Create the outcome
                 Ok(SimOutcomeWithCache {
                     cache_rank,
-                    score,
+                    score: profit,
+                    profit,
+                    beneficiary_balance: initial_beneficiary_balance,
+                    refund: None,
                     rollup_cache: cache,
                     host_cache: Default::default(),
                     host_gas_used: 0,
                     gas_used,
                     bundle_fills,
                     bundle_orders,
+                    receipts: vec![receipt],
+                    dropped_txs: Vec::new(),
+                    footprint,
                 })
             }
             Err(e) => Err(SignetEthBundleError::from(e.into_error())),
@@ -205,12 +270,19 @@ where
     {
         let trevm = self.rollup.create_evm(self.finish_by);
 
+        // Get the initial beneficiary balance
+        let beneficiary = trevm.beneficiary();
+        let beneficiary_balance =
+            trevm.try_read_balance_ref(beneficiary).map_err(EVMError::Database)?;
+
         let mut driver = SignetEthBundleDriver::new_with_fill_state(
             bundle,
             self.host.create_evm(self.finish_by),
             self.finish_by,
             Cow::Borrowed(self.rollup.fill_state()),
-        );
+        )
+        .with_precompile_registry(Cow::Borrowed(self.rollup.precompile_registry()))
+        .with_state_recording();
 
         // Run the bundle
         let trevm = match driver.run_bundle(trevm) {
@@ -219,8 +291,13 @@ where
         };
 
         // Build the SimOutcome
-        let score = driver.beneficiary_balance_increase();
+        let profit: U256 = driver.beneficiary_balance_increase().to();
         let outputs = driver.into_outputs();
+        let refund = Refund::for_bundle(
+            bundle,
+            outputs.refundable_balance_increase,
+            self.rollup.block().basefee,
+        );
 
         // This is redundant with the driver, however, we double check here.
         // If perf is hit too much we can remove.
@@ -229,159 +306,234 @@ where
             .check_ru_tx_events(&outputs.bundle_fills, &outputs.bundle_orders)?;
 
         let host_cache = outputs.host_evm.map(|evm| evm.into_db().into_cache()).unwrap_or_default();
+        let rollup_cache = trevm.into_db().into_cache();
+
+        // The caches only hold the state written by the bundle, so the state
+        // read by each transaction is added. Invalid transactions that were
+        // dropped loaded no state, but their validity depends on their
+        // sender.
+        let dropped_senders = |txs: &[Recovered<TxEnvelope>]| {
+            txs.iter()
+                .filter(|tx| outputs.dropped_txs.contains(tx.hash()))
+                .map(Recovered::signer)
+                .collect::<Vec<_>>()
+        };
+        let mut rollup_access =
+            AccessSet::from_cache(&rollup_cache, self.rollup.db(), Some(beneficiary))
+                .map_err(EVMError::Database)?;
+        rollup_access.read_states(&outputs.rollup_states, Some(beneficiary));
+        rollup_access.read_accounts(dropped_senders(bundle.txs()));
+        let mut host_access = AccessSet::from_cache(&host_cache, self.host.db(), None)
+            .map_err(|_| SignetEthBundleError::HostSimulation("host state read error"))?;
+        host_access.read_states(&outputs.host_states, None);
+        host_access.read_accounts(dropped_senders(bundle.host_txs()));
+        let footprint = StateFootprint::new(rollup_access, host_access);
         trace!(
             gas_used = outputs.total_gas_used,
             host_gas_used = outputs.total_host_gas_used,
-            %score,
+            %profit,
+            refund = ?refund,
             "Bundle simulation successful"
         );
 
         Ok(SimOutcomeWithCache {
             cache_rank,
-            score: score.to(),
-            rollup_cache: trevm.into_db().into_cache(),
+            score: profit.saturating_sub(refund.map(|refund| refund.total()).unwrap_or_default()),
+            profit,
+            beneficiary_balance,
+            refund,
+            rollup_cache,
             host_cache,
             gas_used: outputs.total_gas_used,
             host_gas_used: outputs.total_host_gas_used,
             bundle_fills: outputs.bundle_fills,
             bundle_orders: outputs.bundle_orders,
+            receipts: outputs.receipts,
+            dropped_txs: outputs.dropped_txs,
+            footprint,
         })
     }
 
-    /// Simulates a transaction or bundle in the context of a block.
+    /// Simulates a transaction or bundle in the context of a block, and
+    /// scores the outcome with the [`ScoringPolicy`].
     fn simulate(
         &self,
         cache_rank: u128,
         item: &SimItem,
     ) -> Result<SimOutcomeWithCache, SignetEthBundleError<SimDb<RuDb>>> {
-        match item {
+        let mut outcome = match item {
             SimItem::Bundle(bundle) => self.simulate_bundle(cache_rank, bundle),
-            SimItem::Tx(tx) => self.simulate_tx(cache_rank, tx),
+            SimItem::Tx(tx) => self.simulate_tx(cache_rank, tx, self.finish_by),
+        }?;
+        outcome.score = self.sim_items.policy().score(item, &outcome);
+        Ok(outcome)
+    }
+
+    /// Checkpoint the outcomes accepted so far, i.e. the committed rollup
+    /// and host state, the fill state and the block position.
+    pub fn checkpoint(&self) -> SimCheckpoint {
+        SimCheckpoint {
+            rollup: self.rollup.db().cache().clone(),
+            host: self.host.db().cache().clone(),
+            fill_state: self.rollup.fill_state().clone(),
+            position: self.position.clone(),
+        }
+    }
+
+    /// Roll back to a checkpoint, discarding the outcomes accepted since.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the databases are shared, e.g. by running simulations.
+    pub fn rollback(&mut self, checkpoint: &SimCheckpoint) -> Result<(), ArcUpgradeError> {
+        *self.rollup.db_mut().try_cache_mut()? = checkpoint.rollup.clone();
+        *self.host.db_mut().try_cache_mut()? = checkpoint.host.clone();
+        *self.rollup.fill_state_mut() = checkpoint.fill_state.clone();
+        self.position = checkpoint.position.clone();
+        Ok(())
+    }
+
+    /// Accept a simulation outcome, committing its state changes and updating
+    /// the fill state and block position.
+    ///
+    /// The change of the beneficiary balance during the simulation is applied
+    /// to its committed balance, rather than overwriting it, so that several
+    /// outcomes simulated against the same state can be accepted. The
+    /// outcome's fills and orders must have been checked against the current
+    /// fill state.
+    pub(crate) fn accept_outcome(
+        &mut self,
+        outcome: &SimOutcomeWithCache,
+        item: &SimItem,
+    ) -> Result<(), ArcUpgradeError> {
+        let beneficiary = self.rollup.block().beneficiary;
+        let committed_balance =
+            self.rollup.db().cache().accounts.get(&beneficiary).map(|acct| acct.info.balance);
+
+        // Accept the cache from the simulation.
+        self.rollup.accept_cache_ref(&outcome.rollup_cache)?;
+        // Accept the host cache from the simulation.
+        self.host.accept_cache_ref(&outcome.host_cache)?;
+
+        // Apply the change of the beneficiary balance during the simulation,
+        // which is negative if the beneficiary spent more than it received.
+        let balance = outcome.rollup_cache.accounts.get(&beneficiary).map(|acct| acct.info.balance);
+        if let (Some(committed), Some(balance)) = (committed_balance, balance) {
+            if let Some(acct) = self.rollup.db_mut().try_cache_mut()?.accounts.get_mut(&beneficiary)
+            {
+                acct.info.balance =
+                    committed.saturating_add(balance).saturating_sub(outcome.beneficiary_balance);
+            }
         }
+
+        // Accept the aggregate fills and orders.
+        self.rollup
+            .accept_aggregates(&outcome.bundle_fills, &outcome.bundle_orders)
+            .expect("checked before accepting");
+        self.position.append(item, &outcome.dropped_txs);
+        Ok(())
+    }
+
+    /// Simulate a refund transaction signed by the builder, and apply it to
+    /// the committed state, returning the simulated item to add to the
+    /// block.
+    ///
+    /// Unlike [`Self::accept_outcome`], the beneficiary balance is not
+    /// adjusted, as the builder may pay refunds from it. Refunds are applied
+    /// after the simulation rounds, so `finish_by` is the deadline of the
+    /// refund transaction rather than of the build.
+    ///
+    /// Returns `None` if the transaction is invalid or reverts.
+    pub(crate) fn apply_refund(
+        &mut self,
+        tx: Recovered<TxEnvelope>,
+        finish_by: tokio::time::Instant,
+    ) -> Option<SimulatedItem> {
+        let outcome = match self.simulate_tx(0, &tx, finish_by) {
+            Ok(outcome) if outcome.receipts.iter().all(|receipt| receipt.status()) => outcome,
+            Ok(_) => {
+                debug!(tx_hash = %tx.hash(), "refund transaction reverted");
+                return None;
+            }
+            Err(error) => {
+                debug!(tx_hash = %tx.hash(), %error, "refund transaction failed");
+                return None;
+            }
+        };
+        self.rollup.accept_cache_ref(&outcome.rollup_cache).ok()?;
+
+        let item = SimItem::from(tx);
+        self.position.append(&item, &[]);
+        Some(SimulatedItem {
+            score: outcome.score,
+            gas_used: outcome.gas_used,
+            host_gas_used: 0,
+            item,
+            refund: None,
+            receipts: outcome.receipts,
+            dropped_txs: Vec::new(),
+            bundle_fills: outcome.bundle_fills,
+            bundle_orders: outcome.bundle_orders,
+            rollup_cache: outcome.rollup_cache,
+            host_cache: outcome.host_cache,
+        })
     }
 
-    /// Run a simulation round, returning counts of successful and failed items.
-    pub(crate) fn sim_round(
-        self: Arc<Self>,
+    /// Simulate an item of a simulation round, returning the outcome if it
+    /// is a candidate for inclusion in the block.
+    ///
+    /// Items that fail simulation, have a zero score, or exceed the gas
+    /// limits are rejected, and the rejected attempt is returned for the
+    /// caller to remove them from the cache.
+    pub(crate) fn sim_item(
+        &self,
         max_gas: u64,
         max_host_gas: u64,
-        best_tx: watch::Sender<Option<SimOutcomeWithCache>>,
-        active_sim: Vec<(u128, SimItem)>,
-    ) -> SimRoundCounts {
-        // Create a channel to send the results back.
-        let (candidates, mut candidates_rx) = mpsc::channel(self.concurrency_limit);
-
-        let outer = trace_span!("sim_thread", candidates = active_sim.len()).or_current();
-        let outer_ref = &outer;
-        let _og = outer.enter();
-
-        let ok_count = AtomicU32::new(0);
-        let err_count = AtomicU32::new(0);
-
-        // to be used in the scope
-        let this_ref = self.clone();
-
-        std::thread::scope(|scope| {
-            // Spawn a thread per bundle to simulate.
-            for (cache_rank, item) in active_sim.into_iter() {
-                let c = candidates.clone();
-                let this_ref = this_ref.clone();
-                let ok_ref = &ok_count;
-                let err_ref = &err_count;
-                scope.spawn(move || {
-                    let identifier = item.identifier();
-                    let _ig = trace_span!(parent: outer_ref, "sim_task", %identifier).entered();
-
-                    // If simulation is succesful, send the outcome via the
-                    // channel.
-
-                    match this_ref.simulate(cache_rank, &item) {
-                        Ok(candidate) if candidate.score.is_zero() => {
-                            debug!(
-                                %identifier,
-                                failure_reason = "zero score candidate",
-                                "simulation rejected",
-                            );
-                            err_ref.fetch_add(1, Ordering::Relaxed);
-                        }
-                        Ok(candidate) if candidate.host_gas_used > max_host_gas => {
-                            debug!(
-                                %identifier,
-                                host_gas_used = candidate.host_gas_used,
-                                max_host_gas,
-                                failure_reason = "host gas limit exceeded",
-                                "simulation rejected",
-                            );
-                            err_ref.fetch_add(1, Ordering::Relaxed);
-                        }
-                        Ok(candidate) if candidate.gas_used > max_gas => {
-                            debug!(
-                                %identifier,
-                                gas_used = candidate.gas_used,
-                                max_gas,
-                                failure_reason = "gas limit exceeded",
-                                "simulation rejected",
-                            );
-                            err_ref.fetch_add(1, Ordering::Relaxed);
-                        }
-                        Ok(candidate) => {
-                            ok_ref.fetch_add(1, Ordering::Relaxed);
-                            // shortcut return on success
-                            let _ = c.blocking_send(candidate);
-                            return;
-                        }
-                        Err(error) => {
-                            debug!(
-                                %identifier,
-                                failure_reason = %error,
-                                "simulation failed",
-                            );
-                            err_ref.fetch_add(1, Ordering::Relaxed);
-                        }
-                    };
-                    // fall through applies to all errors, occurs if
-                    // the simulation fails or the gas limit is exceeded.
-                    this_ref.sim_items.remove(cache_rank);
-                });
+        cache_rank: u128,
+        item: &SimItem,
+    ) -> Result<SimOutcomeWithCache, SimAttempt> {
+        let identifier = item.identifier();
+
+        let rejected = match self.simulate(cache_rank, item) {
+            Ok(candidate) if candidate.score.is_zero() => {
+                debug!(%identifier, failure_reason = "zero score candidate", "simulation rejected",);
+                SimAttempt::new(&candidate, Some(RejectionReason::ZeroScore))
+            }
+            Ok(candidate) if candidate.host_gas_used > max_host_gas => {
+                debug!(
+                    %identifier,
+                    host_gas_used = candidate.host_gas_used,
+                    max_host_gas,
+                    failure_reason = "host gas limit exceeded",
+                    "simulation rejected",
+                );
+                let reason = RejectionReason::HostGasLimitExceeded {
+                    host_gas_used: candidate.host_gas_used,
+                    max_host_gas,
+                };
+                SimAttempt::new(&candidate, Some(reason))
             }
-            // Drop the TX so that the channel is closed when all threads
-            // are done.
-            drop(candidates);
-            // Wait for each thread to finish. Find the best outcome.
-            while let Some(candidate) = candidates_rx.blocking_recv() {
-                // Update the best score and send it to the channel.
-                let _ = best_tx.send_if_modified(|current| {
-                    let best_score = current.as_ref().map(|c| c.score).unwrap_or_default();
-                    let current_cache_rank = current.as_ref().map(|c| c.cache_rank);
-
-                    let changed = candidate.score > best_score;
-                    if changed {
-                        trace!(
-                            old_best = ?best_score,
-                            old_cache_rank = current_cache_rank,
-                            new_best = %candidate.score,
-                            new_cache_rank = candidate.cache_rank,
-                            "Found better candidate"
-                        );
-                        *current = Some(candidate);
-                    }
-                    changed
-                });
+            Ok(candidate) if candidate.block_gas() > max_gas => {
+                debug!(
+                    %identifier,
+                    gas_used = candidate.block_gas(),
+                    max_gas,
+                    failure_reason = "gas limit exceeded",
+                    "simulation rejected",
+                );
+                let reason =
+                    RejectionReason::GasLimitExceeded { gas_used: candidate.block_gas(), max_gas };
+                SimAttempt::new(&candidate, Some(reason))
             }
-        });
-
-        SimRoundCounts {
-            ok: ok_count.load(Ordering::Relaxed),
-            err: err_count.load(Ordering::Relaxed),
-        }
+            // shortcut return on success
+            Ok(candidate) => return Ok(candidate),
+            Err(error) => {
+                debug!(%identifier, failure_reason = %error, "simulation failed",);
+                SimAttempt::failed(RejectionReason::SimulationFailed { error: error.to_string() })
+            }
+        };
+        // fall through applies to all errors, occurs if
+        // the simulation fails or the gas limit is exceeded.
+        Err(rejected)
     }
 }
-
-/// Counts of items from a simulation round.
-#[derive(Debug, Clone, Copy)]
-pub(crate) struct SimRoundCounts {
-    /// Items that simulated successfully.
-    pub ok: u32,
-    /// Items that failed or were rejected.
-    pub err: u32,
-}
@@ -0,0 +1,275 @@
+use alloy::primitives::{Address, U256};
+use std::collections::HashSet;
+use trevm::revm::{
+    database::{AccountState, Cache},
+    state::{AccountInfo, EvmState},
+    DatabaseRef,
+};
+
+/// A location in the state of a chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Location {
+    /// The nonce, balance and code of an account.
+    Account(Address),
+    /// A storage slot of an account.
+    Slot(Address, U256),
+}
+
+/// Check if the nonce, balance or code of an account changed.
+fn info_changed(original: Option<&AccountInfo>, info: &AccountInfo) -> bool {
+    original.is_none_or(|original| {
+        original.nonce != info.nonce
+            || original.balance != info.balance
+            || original.code_hash != info.code_hash
+    })
+}
+
+/// Check if the nonce or code of the beneficiary changed. Changes to its
+/// balance are ignored, as fee payments commute.
+fn beneficiary_changed(original: Option<&AccountInfo>, info: &AccountInfo) -> bool {
+    original
+        .is_none_or(|original| original.nonce != info.nonce || original.code_hash != info.code_hash)
+}
+
+/// The state locations read and written on a single chain by a simulated
+/// item.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessSet {
+    reads: HashSet<Location>,
+    writes: HashSet<Location>,
+}
+
+impl AccessSet {
+    /// Record a read of the location.
+    fn read(&mut self, location: Location) {
+        self.reads.insert(location);
+    }
+
+    /// Record a read and write of the location.
+    fn write(&mut self, location: Location) {
+        self.reads.insert(location);
+        self.writes.insert(location);
+    }
+
+    /// Create an access set from the state loaded by a transaction.
+    ///
+    /// If `beneficiary` is provided, changes to its balance are not recorded.
+    pub fn from_state(state: &EvmState, beneficiary: Option<Address>) -> Self {
+        let mut this = Self::default();
+
+        for (address, account) in state {
+            let location = Location::Account(*address);
+            let original = Some(account.original_info.as_ref());
+            if account.is_created() || account.is_selfdestructed() {
+                this.write(location);
+            } else if Some(*address) == beneficiary {
+                if beneficiary_changed(original, &account.info) {
+                    this.write(location);
+                }
+            } else if info_changed(original, &account.info) {
+                this.write(location);
+            } else {
+                this.read(location);
+            }
+
+            for (slot, value) in &account.storage {
+                let location = Location::Slot(*address, *slot);
+                if value.is_changed() {
+                    this.write(location);
+                } else {
+                    this.read(location);
+                }
+            }
+        }
+
+        this
+    }
+
+    /// Create an access set from the state changes of a simulation, by
+    /// comparing them to the state they were simulated against.
+    ///
+    /// Locations that were read but not written by the simulation are not in
+    /// the cache, and are not recorded. Record them with
+    /// [`Self::read_states`]. If `beneficiary` is provided, changes to its
+    /// balance are not recorded.
+    pub fn from_cache<Db: DatabaseRef>(
+        cache: &Cache,
+        db: &Db,
+        beneficiary: Option<Address>,
+    ) -> Result<Self, Db::Error> {
+        let mut this = Self::default();
+
+        for (address, account) in &cache.accounts {
+            let location = Location::Account(*address);
+            let original = db.basic_ref(*address)?;
+            if matches!(
+                account.account_state,
+                AccountState::StorageCleared | AccountState::NotExisting
+            ) {
+                this.write(location);
+            } else if Some(*address) == beneficiary {
+                if beneficiary_changed(original.as_ref(), &account.info) {
+                    this.write(location);
+                }
+            } else if info_changed(original.as_ref(), &account.info) {
+                this.write(location);
+            } else {
+                this.read(location);
+            }
+
+            for (slot, value) in &account.storage {
+                let location = Location::Slot(*address, *slot);
+                if db.storage_ref(*address, *slot)? != *value {
+                    this.write(location);
+                } else {
+                    this.read(location);
+                }
+            }
+        }
+
+        Ok(this)
+    }
+
+    /// Record the locations loaded by transactions as reads, e.g. the state
+    /// loaded by each transaction of a bundle, including the transactions
+    /// whose state changes were discarded.
+    ///
+    /// As in [`Self::from_state`], the `beneficiary` is not recorded.
+    pub fn read_states<'a>(
+        &mut self,
+        states: impl IntoIterator<Item = &'a EvmState>,
+        beneficiary: Option<Address>,
+    ) {
+        for state in states {
+            for (address, account) in state {
+                if Some(*address) != beneficiary {
+                    self.read(Location::Account(*address));
+                }
+                for slot in account.storage.keys() {
+                    self.read(Location::Slot(*address, *slot));
+                }
+            }
+        }
+    }
+
+    /// Record reads of the accounts, e.g. of the senders of transactions
+    /// that were invalid, and loaded no state.
+    pub fn read_accounts(&mut self, addresses: impl IntoIterator<Item = Address>) {
+        for address in addresses {
+            self.read(Location::Account(address));
+        }
+    }
+
+    /// Check if this access set reads any location written by `other`.
+    pub fn reads_writes_of(&self, other: &Self) -> bool {
+        !self.reads.is_disjoint(&other.writes)
+    }
+
+    /// Extend this access set with the locations of another.
+    pub fn extend(&mut self, other: &Self) {
+        self.reads.extend(other.reads.iter().copied());
+        self.writes.extend(other.writes.iter().copied());
+    }
+}
+
+/// The state locations read and written on the rollup and host chains by a
+/// simulated item.
+///
+/// Outcomes simulated against the same state can be committed together if
+/// they do not conflict. Committing an outcome after others is equivalent to
+/// simulating it after them, as long as it does not read any location they
+/// wrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateFootprint {
+    /// The rollup state accessed.
+    pub rollup: AccessSet,
+    /// The host state accessed.
+    pub host: AccessSet,
+}
+
+impl StateFootprint {
+    /// Create a new footprint from the rollup and host access sets.
+    pub const fn new(rollup: AccessSet, host: AccessSet) -> Self {
+        Self { rollup, host }
+    }
+
+    /// Check if this footprint conflicts with previously committed
+    /// footprints, i.e. it reads a location they wrote.
+    pub fn conflicts_with(&self, committed: &Self) -> bool {
+        self.rollup.reads_writes_of(&committed.rollup) || self.host.reads_writes_of(&committed.host)
+    }
+
+    /// Extend this footprint with the locations of another.
+    pub fn extend(&mut self, other: &Self) {
+        self.rollup.extend(&other.rollup);
+        self.host.extend(&other.host);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use trevm::revm::state::{Account, EvmStorageSlot};
+
+    #[test]
+    fn conflicts() {
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+
+        let mut writes_alice = AccessSet::default();
+        writes_alice.write(Location::Account(alice));
+        let mut reads_alice = AccessSet::default();
+        reads_alice.read(Location::Account(alice));
+        let mut writes_bob_slot = AccessSet::default();
+        writes_bob_slot.write(Location::Slot(bob, U256::ZERO));
+        let mut reads_bob_other_slot = AccessSet::default();
+        reads_bob_other_slot.read(Location::Account(bob));
+        reads_bob_other_slot.read(Location::Slot(bob, U256::from(1)));
+
+        let footprint =
+            |rollup: &AccessSet| StateFootprint::new(rollup.clone(), Default::default());
+
+        // Reading a written location conflicts.
+        assert!(footprint(&reads_alice).conflicts_with(&footprint(&writes_alice)));
+        assert!(footprint(&writes_alice).conflicts_with(&footprint(&writes_alice)));
+        // Reading a location written later does not.
+        assert!(!footprint(&writes_alice).conflicts_with(&footprint(&reads_alice)));
+        // Disjoint slots and accounts do not.
+        assert!(!footprint(&reads_bob_other_slot).conflicts_with(&footprint(&writes_bob_slot)));
+        assert!(!footprint(&writes_bob_slot).conflicts_with(&footprint(&writes_alice)));
+        // Host locations do not conflict with rollup locations.
+        let host = StateFootprint::new(Default::default(), writes_alice.clone());
+        assert!(!footprint(&reads_alice).conflicts_with(&host));
+    }
+
+    #[test]
+    fn read_states() {
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+        let slot = U256::from(1);
+
+        let mut account = Account::from(AccountInfo::default());
+        account.storage.insert(slot, EvmStorageSlot::new(U256::from(5), 0));
+        let state = EvmState::from_iter([(alice, account.clone()), (bob, account)]);
+
+        // Locations loaded by a transaction are read, except for the
+        // beneficiary account.
+        let mut reads = AccessSet::default();
+        reads.read_states([&state], Some(bob));
+        assert!(reads.writes.is_empty());
+        assert!(reads.reads.contains(&Location::Account(alice)));
+        assert!(reads.reads.contains(&Location::Slot(alice, slot)));
+        assert!(reads.reads.contains(&Location::Slot(bob, slot)));
+        assert!(!reads.reads.contains(&Location::Account(bob)));
+
+        // They conflict with previously committed writes.
+        let mut writes = AccessSet::default();
+        writes.write(Location::Slot(alice, slot));
+        assert!(reads.reads_writes_of(&writes));
+
+        let mut reads = AccessSet::default();
+        reads.read_accounts([bob]);
+        assert!(reads.writes.is_empty());
+        assert!(reads.reads.contains(&Location::Account(bob)));
+    }
+}
@@ -16,19 +16,35 @@
 
 mod built;
 
-pub use built::BuiltBlock;
+pub use built::{BlockCheckpoint, BuiltBlock};
 
 mod cache;
 pub use cache::{
-    check_bundle_tx_list, AcctInfo, CacheError, ProviderStateSource, SimCache, SimIdentifier,
-    SimItem, SimItemValidity, StateSource,
+    check_bundle_tx_list, AcctInfo, CacheError, CacheEvent, ProviderStateSource, SimCache,
+    SimCacheSnapshot, SimIdentifier, SimItem, SimItemValidity, StateSource, DEFAULT_PRICE_BUMP,
+    DEFAULT_SENDER_SLOTS,
 };
 
 mod env;
-pub use env::{HostEnv, RollupEnv, SharedSimEnv, SimEnv};
+pub use env::{HostEnv, RollupEnv, SharedSimEnv, SimCheckpoint, SimEnv, SimPool};
+
+mod footprint;
+pub use footprint::{AccessSet, StateFootprint};
 
 mod outcome;
-pub use outcome::SimOutcomeWithCache;
+pub use outcome::{SimOutcomeWithCache, SimulatedItem};
+
+mod policy;
+pub use policy::{DefaultScoring, ScoringPolicy};
+
+mod refund;
+pub use refund::{Refund, REFUND_TX_GAS};
+
+mod replay;
+pub use replay::{BuildRecording, RecordedRound};
+
+mod report;
+pub use report::{BuildReport, ItemReport, PreflightCheck, RejectionReason, SimAttempt};
 
 mod task;
 pub use task::BlockBuild;
@@ -40,6 +56,10 @@ use trevm::{
     revm::database::CacheDB,
 };
 
+// Suppress unused_crate_dependencies warning for revm (used to enable serde
+// for the revm types in build recordings)
+use revm as _;
+
 /// A type alias for the database underlying the simulation.
 pub type InnerDb<Db> = Arc<CacheDB<Db>>;
 
@@ -1,7 +1,10 @@
+use crate::{Refund, SimItem, StateFootprint, REFUND_TX_GAS};
 #[cfg(doc)]
-use crate::SimCache;
-use crate::SimItem;
-use alloy::primitives::U256;
+use crate::{ScoringPolicy, SimCache};
+use alloy::{
+    consensus::ReceiptEnvelope,
+    primitives::{TxHash, U256},
+};
 use signet_types::{AggregateFills, AggregateOrders};
 use trevm::revm::database::Cache;
 
@@ -12,10 +15,20 @@ pub struct SimOutcomeWithCache {
     /// The key for the item in the [`SimCache`].
     pub cache_rank: u128,
 
-    /// The score of the simulation, a [`U256`] value that represents the
-    /// increase in the beneficiary's balance.
+    /// The score of the simulation, assigned by the [`ScoringPolicy`].
     pub score: U256,
 
+    /// The increase in the beneficiary's balance.
+    pub profit: U256,
+
+    /// The beneficiary's balance before the simulation. Its balance after
+    /// the simulation is in the [`Self::rollup_cache`], if it changed.
+    pub beneficiary_balance: U256,
+
+    /// The refund owed to the searcher of the bundle, if requested. It is
+    /// paid out of the profit.
+    pub refund: Option<Refund>,
+
     /// The total amount of gas used by the simulation.
     pub gas_used: u64,
 
@@ -35,13 +48,37 @@ pub struct SimOutcomeWithCache {
 
     /// The aggregate orders after simulation.
     pub bundle_orders: AggregateOrders,
+
+    /// The receipts of the rollup transactions executed by the simulation.
+    /// The cumulative gas used is relative to the start of the item.
+    pub receipts: Vec<ReceiptEnvelope>,
+
+    /// The hashes of the droppable bundle transactions that failed and were
+    /// skipped by the simulation.
+    pub dropped_txs: Vec<TxHash>,
+
+    /// The state read and written by the simulation, used to detect
+    /// conflicts with other outcomes of the same round.
+    pub footprint: StateFootprint,
+}
+
+impl SimOutcomeWithCache {
+    /// Get the profit, net of the refund owed to the searcher of the bundle.
+    pub fn net_profit(&self) -> U256 {
+        self.profit.saturating_sub(self.refund.map(|refund| refund.total()).unwrap_or_default())
+    }
+
+    /// Get the block gas needed to include the outcome, including the gas of
+    /// the refund transaction, if a refund is owed.
+    pub fn block_gas(&self) -> u64 {
+        self.gas_used + self.refund.map(|_| REFUND_TX_GAS).unwrap_or_default()
+    }
 }
 
 /// An item after simulation, containing the score and gas used.
 #[derive(Debug, Clone)]
 pub struct SimulatedItem {
-    /// The score of the simulation, a [`U256`] value that represents the
-    /// increase in the beneficiary's balance.
+    /// The score of the simulation, assigned by the [`ScoringPolicy`].
     pub score: U256,
 
     /// The total amount of gas used by the simulation.
@@ -52,4 +89,29 @@ pub struct SimulatedItem {
 
     /// The transaction or bundle that was simulated.
     pub item: SimItem,
+
+    /// The refund owed to the searcher of the bundle, if requested. The
+    /// score is net of the refund.
+    pub refund: Option<Refund>,
+
+    /// The receipts of the rollup transactions executed by the simulation.
+    /// The cumulative gas used is relative to the start of the item.
+    pub receipts: Vec<ReceiptEnvelope>,
+
+    /// The hashes of the droppable bundle transactions that failed and were
+    /// skipped by the simulation. They are not included in the block.
+    pub dropped_txs: Vec<TxHash>,
+
+    /// The aggregate fills of the simulation.
+    pub bundle_fills: AggregateFills,
+
+    /// The aggregate orders of the simulation.
+    pub bundle_orders: AggregateOrders,
+
+    /// The rollup state changes of the simulation, as committed to the
+    /// block.
+    pub rollup_cache: Cache,
+
+    /// The host state changes of the simulation.
+    pub host_cache: Cache,
 }
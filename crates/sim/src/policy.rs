@@ -0,0 +1,54 @@
+use crate::{outcome::SimulatedItem, SimItem, SimOutcomeWithCache};
+use alloy::primitives::U256;
+
+/// A policy for ordering items during block building.
+///
+/// The policy determines both the order in which the [`SimCache`] offers
+/// items for simulation, and the order in which simulated items are added to
+/// the block. Policies may, for example, weight the score by gas efficiency,
+/// account for host gas, prioritize order-filling bundles, or track included
+/// items to enforce fairness across senders.
+///
+/// [`SimCache`]: crate::SimCache
+pub trait ScoringPolicy: Send + Sync + 'static {
+    /// Rank an item in the [`SimCache`], when it is added. Items with a
+    /// higher rank are simulated first.
+    ///
+    /// Defaults to the maximum fee payable by the item, see
+    /// [`SimItem::calculate_total_fee`].
+    ///
+    /// [`SimCache`]: crate::SimCache
+    fn cache_rank(&self, item: &SimItem, basefee: u64) -> u128 {
+        item.calculate_total_fee(basefee)
+    }
+
+    /// Score a simulated item. Items with a higher score are added to the
+    /// block first, and items with a zero score are rejected.
+    ///
+    /// Defaults to the increase in the beneficiary's balance, net of the
+    /// refund owed to the searcher, see [`SimOutcomeWithCache::net_profit`].
+    fn score(&self, item: &SimItem, outcome: &SimOutcomeWithCache) -> U256 {
+        let _ = item;
+        outcome.net_profit()
+    }
+
+    /// Called when a simulated item is added to the block.
+    fn on_included(&self, item: &SimulatedItem) {
+        let _ = item;
+    }
+
+    /// Called when a simulated item is taken out of the block by a re-pack.
+    /// See [`BlockBuild::with_repack_threshold`].
+    ///
+    /// [`BlockBuild::with_repack_threshold`]: crate::BlockBuild::with_repack_threshold
+    fn on_displaced(&self, item: &SimulatedItem) {
+        let _ = item;
+    }
+}
+
+/// The default [`ScoringPolicy`]. Items are ranked by the maximum fee they
+/// may pay, and scored by the increase in the beneficiary's balance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DefaultScoring;
+
+impl ScoringPolicy for DefaultScoring {}
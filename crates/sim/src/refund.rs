@@ -0,0 +1,64 @@
+use alloy::{
+    consensus::TxEip1559,
+    primitives::{Address, TxKind, U256},
+};
+use signet_bundle::RecoveredBundle;
+
+/// The gas limit of a refund transaction, a plain transfer.
+pub const REFUND_TX_GAS: u64 = 21_000;
+
+/// A refund owed to the searcher of a bundle, as requested by its
+/// `refundPercent` and `refundRecipient`.
+///
+/// The refund is a share of the beneficiary payments of the bundle's refund
+/// transactions, see [`RecoveredBundle::is_refund_tx`]. It is paid by a
+/// transfer to the recipient at the end of the block, whose fee is deducted
+/// from the refund.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Refund {
+    /// The address that receives the refund.
+    pub recipient: Address,
+    /// The value transferred to the recipient.
+    pub value: U256,
+    /// The fee of the refund transaction.
+    pub fee: U256,
+}
+
+impl Refund {
+    /// Calculate the refund owed for a bundle, given the beneficiary balance
+    /// increase from its refund transactions and the block basefee.
+    ///
+    /// Returns `None` if the bundle does not request a refund, or if the
+    /// refund does not cover the fee of the refund transaction.
+    pub fn for_bundle(bundle: &RecoveredBundle, refundable: U256, basefee: u64) -> Option<Self> {
+        let percent = bundle.refund_percent().filter(|percent| *percent > 0)?.min(100);
+        let recipient = bundle.refund_recipient_or_default()?;
+
+        let total = refundable * U256::from(percent) / U256::from(100);
+        let fee = U256::from(REFUND_TX_GAS) * U256::from(basefee);
+        let value = total.checked_sub(fee).filter(|value| !value.is_zero())?;
+
+        Some(Self { recipient, value, fee })
+    }
+
+    /// Get the total cost of the refund to the builder, the value and the
+    /// fee of the refund transaction.
+    pub const fn total(&self) -> U256 {
+        self.value.saturating_add(self.fee)
+    }
+
+    /// Create the refund transaction, to be signed by the builder. It pays
+    /// the basefee, and no priority fee.
+    pub fn to_tx(&self, chain_id: u64, nonce: u64, basefee: u64) -> TxEip1559 {
+        TxEip1559 {
+            chain_id,
+            nonce,
+            gas_limit: REFUND_TX_GAS,
+            max_fee_per_gas: basefee as u128,
+            max_priority_fee_per_gas: 0,
+            to: TxKind::Call(self.recipient),
+            value: self.value,
+            ..Default::default()
+        }
+    }
+}
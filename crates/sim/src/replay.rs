@@ -0,0 +1,123 @@
+use crate::{CacheEvent, SimCacheSnapshot};
+use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
+use signet_types::AggregateFills;
+use trevm::revm::context::{BlockEnv, CfgEnv};
+
+/// The changes made to the cache around a simulation round of a recorded
+/// build.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordedRound {
+    /// The changes made since the previous round, applied before the round's
+    /// preflight checks.
+    pub before: Vec<CacheEvent>,
+    /// The changes made while the round's items were checked and simulated,
+    /// applied before the round's outcomes are accepted.
+    pub during: Vec<CacheEvent>,
+}
+
+/// A recording of a block build, to replay it deterministically with
+/// [`BlockBuild::replay`].
+///
+/// The recording contains a snapshot of the [`SimCache`] when recording
+/// started, the rollup and host block and config, and the timeline of
+/// changes to the cache, by round. Replays run on a virtual clock that only
+/// advances between rounds: they run exactly the rounds that the recorded
+/// build completed before its deadline, applying each change at the same
+/// point of the round as it was made, and are not affected by wall-clock
+/// deadlines or sleeps.
+///
+/// Rounds cut short by the deadline are not recorded, as the recorded build
+/// discards them.
+///
+/// Recordings can be serialized, e.g. to replay a build elsewhere. Items are
+/// serialized as in [`SimItem`].
+///
+/// [`BlockBuild::replay`]: crate::BlockBuild::replay
+/// [`SimCache`]: crate::SimCache
+/// [`SimItem`]: crate::SimItem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildRecording {
+    /// The contents of the cache when recording started.
+    pub(crate) cache: SimCacheSnapshot,
+    /// The rollup config.
+    pub(crate) rollup_cfg: CfgEnv,
+    /// The rollup block.
+    pub(crate) rollup_block: BlockEnv,
+    /// The rollup fill state.
+    pub(crate) fill_state: AggregateFills,
+    /// The host config.
+    pub(crate) host_cfg: CfgEnv,
+    /// The host block.
+    pub(crate) host_block: BlockEnv,
+    /// The maximum gas of the block.
+    pub(crate) max_gas: u64,
+    /// The maximum host gas of the block.
+    pub(crate) max_host_gas: u64,
+    /// The minimum score gain to keep a re-pack, if re-packing is enabled.
+    pub(crate) repack_threshold: Option<U256>,
+    /// The maximum number of concurrent simulations.
+    pub(crate) concurrency_limit: usize,
+    /// The completed rounds.
+    pub(crate) rounds: Vec<RecordedRound>,
+}
+
+impl BuildRecording {
+    /// Get the contents of the cache when recording started.
+    pub const fn cache(&self) -> &SimCacheSnapshot {
+        &self.cache
+    }
+
+    /// Get the rollup config.
+    pub const fn rollup_cfg(&self) -> &CfgEnv {
+        &self.rollup_cfg
+    }
+
+    /// Get the rollup block.
+    pub const fn rollup_block(&self) -> &BlockEnv {
+        &self.rollup_block
+    }
+
+    /// Get the rollup fill state when recording started.
+    pub const fn fill_state(&self) -> &AggregateFills {
+        &self.fill_state
+    }
+
+    /// Get the host config.
+    pub const fn host_cfg(&self) -> &CfgEnv {
+        &self.host_cfg
+    }
+
+    /// Get the host block.
+    pub const fn host_block(&self) -> &BlockEnv {
+        &self.host_block
+    }
+
+    /// Get the maximum gas of the block.
+    pub const fn max_gas(&self) -> u64 {
+        self.max_gas
+    }
+
+    /// Get the maximum host gas of the block.
+    pub const fn max_host_gas(&self) -> u64 {
+        self.max_host_gas
+    }
+
+    /// Get the minimum score gain to keep a re-pack, if re-packing is
+    /// enabled.
+    pub const fn repack_threshold(&self) -> Option<U256> {
+        self.repack_threshold
+    }
+
+    /// Get the maximum number of concurrent simulations.
+    pub const fn concurrency_limit(&self) -> usize {
+        self.concurrency_limit
+    }
+
+    /// Get the completed rounds.
+    #[allow(clippy::missing_const_for_fn)] // false positive, const deref
+    pub fn rounds(&self) -> &[RecordedRound] {
+        &self.rounds
+    }
+}
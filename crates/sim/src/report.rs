@@ -0,0 +1,252 @@
+use crate::{SimItem, SimItemValidity, SimOutcomeWithCache, SimulatedItem};
+use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
+use signet_bundle::BundlePlacement;
+use std::collections::HashMap;
+
+/// The reason a simulated item was not added to the block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[serde(tag = "reason", rename_all = "camelCase")]
+pub enum RejectionReason {
+    /// The simulation failed, e.g. because a transaction was invalid or the
+    /// bundle reverted.
+    #[error("simulation failed: {error}")]
+    SimulationFailed {
+        /// The simulation error.
+        error: String,
+    },
+    /// The item was scored zero by the scoring policy.
+    #[error("zero score")]
+    ZeroScore,
+    /// The item uses more gas than is left in the block.
+    #[error("gas limit exceeded: used {gas_used}, max {max_gas}")]
+    GasLimitExceeded {
+        /// The gas used by the item.
+        gas_used: u64,
+        /// The gas left in the block.
+        max_gas: u64,
+    },
+    /// The item uses more host gas than is left in the block.
+    #[error("host gas limit exceeded: used {host_gas_used}, max {max_host_gas}")]
+    HostGasLimitExceeded {
+        /// The host gas used by the item.
+        host_gas_used: u64,
+        /// The host gas left in the block.
+        max_host_gas: u64,
+    },
+    /// The item read state written by a higher-scoring item of the same
+    /// round. It remains in the cache, and is simulated again in a later
+    /// round.
+    #[error("conflicts with a higher-scoring item")]
+    Conflict,
+    /// The item uses more gas than is left after the higher-scoring items of
+    /// the same round. It remains in the cache.
+    #[error("exceeds the gas left in the round")]
+    RoundGasExceeded,
+    /// The item's fills are insufficient after the higher-scoring items of
+    /// the same round. It remains in the cache.
+    #[error("insufficient fills")]
+    InsufficientFills,
+    /// The bundle's placement can no longer be met in the block, e.g. a top
+    /// of block bundle after another was added. It is removed from the
+    /// cache.
+    #[error("placement {placement:?} unsatisfiable at position {position}")]
+    PlacementUnsatisfiable {
+        /// The placement of the bundle.
+        placement: BundlePlacement,
+        /// The position of the block the bundle would be added at.
+        position: u64,
+    },
+    /// The bundle's placement is not met yet, e.g. the transaction it
+    /// follows is not in the block. It remains in the cache.
+    #[error("placement {placement:?} not met at position {position}")]
+    PlacementPending {
+        /// The placement of the bundle.
+        placement: BundlePlacement,
+        /// The position of the block the bundle would be added at.
+        position: u64,
+    },
+    /// The item was replaced or cancelled while it was being simulated.
+    #[error("replaced during simulation")]
+    Replaced,
+    /// The item was taken out of the block by a re-pack, and not added back.
+    /// It is returned to the cache.
+    #[error("displaced by a re-pack")]
+    Displaced,
+    /// The item was accepted in a re-pack of the block that was discarded,
+    /// as it did not gain enough over the items it would displace. It is
+    /// returned to the cache.
+    #[error("re-pack gain below threshold")]
+    RepackDiscarded,
+}
+
+/// A simulation of an item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimAttempt {
+    /// The round of the simulation, starting at 1.
+    pub round: u32,
+    /// The score of the item. Zero if the simulation failed.
+    pub score: U256,
+    /// The gas used by the item. Zero if the simulation failed.
+    pub gas_used: u64,
+    /// The host gas used by the item. Zero if the simulation failed.
+    pub host_gas_used: u64,
+    /// The reason the item was not added to the block, if it was not.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejection: Option<RejectionReason>,
+}
+
+impl SimAttempt {
+    /// Create an attempt from a simulation outcome. The round is set when
+    /// the attempt is recorded.
+    pub(crate) const fn new(
+        outcome: &SimOutcomeWithCache,
+        rejection: Option<RejectionReason>,
+    ) -> Self {
+        Self {
+            round: 0,
+            score: outcome.score,
+            gas_used: outcome.gas_used,
+            host_gas_used: outcome.host_gas_used,
+            rejection,
+        }
+    }
+
+    /// Create an attempt for a failed simulation. The round is set when the
+    /// attempt is recorded.
+    pub(crate) const fn failed(rejection: RejectionReason) -> Self {
+        Self {
+            round: 0,
+            score: U256::ZERO,
+            gas_used: 0,
+            host_gas_used: 0,
+            rejection: Some(rejection),
+        }
+    }
+}
+
+/// A preflight validity check of an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightCheck {
+    /// The round of the check, starting at 1.
+    pub round: u32,
+    /// The validity of the item.
+    pub validity: SimItemValidity,
+}
+
+/// The history of an item considered while building a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemReport {
+    /// The item's identifier, i.e. the replacement UUID of a bundle, or the
+    /// hash of a transaction.
+    pub identifier: String,
+    /// Whether the item is a bundle.
+    pub bundle: bool,
+    /// The preflight validity checks of the item. Only checks that changed
+    /// the item's validity are recorded.
+    pub preflight: Vec<PreflightCheck>,
+    /// The simulations of the item.
+    pub attempts: Vec<SimAttempt>,
+    /// The round in which the item was added to the block, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub included_in_round: Option<u32>,
+}
+
+/// A report of the building of a block, explaining why each item considered
+/// was included or not.
+///
+/// Items are listed in the order they were first considered.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReport {
+    /// The number of simulation rounds run.
+    pub rounds: u32,
+    /// The items considered.
+    pub items: Vec<ItemReport>,
+    /// The index of each item in `items`, by identifier.
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+}
+
+impl BuildReport {
+    /// Get the report of an item by its identifier.
+    pub fn item(&self, identifier: &str) -> Option<&ItemReport> {
+        self.index.get(identifier).map(|&i| &self.items[i])
+    }
+
+    /// Get the reports of the items added to the block.
+    pub fn included(&self) -> impl Iterator<Item = &ItemReport> {
+        self.items.iter().filter(|item| item.included_in_round.is_some())
+    }
+
+    /// Start a new round.
+    pub(crate) const fn start_round(&mut self) {
+        self.rounds += 1;
+    }
+
+    /// Get the report of an item, creating it if it was not considered yet.
+    fn entry(&mut self, item: &SimItem) -> &mut ItemReport {
+        let identifier = item.identifier().to_string();
+        let i = *self.index.entry(identifier.clone()).or_insert_with(|| {
+            self.items.push(ItemReport {
+                identifier,
+                bundle: item.as_bundle().is_some(),
+                preflight: Vec::new(),
+                attempts: Vec::new(),
+                included_in_round: None,
+            });
+            self.items.len() - 1
+        });
+        &mut self.items[i]
+    }
+
+    /// Record a preflight validity check, if it changed the item's validity.
+    pub(crate) fn record_preflight(&mut self, item: &SimItem, validity: SimItemValidity) {
+        let round = self.rounds;
+        let report = self.entry(item);
+        if report.preflight.last().map(|check| check.validity) != Some(validity) {
+            report.preflight.push(PreflightCheck { round, validity });
+        }
+    }
+
+    /// Record a simulation of an item in the current round.
+    pub(crate) fn record_attempt(&mut self, item: &SimItem, mut attempt: SimAttempt) {
+        attempt.round = self.rounds;
+        self.entry(item).attempts.push(attempt);
+    }
+
+    /// Record that an item added to the block, or accepted in a re-pack,
+    /// was taken out of it.
+    pub(crate) fn record_excluded(&mut self, item: &SimulatedItem, rejection: RejectionReason) {
+        let attempt = SimAttempt {
+            round: self.rounds,
+            score: item.score,
+            gas_used: item.gas_used,
+            host_gas_used: item.host_gas_used,
+            rejection: Some(rejection),
+        };
+        let report = self.entry(&item.item);
+        report.attempts.push(attempt);
+        report.included_in_round = None;
+    }
+
+    /// Get the round in which an item was added to the block, if it was.
+    pub(crate) fn included_in_round(&self, item: &SimItem) -> Option<u32> {
+        self.item(&item.identifier().to_string()).and_then(|report| report.included_in_round)
+    }
+
+    /// Restore the round in which an item was added to the block, e.g.
+    /// after it was accepted again in a discarded re-pack.
+    pub(crate) fn restore_included(&mut self, item: &SimItem, round: Option<u32>) {
+        self.entry(item).included_in_round = round;
+    }
+
+    /// Record that an item was added to the block.
+    pub(crate) fn record_included(&mut self, item: &SimItem) {
+        let round = self.rounds;
+        self.entry(item).included_in_round = Some(round);
+    }
+}
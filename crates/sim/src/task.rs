@@ -1,9 +1,16 @@
 use crate::{
-    cache::StateSource, env::SimEnv, BuiltBlock, HostEnv, RollupEnv, SharedSimEnv, SimCache, SimDb,
+    cache::StateSource, env::SimEnv, BlockCheckpoint, BuildRecording, BuildReport, BuiltBlock,
+    DefaultScoring, HostEnv, RecordedRound, RejectionReason, RollupEnv, ScoringPolicy,
+    SharedSimEnv, SimCache, SimCheckpoint, SimDb, SimPool, SimulatedItem,
+};
+use alloy::{
+    consensus::{transaction::Recovered, SignableTransaction, TxEnvelope},
+    network::{EthereumWallet, TxSigner},
+    primitives::U256,
 };
 use std::time::Duration;
 use tokio::select;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 use trevm::{
     helpers::Ctx,
     revm::{inspector::NoOpInspector, DatabaseRef, Inspector},
@@ -12,6 +19,30 @@ use trevm::{
 /// The amount of time to sleep between simulation rounds when there are no items to simulate.
 pub(crate) const SIM_SLEEP_MS: u64 = 50;
 
+/// The time limit for the simulations of a replayed build. Replays run on a
+/// virtual clock, so this only guards against simulations that never halt.
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// The time reserved at the end of the build to pay refunds, if a refund
+/// wallet is set.
+const REFUND_RESERVE: Duration = Duration::from_millis(100);
+
+/// The time to wait for the simulations of a round cut short by the deadline
+/// to stop.
+const SIM_STOP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The items added to the block by a simulation round, and the state before
+/// the round, to roll back to when re-packing.
+#[derive(Debug)]
+struct PackedRound {
+    /// The simulation environment before the round.
+    env: SimCheckpoint,
+    /// The block before the round.
+    block: BlockCheckpoint,
+    /// The items added by the round.
+    items: Vec<SimulatedItem>,
+}
+
 /// Builds a single block by repeatedly invoking [`SimEnv`].
 #[derive(Debug)]
 pub struct BlockBuild<
@@ -21,9 +52,10 @@ pub struct BlockBuild<
     HostAsync,
     RuInsp = NoOpInspector,
     HostInsp = NoOpInspector,
+    P = DefaultScoring,
 > {
     /// The simulation environment.
-    env: SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp>,
+    env: SharedSimEnv<RuDb, HostDb, RuInsp, HostInsp, P>,
 
     /// Async state source for the rollup chain, used for preflight validity checks.
     ru_async_source: RuAsync,
@@ -34,6 +66,12 @@ pub struct BlockBuild<
     /// The block being built.
     block: BuiltBlock,
 
+    /// The report of the build, if enabled.
+    report: Option<BuildReport>,
+
+    /// The recording of the build, if enabled.
+    recording: Option<BuildRecording>,
+
     /// The deadline to produce a block by.
     finish_by: tokio::time::Instant,
 
@@ -43,10 +81,28 @@ pub struct BlockBuild<
     /// The maximum amount of host gas to use in the user portion of the built
     /// block, not including overhead for the signet RU block submission.
     max_host_gas: u64,
+
+    /// The minimum score gain to keep a re-pack of the block, if re-packing
+    /// is enabled.
+    repack_threshold: Option<U256>,
+
+    /// The rounds that added items to the block, if re-packing is enabled.
+    packed: Vec<PackedRound>,
+
+    /// The first round rolled back by the re-pack in progress, and the
+    /// state before the rollback, to restore if the re-pack is cut short.
+    repacking: Option<(usize, SimCheckpoint)>,
+
+    /// The number of items added to the cache when it was last checked for
+    /// new arrivals.
+    arrivals: u64,
+
+    /// The wallet signing the refund transactions, if refunds are paid.
+    refund_wallet: Option<EthereumWallet>,
 }
 
-impl<RuDb, HostDb, RuAsync, HostAsync, RuInsp, HostInsp>
-    BlockBuild<RuDb, HostDb, RuAsync, HostAsync, RuInsp, HostInsp>
+impl<RuDb, HostDb, RuAsync, HostAsync, RuInsp, HostInsp, P>
+    BlockBuild<RuDb, HostDb, RuAsync, HostAsync, RuInsp, HostInsp, P>
 where
     RuDb: DatabaseRef + Send + Sync + 'static,
     RuInsp: Inspector<Ctx<SimDb<RuDb>>> + Default + Sync + 'static,
@@ -54,6 +110,7 @@ where
     HostInsp: Inspector<Ctx<SimDb<HostDb>>> + Default + Sync + 'static,
     RuAsync: StateSource,
     HostAsync: StateSource,
+    P: ScoringPolicy,
 {
     /// Create a new block building process.
     #[expect(clippy::too_many_arguments, reason = "should be refactored to avoid this warning")]
@@ -62,7 +119,7 @@ where
         host: HostEnv<HostDb, HostInsp>,
         finish_by: tokio::time::Instant,
         concurrency_limit: usize,
-        sim_items: SimCache,
+        sim_items: SimCache<P>,
         max_gas: u64,
         max_host_gas: u64,
         ru_async_source: RuAsync,
@@ -70,7 +127,7 @@ where
     ) -> Self {
         let number = rollup.block().number;
 
-        let env = SimEnv::<RuDb, HostDb, RuInsp, HostInsp>::new(
+        let env = SimEnv::<RuDb, HostDb, RuInsp, HostInsp, P>::new(
             rollup,
             host,
             finish_by,
@@ -83,12 +140,113 @@ where
             ru_async_source,
             host_async_source,
             block: BuiltBlock::new(number.to()),
+            report: None,
+            recording: None,
             finish_by,
             max_gas,
             max_host_gas,
+            repack_threshold: None,
+            packed: Vec::new(),
+            repacking: None,
+            arrivals: 0,
+            refund_wallet: None,
         }
     }
 
+    /// Set the [`SimPool`] used to run simulations, e.g. to reuse the same
+    /// threads for successive blocks. By default, a pool of
+    /// `concurrency_limit` threads is created for the block.
+    pub fn with_pool(mut self, pool: SimPool) -> Self {
+        self.env = self.env.with_pool(pool);
+        self
+    }
+
+    /// Record a [`BuildReport`] of the items considered while building.
+    pub fn with_report(mut self) -> Self {
+        self.report.get_or_insert_default();
+        self
+    }
+
+    /// Get the report of the build, if enabled.
+    pub const fn report(&self) -> Option<&BuildReport> {
+        self.report.as_ref()
+    }
+
+    /// Re-pack the block when new items arrive, keeping the re-pack if it
+    /// increases the total score by at least `min_gain`.
+    ///
+    /// When items are added to the cache, the next round rolls back the
+    /// latest rounds, discarding the items they added, and simulates the new
+    /// items along with the displaced ones against the earlier state. It
+    /// rolls back as many rounds as a single round can re-pack, i.e. up to
+    /// the concurrency limit of displaced items. If the items accepted score
+    /// less than `min_gain` more than the displaced items, the rollback is
+    /// undone. Displaced items not added back are returned to the cache.
+    ///
+    /// Re-packs are rounds of the build, and are cut short by its deadline
+    /// like any other round. This checkpoints the state before each round,
+    /// which costs a copy of the state changes of the block per round.
+    pub const fn with_repack_threshold(mut self, min_gain: U256) -> Self {
+        self.repack_threshold = Some(min_gain);
+        self
+    }
+
+    /// Get the minimum score gain to keep a re-pack of the block, if
+    /// re-packing is enabled.
+    pub const fn repack_threshold(&self) -> Option<U256> {
+        self.repack_threshold
+    }
+
+    /// Pay the refunds owed by the bundles in the block, by appending
+    /// transfers signed by the wallet's default signer to the end of the
+    /// block. The signer is typically the block beneficiary, which receives
+    /// the payments refunds are a share of.
+    ///
+    /// Bundles that request a refund are scored net of it, and gas is
+    /// reserved for their refund transactions, whether or not a refund
+    /// wallet is set. Without one, the refunds owed are listed by
+    /// [`BuiltBlock::refunds`] for the caller to pay.
+    ///
+    /// With a refund wallet, the simulation rounds stop 100ms before the
+    /// deadline, and the refunds are paid by the deadline. Refunds that can
+    /// not be paid in time are left to the caller.
+    pub fn with_refund_wallet(mut self, wallet: impl Into<EthereumWallet>) -> Self {
+        self.refund_wallet = Some(wallet.into());
+        self
+    }
+
+    /// Get the wallet signing the refund transactions, if refunds are paid.
+    pub const fn refund_wallet(&self) -> Option<&EthereumWallet> {
+        self.refund_wallet.as_ref()
+    }
+
+    /// Record the build, to replay it with [`Self::replay`]. This snapshots
+    /// the simulation cache, and records the changes made to it until the
+    /// build completes.
+    pub fn with_recording(mut self) -> Self {
+        let cache = self.env.sim_items().start_recording();
+        let (rollup, host) = (self.env.rollup_env(), self.env.host_env());
+        self.recording = Some(BuildRecording {
+            cache,
+            rollup_cfg: rollup.cfg().clone(),
+            rollup_block: rollup.block().clone(),
+            fill_state: rollup.fill_state().clone(),
+            host_cfg: host.cfg().clone(),
+            host_block: host.block().clone(),
+            max_gas: self.max_gas,
+            max_host_gas: self.max_host_gas,
+            repack_threshold: self.repack_threshold,
+            concurrency_limit: self.env.concurrency_limit(),
+            rounds: Vec::new(),
+        });
+        self
+    }
+
+    /// Get the recording of the build, if enabled.
+    pub const fn recording(&self) -> Option<&BuildRecording> {
+        self.recording.as_ref()
+    }
+
     /// Get the maximum gas limit for the block being built.
     pub const fn max_gas(&self) -> u64 {
         self.max_gas
@@ -110,7 +268,7 @@ where
     }
 
     /// Get a reference the simulation cache used by this builder.
-    pub fn sim_items(&self) -> &SimCache {
+    pub fn sim_items(&self) -> &SimCache<P> {
         self.env.sim_items()
     }
 
@@ -132,21 +290,259 @@ where
         self.block
     }
 
-    /// Run a simulation round, and accumulate the results into the block.
-    async fn round(&mut self) {
-        let gas_allowed = self.max_gas - self.block.gas_used();
+    /// Run a simulation round, and accumulate the results into the block,
+    /// recording the changes made to the cache around the round if the
+    /// build is recorded.
+    ///
+    /// Returns whether the round added items to the block or re-packed it.
+    async fn round(&mut self) -> bool {
+        if self.recording.is_none() {
+            return self.round_with(|_| {}).await;
+        }
+
+        let before = self.env.sim_items().take_events();
+        let mut during = Vec::new();
+        let progress = self.round_with(|cache| during = cache.take_events()).await;
+
+        if let Some(recording) = self.recording.as_mut() {
+            recording.rounds.push(RecordedRound { before, during });
+        }
+        progress
+    }
+
+    /// Run a simulation round, and accumulate the results into the block,
+    /// re-packing it if new items arrived and re-packing is enabled.
+    ///
+    /// `simulated` is called with the cache once the simulations of the
+    /// round are done, before their outcomes are accepted.
+    async fn round_with(&mut self, simulated: impl FnOnce(&SimCache<P>)) -> bool {
+        if let Some(report) = self.report.as_mut() {
+            report.start_round();
+        }
+
+        match self.repack_from() {
+            Some(from) => self.repack(from, simulated).await,
+            None => self.pack(simulated).await,
+        }
+    }
+
+    /// Get the first packed round to roll back to, if re-packing is enabled
+    /// and new items arrived since the last round.
+    fn repack_from(&mut self) -> Option<usize> {
+        self.repack_threshold?;
+        let arrivals = self.env.sim_items().arrivals();
+        if std::mem::replace(&mut self.arrivals, arrivals) == arrivals {
+            return None;
+        }
+
+        // Roll back as many rounds as a single round can re-pack.
+        let limit = self.env.concurrency_limit();
+        let mut displaced = 0;
+        let mut from = None;
+        for (i, round) in self.packed.iter().enumerate().rev() {
+            displaced += round.items.len();
+            if displaced > limit {
+                break;
+            }
+            from = Some(i);
+        }
+        from
+    }
+
+    /// Run a simulation round against the current state, and add the
+    /// accepted items to the block.
+    async fn pack(&mut self, simulated: impl FnOnce(&SimCache<P>)) -> bool {
+        let checkpoint =
+            self.repack_threshold.map(|_| (self.env.checkpoint(), self.block.checkpoint()));
+        let gas_allowed = self.max_gas - self.block.gas_used() - self.block.refund_gas();
         let host_gas_allowed = self.max_host_gas - self.block.host_gas_used();
 
-        if let Some(simulated) = self
+        let simulated = self
             .env
-            .sim_round(
+            .sim_round_inner(
                 gas_allowed,
                 host_gas_allowed,
                 &self.ru_async_source,
                 &self.host_async_source,
+                self.report.as_mut(),
+                Vec::new(),
+                None,
+                simulated,
             )
-            .await
-        {
+            .await;
+
+        let progress = !simulated.is_empty();
+        self.ingest(simulated, checkpoint);
+        progress
+    }
+
+    /// Roll back the packed rounds starting at `from`, and run a simulation
+    /// round against the earlier state with the displaced items. The re-pack
+    /// is kept if it gains at least the repack threshold, and undone
+    /// otherwise.
+    ///
+    /// The items rejected by the re-pack are only removed from the cache if
+    /// it is kept. If the re-pack is cut short, the rollback is undone by
+    /// [`Self::abort_repack`].
+    async fn repack(&mut self, from: usize, simulated: impl FnOnce(&SimCache<P>)) -> bool {
+        self.repacking = Some((from, self.env.checkpoint()));
+        let displaced: Vec<SimulatedItem> =
+            self.packed[from..].iter().flat_map(|round| round.items.iter().cloned()).collect();
+
+        let checkpoint = &self.packed[from];
+        self.env.rollback(&checkpoint.env);
+        let gas_allowed =
+            self.max_gas - checkpoint.block.gas_used() - checkpoint.block.refund_gas();
+        let host_gas_allowed = self.max_host_gas - checkpoint.block.host_gas_used();
+
+        // Displaced items are not in the cache, and are ranked as if added.
+        let basefee = self.env.rollup_env().block().basefee;
+        let ranked = displaced
+            .iter()
+            .map(|displaced| {
+                let cache_rank = self.env.sim_items().policy().cache_rank(&displaced.item, basefee);
+                (cache_rank, displaced.item.clone())
+            })
+            .collect();
+
+        let included_in: Vec<_> = self
+            .report
+            .as_ref()
+            .map(|report| {
+                displaced.iter().map(|item| report.included_in_round(&item.item)).collect()
+            })
+            .unwrap_or_default();
+
+        let mut removed = Vec::new();
+        let simulated = self
+            .env
+            .sim_round_inner(
+                gas_allowed,
+                host_gas_allowed,
+                &self.ru_async_source,
+                &self.host_async_source,
+                self.report.as_mut(),
+                ranked,
+                Some(&mut removed),
+                simulated,
+            )
+            .await;
+        let (_, current) = self.repacking.take().expect("re-pack in progress");
+
+        let old_score: U256 = displaced.iter().map(|item| item.score).sum();
+        let new_score: U256 = simulated.iter().map(|item| item.score).sum();
+        let min_gain = self.repack_threshold.unwrap_or_default();
+        let was_displaced = |item: &SimulatedItem| displaced.iter().any(|d| d.item == item.item);
+
+        if new_score <= old_score || new_score - old_score < min_gain {
+            debug!(%old_score, %new_score, "Discarding re-pack below the gain threshold");
+            self.env.rollback(&current);
+
+            // Return the items accepted from the cache. The displaced items
+            // stay in the block, and the rejected items in the cache.
+            let returned = simulated.iter().filter(|item| !was_displaced(item));
+            if let Some(report) = self.report.as_mut() {
+                returned.clone().for_each(|item| {
+                    report.record_excluded(item, RejectionReason::RepackDiscarded)
+                });
+                for (item, round) in displaced.iter().zip(included_in) {
+                    report.restore_included(&item.item, round);
+                }
+            }
+            self.return_to_cache(returned);
+            return true;
+        }
+
+        debug!(
+            %old_score,
+            %new_score,
+            rounds = self.packed.len() - from,
+            displaced = displaced.len(),
+            "Re-packing block",
+        );
+        let checkpoint = self.packed.drain(from..).next().expect("rolled back round exists");
+        self.block.rollback(&checkpoint.block);
+
+        for (cache_rank, item) in &removed {
+            self.env.sim_items().remove_item(*cache_rank, item);
+        }
+        for item in &displaced {
+            self.env.sim_items().policy().on_displaced(item);
+        }
+        let returned =
+            displaced.iter().filter(|item| !simulated.iter().any(|s| s.item == item.item));
+        if let Some(report) = self.report.as_mut() {
+            returned
+                .clone()
+                .for_each(|item| report.record_excluded(item, RejectionReason::Displaced));
+        }
+        self.return_to_cache(returned);
+
+        self.ingest(simulated, Some((checkpoint.env, checkpoint.block)));
+        true
+    }
+
+    /// Undo the rollback of a re-pack cut short by the deadline, once the
+    /// simulations it started have stopped. If they don't stop in time, the
+    /// rolled back rounds are taken out of the block instead, and their
+    /// items returned to the cache.
+    async fn abort_repack(&mut self) {
+        let Some((from, current)) = self.repacking.take() else {
+            return;
+        };
+        if self.sims_stopped(tokio::time::Instant::now() + SIM_STOP_TIMEOUT).await {
+            self.env.rollback(&current);
+            return;
+        }
+
+        warn!("simulations still running, dropping the rolled back rounds");
+        let rounds: Vec<_> = self.packed.drain(from..).collect();
+        self.block.rollback(&rounds[0].block);
+        let displaced = rounds.iter().flat_map(|round| &round.items);
+        if let Some(report) = self.report.as_mut() {
+            displaced
+                .clone()
+                .for_each(|item| report.record_excluded(item, RejectionReason::Displaced));
+        }
+        self.return_to_cache(displaced);
+    }
+
+    /// Wait for the simulations of a round cut short by the deadline to
+    /// stop, until `wait_until`. Returns whether they stopped.
+    async fn sims_stopped(&self, wait_until: tokio::time::Instant) -> bool {
+        while self.env.is_shared() {
+            if tokio::time::Instant::now() >= wait_until {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        true
+    }
+
+    /// Return items taken out of the cache by the build. They are not
+    /// counted as new arrivals.
+    fn return_to_cache<'a>(&mut self, items: impl Iterator<Item = &'a SimulatedItem>) {
+        let basefee = self.env.rollup_env().block().basefee;
+        let cache = self.env.sim_items();
+        let items =
+            items.map(|item| (cache.policy().cache_rank(&item.item, basefee), item.item.clone()));
+        self.arrivals += cache.reinsert(items);
+    }
+
+    /// Add simulated items to the block. If re-packing is enabled, they are
+    /// recorded with the state before the round, to roll back to.
+    fn ingest(
+        &mut self,
+        simulated: Vec<SimulatedItem>,
+        checkpoint: Option<(SimCheckpoint, BlockCheckpoint)>,
+    ) {
+        if simulated.is_empty() {
+            return;
+        }
+        let mut packed =
+            checkpoint.map(|(env, block)| PackedRound { env, block, items: Vec::new() });
+
+        for simulated in simulated {
             debug!(
                 score = %simulated.score,
                 gas_used = simulated.gas_used,
@@ -154,8 +550,71 @@ where
                 identifier = %simulated.item.identifier(),
                 "Adding item to block"
             );
+            self.env.sim_items().policy().on_included(&simulated);
+            // Promote the transactions queued behind the item's nonces.
+            self.env.sim_items().mark_included(&simulated.item);
+            if let Some(packed) = packed.as_mut() {
+                packed.items.push(simulated.clone());
+            }
             self.block.ingest(simulated);
         }
+        self.packed.extend(packed);
+    }
+
+    /// Pay the refunds owed by the bundles in the block, if a refund wallet
+    /// is set, by appending the refund transactions to the block.
+    ///
+    /// Refunds are paid in block order, until `finish_by`. Refunds whose
+    /// transaction fails are skipped, and none are paid once a transaction
+    /// can not be signed or the deadline has passed.
+    async fn pay_refunds(&mut self, finish_by: tokio::time::Instant) {
+        let Some(signer) = self.refund_wallet.as_ref().map(EthereumWallet::default_signer) else {
+            return;
+        };
+        if self.block.refunds().is_empty() {
+            return;
+        }
+
+        // The simulations of a round cut short by the deadline run until
+        // their time limit.
+        if !self.sims_stopped(finish_by).await {
+            warn!("simulations still running, refunds not paid");
+            return;
+        }
+
+        let sender = signer.address();
+        let rollup = self.env.rollup_env();
+        let (chain_id, basefee) = (rollup.cfg().chain_id, rollup.block().basefee);
+        let mut nonce = match rollup.db().basic_ref(sender) {
+            Ok(info) => info.map(|info| info.nonce).unwrap_or_default(),
+            Err(_) => {
+                warn!(%sender, "failed to read refund signer nonce");
+                return;
+            }
+        };
+
+        for refund in self.block.refunds().to_vec() {
+            if tokio::time::Instant::now() >= finish_by {
+                warn!("deadline reached, remaining refunds not paid");
+                return;
+            }
+            let mut tx = refund.to_tx(chain_id, nonce, basefee);
+            let signature = match signer.sign_transaction(&mut tx).await {
+                Ok(signature) => signature,
+                Err(error) => {
+                    warn!(%error, "failed to sign refund transaction");
+                    return;
+                }
+            };
+            let tx = Recovered::new_unchecked(TxEnvelope::from(tx.into_signed(signature)), sender);
+            let Some(item) = self.env.env_mut().apply_refund(tx, finish_by) else {
+                warn!(recipient = %refund.recipient, value = %refund.value, "refund not paid");
+                continue;
+            };
+            debug!(recipient = %refund.recipient, value = %refund.value, "Paying refund");
+            nonce += 1;
+            self.block.ingest(item);
+        }
     }
 
     /// Run several rounds, building a block by iteratively adding simulated
@@ -172,11 +631,17 @@ where
             );
             return self;
         }
+
+        // Stop simulating early enough to pay the refunds by the deadline.
+        let finish_by = match self.refund_wallet {
+            Some(_) => self.finish_by.checked_sub(REFUND_RESERVE).unwrap_or(self.finish_by),
+            None => self.finish_by,
+        };
+        self.env.env_mut().set_finish_by(finish_by);
+
         let mut i = 1;
         // Run until the deadline is reached.
         loop {
-            let finish_by = self.finish_by;
-
             let next_round_time = tokio::time::Instant::now() + Duration::from_millis(SIM_SLEEP_MS);
 
             // If the next round time is past the deadline, we stop the simulation loop.
@@ -195,10 +660,9 @@ where
             }
 
             // If there are items to simulate, we run a simulation round.
-            let prev_tx_count = self.block.transactions.len();
             let fut = self.round();
 
-            select! {
+            let progress = select! {
                 biased;
                 _ = tokio::time::sleep_until(finish_by) => {
                     // This event is not a round event. It's a control flow
@@ -206,21 +670,28 @@ where
                     debug!("Deadline reached, stopping sim loop");
                     break;
                 },
-                _ = fut => {
+                progress = fut => {
                     i += 1;
                     let remaining_items = self.env.sim_items().len();
                     trace!(remaining_items, "Round completed");
+                    progress
                 }
-            }
+            };
 
             // If the round didn't produce any new transactions (e.g. all
-            // cached items have Future validity), sleep to avoid spinning
-            // and allow new items to arrive.
-            if self.block.transactions.len() == prev_tx_count {
+            // cached items have Future validity) or re-pack the block, sleep
+            // to avoid spinning and allow new items to arrive.
+            if !progress {
                 tokio::time::sleep_until(next_round_time).await;
             }
         }
 
+        if self.recording.is_some() {
+            self.env.sim_items().stop_recording();
+        }
+        self.abort_repack().await;
+        self.pay_refunds(self.finish_by).await;
+
         debug!(
             rounds = i,
             transactions = self.block.transactions.len(),
@@ -235,6 +706,72 @@ where
     pub async fn build(self) -> BuiltBlock {
         self.run_build().await.block
     }
+
+    /// Run several rounds, building a block by iteratively adding simulated
+    /// items, and return a [`BuildReport`] explaining why each item
+    /// considered was included or not.
+    pub async fn build_with_report(self) -> (BuiltBlock, BuildReport) {
+        let this = self.with_report().run_build().await;
+        (this.block, this.report.unwrap_or_default())
+    }
+
+    /// Run several rounds, building a block by iteratively adding simulated
+    /// items, and return a [`BuildRecording`] to replay the build.
+    pub async fn build_with_recording(self) -> (BuiltBlock, BuildRecording) {
+        let this = self.with_recording().run_build().await;
+        (this.block, this.recording.expect("recording enabled"))
+    }
+
+    /// Replay a recorded build deterministically, running the recorded
+    /// rounds on a virtual clock rather than until the deadline.
+    ///
+    /// The cache contents, the rollup and host block and config, and the gas,
+    /// concurrency and re-pack settings are restored from the recording. The builder
+    /// must be created against the same rollup and host state, and with a
+    /// cache using the same [`ScoringPolicy`], in the same state, as the
+    /// recorded build. The refund wallet is not recorded, and refunds are
+    /// paid if the builder has one.
+    ///
+    /// This version returns self to allow inspection of the building process.
+    pub async fn run_replay(mut self, recording: &BuildRecording) -> Self {
+        self.finish_by = tokio::time::Instant::now() + REPLAY_TIMEOUT;
+        self.max_gas = recording.max_gas;
+        self.max_host_gas = recording.max_host_gas;
+        self.repack_threshold = recording.repack_threshold;
+        self.block = BuiltBlock::new(recording.rollup_block.number.to());
+
+        let env = self.env.env_mut();
+        env.set_finish_by(self.finish_by);
+        env.set_concurrency_limit(recording.concurrency_limit);
+        let rollup = env.rollup_mut();
+        *rollup.cfg_mut() = recording.rollup_cfg.clone();
+        *rollup.block_mut() = recording.rollup_block.clone();
+        *rollup.fill_state_mut() = recording.fill_state.clone();
+        let host = env.host_mut();
+        *host.cfg_mut() = recording.host_cfg.clone();
+        *host.block_mut() = recording.host_block.clone();
+        env.sim_items().restore(&recording.cache);
+
+        for (i, round) in recording.rounds.iter().enumerate() {
+            self.env.sim_items().apply_events(&round.before, &recording.cache);
+            self.round_with(|cache| cache.apply_events(&round.during, &recording.cache)).await;
+            trace!(round = i + 1, remaining_items = self.env.sim_items().len(), "Round replayed");
+        }
+        self.pay_refunds(self.finish_by).await;
+
+        debug!(
+            rounds = recording.rounds.len(),
+            transactions = self.block.transactions.len(),
+            "Replay completed",
+        );
+        self
+    }
+
+    /// Replay a recorded build deterministically, and return the built
+    /// block. See [`Self::run_replay`].
+    pub async fn replay(self, recording: &BuildRecording) -> BuiltBlock {
+        self.run_replay(recording).await.block
+    }
 }
 
 #[cfg(test)]
@@ -16,7 +16,7 @@ use alloy::{
     primitives::{Address, Bytes, KECCAK256_EMPTY, U256},
 };
 use signet_constants::test_utils::*;
-use signet_sim::{AcctInfo, BlockBuild, HostEnv, RollupEnv, StateSource};
+use signet_sim::{AcctInfo, BlockBuild, HostEnv, RollupEnv, ScoringPolicy, SimCache, StateSource};
 use trevm::{
     helpers::Ctx,
     revm::{
@@ -66,6 +66,21 @@ where
     signet_evm::signet_evm_with_inspector(db, inspector, TEST_SYS).fill_cfg(&TestCfg)
 }
 
+/// Create a new host EVM with an in-memory database for testing. The EVM
+/// detects host fills.
+///
+/// Performs the same setup as [`test_signet_evm_with_inspector`], with the
+/// host system contracts and tokens.
+pub fn test_host_evm_with_inspector<I>(inspector: I) -> signet_evm::EvmNeedsBlock<InMemoryDB, I>
+where
+    I: Inspector<Ctx<InMemoryDB>>,
+{
+    let mut db = InMemoryDB::default();
+    setup_host_db(&mut db).unwrap();
+
+    signet_evm::signet_host_evm_with_inspector(db, inspector, TEST_SYS).fill_cfg(&HostTestCfg)
+}
+
 /// Test configuration for the Signet EVM.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TestCfg;
@@ -134,7 +149,59 @@ impl StateSource for SyncAsyncSource {
 pub fn test_sim_env(
     deadline: tokio::time::Instant,
 ) -> BlockBuild<Arc<InMemoryDB>, Arc<InMemoryDB>, SyncAsyncSource, SyncAsyncSource> {
-    let ru_evm = rollup_sim_env();
+    test_sim_env_with_cache(deadline, Default::default())
+}
+
+/// Create a [`BlockBuild`] simulator environment for testing, using the
+/// given [`SimCache`] and its [`ScoringPolicy`].
+pub fn test_sim_env_with_cache<P: ScoringPolicy>(
+    deadline: tokio::time::Instant,
+    sim_items: SimCache<P>,
+) -> BlockBuild<
+    Arc<InMemoryDB>,
+    Arc<InMemoryDB>,
+    SyncAsyncSource,
+    SyncAsyncSource,
+    NoOpInspector,
+    NoOpInspector,
+    P,
+> {
+    test_sim_env_with_rollup(deadline, sim_items, rollup_sim_env())
+}
+
+/// Create a [`BlockBuild`] simulator environment for testing, as
+/// [`test_sim_env_with_cache`], with the given block beneficiary.
+pub fn test_sim_env_with_beneficiary<P: ScoringPolicy>(
+    deadline: tokio::time::Instant,
+    sim_items: SimCache<P>,
+    beneficiary: Address,
+) -> BlockBuild<
+    Arc<InMemoryDB>,
+    Arc<InMemoryDB>,
+    SyncAsyncSource,
+    SyncAsyncSource,
+    NoOpInspector,
+    NoOpInspector,
+    P,
+> {
+    let mut ru_evm = rollup_sim_env();
+    ru_evm.block_mut().beneficiary = beneficiary;
+    test_sim_env_with_rollup(deadline, sim_items, ru_evm)
+}
+
+fn test_sim_env_with_rollup<P: ScoringPolicy>(
+    deadline: tokio::time::Instant,
+    sim_items: SimCache<P>,
+    ru_evm: RollupEnv<Arc<InMemoryDB>, NoOpInspector>,
+) -> BlockBuild<
+    Arc<InMemoryDB>,
+    Arc<InMemoryDB>,
+    SyncAsyncSource,
+    SyncAsyncSource,
+    NoOpInspector,
+    NoOpInspector,
+    P,
+> {
     let host_evm = host_sim_env();
 
     let mut ru_async_db = InMemoryDB::default();
@@ -147,7 +214,7 @@ pub fn test_sim_env(
         host_evm,
         deadline,
         10,
-        Default::default(),
+        sim_items,
         50_000_000,
         50_000_000,
         SyncAsyncSource(Arc::new(ru_async_db)),
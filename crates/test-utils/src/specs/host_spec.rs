@@ -7,7 +7,7 @@ use alloy::{
         constants::GWEI_TO_WEI, BlobTransactionSidecar, Header, Receipt, ReceiptEnvelope,
         TxEip1559, TxEip4844,
     },
-    primitives::{Address, Bytes, Log, LogData, U256},
+    primitives::{Address, Bytes, Log, LogData, B256, U256},
     signers::Signature,
 };
 use signet_evm::ExecutionOutcome;
@@ -51,6 +51,8 @@ pub struct HostBlockSpec {
     pub ru_block_receipt: Option<ReceiptEnvelope>,
     /// The block number. This will be overridden when making chains of blocks.
     pub block_number: AtomicU64,
+    /// The hash of the parent block.
+    pub parent_hash: B256,
 
     /// The events that were used to create this block.
     pub events: Vec<Events>,
@@ -65,6 +67,7 @@ impl Clone for HostBlockSpec {
             sidecar: self.sidecar.clone(),
             ru_block_receipt: self.ru_block_receipt.clone(),
             block_number: self.block_number().into(),
+            parent_hash: self.parent_hash,
             events: self.events.clone(),
         }
     }
@@ -86,6 +89,7 @@ impl HostBlockSpec {
             sidecar: None,
             ru_block_receipt: None,
             block_number: AtomicU64::new(0),
+            parent_hash: B256::ZERO,
             events: vec![],
         }
     }
@@ -321,13 +325,36 @@ impl HostBlockSpec {
         self.block_number.store(block_number, Ordering::Relaxed);
     }
 
+    /// Set the parent hash.
+    pub const fn with_parent_hash(mut self, parent_hash: B256) -> Self {
+        self.parent_hash = parent_hash;
+        self
+    }
+
+    /// Make a spec for the next block, with the block number and parent hash
+    /// following this block. Events are not copied.
+    pub fn child(&self) -> Self {
+        Self::new(self.constants.clone())
+            .with_block_number(self.block_number() + 1)
+            .with_parent_hash(self.hash())
+    }
+
     /// Make a header
     pub fn header(&self) -> SignetHeaderV1 {
-        let header =
-            Header { number: self.block_number(), timestamp: 1716555576, ..Default::default() };
+        let header = Header {
+            number: self.block_number(),
+            parent_hash: self.parent_hash,
+            timestamp: 1716555576,
+            ..Default::default()
+        };
         SignetHeaderV1::try_from(header).expect("test header is valid V1")
     }
 
+    /// Get the hash of the block header.
+    pub fn hash(&self) -> B256 {
+        self.header().hash()
+    }
+
     /// Make a block
     pub fn sealed_block(&self) -> SealedBlock {
         let header = self.header();
@@ -1,6 +1,6 @@
 use alloy::{
     consensus::{
-        constants::GWEI_TO_WEI,
+        constants::{ETH_TO_WEI, GWEI_TO_WEI},
         transaction::{Recovered, SignerRecoverable},
         Signed, Transaction, TxEip1559, TxEnvelope,
     },
@@ -8,15 +8,21 @@ use alloy::{
     network::TxSigner,
     primitives::{Address, TxKind, U256},
     rpc::types::mev::EthSendBundle,
-    signers::Signature,
+    signers::{local::PrivateKeySigner, Signature},
+};
+use signet_bundle::{BundlePlacement, SignetEthBundle, PLACEMENT_FIELD};
+use signet_sim::{
+    BuildRecording, BuildReport, BuiltBlock, Refund, RejectionReason, ScoringPolicy, SimCache,
+    SimItem, SimItemValidity, SimOutcomeWithCache, SimulatedItem,
 };
-use signet_bundle::SignetEthBundle;
 use signet_test_utils::{
-    evm::test_sim_env,
+    evm::{test_sim_env, test_sim_env_with_beneficiary, test_sim_env_with_cache},
     test_constants::*,
     users::{TEST_SIGNERS, TEST_USERS},
 };
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::time::{Duration, Instant};
+use trevm::revm::DatabaseRef;
 
 /// Tests the case where multiple transactions from the same
 /// sender with successive nonces are included in the same
@@ -181,6 +187,528 @@ async fn test_bundle_future_validity() {
     assert_eq!(built.transactions()[0].signer(), built.transactions()[1].signer());
 }
 
+/// Tests that bundles are replaced and cancelled by replacement UUID. This
+/// adds two bundles, replaces the first and cancels the second. The built
+/// block should contain only the replacement.
+#[tokio::test]
+async fn test_bundle_replacement_and_cancellation() {
+    let builder = test_sim_env(Instant::now() + Duration::from_millis(200));
+
+    let sender_0 = &TEST_SIGNERS[0];
+    let sender_1 = &TEST_SIGNERS[1];
+
+    let bundle = |txs, uuid: &str, nonce: u64| {
+        let mut bundle =
+            EthSendBundle { txs, replacement_uuid: Some(uuid.to_owned()), ..Default::default() };
+        bundle
+            .extra_fields
+            .insert_value(signet_bundle::REPLACEMENT_NONCE_FIELD.to_owned(), nonce)
+            .unwrap();
+        SignetEthBundle { bundle, host_txs: vec![] }
+    };
+    let send = |sender, to, nonce| async move {
+        signed_send_with_mfpg(sender, to, U256::from(1000), GWEI_TO_WEI as u128 * 10, nonce)
+            .await
+            .encoded_2718()
+            .into()
+    };
+
+    let original = bundle(vec![send(sender_0, TEST_USERS[2], 0).await], "replaced", 1);
+    let replacement = bundle(vec![send(sender_0, TEST_USERS[3], 0).await], "replaced", 2);
+    let stale = bundle(vec![send(sender_0, TEST_USERS[4], 0).await], "replaced", 1);
+    let cancelled = bundle(vec![send(sender_1, TEST_USERS[2], 0).await], "cancelled", 1);
+    let cancellation = bundle(vec![], "cancelled", 2);
+
+    let cache = builder.sim_items();
+    cache.add_bundle(original, 0).unwrap();
+    cache.add_bundle(cancelled, 0).unwrap();
+    cache.add_bundle(replacement, 0).unwrap();
+    cache.add_bundle(cancellation, 0).unwrap();
+    assert!(matches!(
+        cache.add_bundle(stale, 0),
+        Err(signet_sim::CacheError::StaleBundleUpdate { nonce: 1, latest: 2, .. })
+    ));
+    assert_eq!(cache.len(), 1);
+
+    let built = builder.build().await;
+
+    assert_eq!(built.transactions().len(), 1);
+    assert_eq!(built.transactions()[0].signer(), sender_0.address());
+    assert_eq!(built.transactions()[0].to(), Some(TEST_USERS[3]));
+}
+
+/// A scoring policy that places transactions from a favored sender first,
+/// and counts included items.
+#[derive(Debug)]
+struct FavorSender {
+    favored: Address,
+    included: AtomicUsize,
+}
+
+impl ScoringPolicy for FavorSender {
+    fn score(&self, item: &SimItem, outcome: &SimOutcomeWithCache) -> U256 {
+        let favored = item.as_tx().is_some_and(|tx| tx.signer() == self.favored);
+        if favored {
+            outcome.profit + U256::from(ETH_TO_WEI)
+        } else {
+            outcome.profit
+        }
+    }
+
+    fn on_included(&self, _item: &SimulatedItem) {
+        self.included.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Tests that the block is ordered by a custom scoring policy. This sends a
+/// transaction from each of three signers with escalating priority fees, and
+/// favors the signer paying the lowest fee.
+#[tokio::test]
+async fn test_custom_scoring_policy() {
+    let favored = TEST_SIGNERS[0].address();
+    let policy = FavorSender { favored, included: AtomicUsize::new(0) };
+    let builder = test_sim_env_with_cache(
+        Instant::now() + Duration::from_millis(200),
+        SimCache::with_policy(100, policy),
+    );
+
+    for (i, sender) in TEST_SIGNERS.iter().take(3).enumerate() {
+        let tx = signed_send_with_mfpg(
+            sender,
+            TEST_USERS[5],
+            U256::from(1000),
+            (i + 1) as u128 * GWEI_TO_WEI as u128,
+            0,
+        )
+        .await;
+        builder.sim_items().add_tx(tx, 0);
+    }
+
+    let cache = builder.sim_items().clone();
+    let built = builder.build().await;
+
+    assert_eq!(built.transactions().len(), 3);
+    assert_eq!(cache.policy().included.load(Ordering::Relaxed), 3);
+    assert_eq!(built.transactions()[0].signer(), favored);
+    assert_eq!(built.transactions()[1].signer(), TEST_SIGNERS[2].address());
+    assert_eq!(built.transactions()[2].signer(), TEST_SIGNERS[1].address());
+}
+
+/// A scoring policy that scores every outcome above zero, so that
+/// transactions sent by the beneficiary, which make no profit, are included.
+#[derive(Debug)]
+struct NonZeroScore;
+
+impl ScoringPolicy for NonZeroScore {
+    fn score(&self, _item: &SimItem, outcome: &SimOutcomeWithCache) -> U256 {
+        outcome.profit + U256::from(1)
+    }
+}
+
+/// Tests that a transaction sent by the beneficiary, accepted after another
+/// transaction of the same round, spends from the committed beneficiary
+/// balance.
+#[tokio::test]
+async fn test_beneficiary_spend() {
+    let beneficiary = &TEST_SIGNERS[0];
+    let builder = test_sim_env_with_beneficiary(
+        Instant::now() + Duration::from_millis(200),
+        SimCache::with_policy(100, NonZeroScore),
+        beneficiary.address(),
+    );
+    let initial =
+        builder.rollup_env().db().basic_ref(beneficiary.address()).unwrap().unwrap().balance;
+
+    let tip = GWEI_TO_WEI as u128;
+    let value = U256::from(ETH_TO_WEI);
+    let spend = signed_send_with_mfpg(beneficiary, TEST_USERS[5], value, tip, 0).await;
+    let send =
+        signed_send_with_mfpg(&TEST_SIGNERS[1], TEST_USERS[6], U256::from(1000), tip, 0).await;
+    builder.sim_items().add_tx(spend, 0);
+    builder.sim_items().add_tx(send, 0);
+
+    let (built, report) = builder.build_with_report().await;
+    assert_eq!(built.transactions().len(), 2);
+    assert_eq!(built.transactions()[1].signer(), beneficiary.address());
+    assert!(report.items.iter().all(|item| item.included_in_round == Some(1)));
+
+    // The beneficiary pays its own fee, and receives the fee of the other
+    // transaction.
+    let fee = U256::from(21_000 * tip);
+    assert_eq!(
+        built.rollup_state().accounts[&beneficiary.address()].info.balance,
+        initial - value + fee
+    );
+}
+
+/// Tests that the build report explains why each item was included or not.
+/// This adds a valid transaction, a transaction paying no fees, and a
+/// transaction with a nonce gap.
+#[tokio::test]
+async fn test_build_report() {
+    let builder = test_sim_env(Instant::now() + Duration::from_millis(200));
+
+    let included = signed_send_with_mfpg(
+        &TEST_SIGNERS[0],
+        TEST_USERS[5],
+        U256::from(1000),
+        GWEI_TO_WEI as u128,
+        0,
+    )
+    .await;
+    let zero_score =
+        signed_send_with_mfpg(&TEST_SIGNERS[1], TEST_USERS[5], U256::from(1000), 0, 0).await;
+    let nonce_gap = signed_send_with_mfpg(
+        &TEST_SIGNERS[2],
+        TEST_USERS[5],
+        U256::from(1000),
+        GWEI_TO_WEI as u128,
+        5,
+    )
+    .await;
+    let [included, zero_score, nonce_gap] = [included, zero_score, nonce_gap].map(|tx| {
+        let hash = tx.hash().to_string();
+        builder.sim_items().add_tx(tx, 0);
+        hash
+    });
+
+    let (built, report) = builder.build_with_report().await;
+    assert_eq!(built.transactions().len(), 1);
+    assert!(report.rounds >= 1);
+
+    let item = report.item(&included).unwrap();
+    assert_eq!(item.included_in_round, Some(1));
+    assert_eq!(item.preflight[0].validity, SimItemValidity::Now);
+    assert_eq!(item.attempts.len(), 1);
+    assert_eq!(item.attempts[0].rejection, None);
+    assert_eq!(item.attempts[0].gas_used, 21_000);
+    assert_eq!(report.included().count(), 1);
+
+    let item = report.item(&zero_score).unwrap();
+    assert_eq!(item.included_in_round, None);
+    assert_eq!(item.attempts[0].rejection, Some(RejectionReason::ZeroScore));
+
+    let item = report.item(&nonce_gap).unwrap();
+    assert!(item.attempts.is_empty());
+    assert_eq!(item.preflight.len(), 1);
+    assert_eq!(item.preflight[0].validity, SimItemValidity::Future);
+
+    let json = serde_json::to_value(&report).unwrap();
+    let json_item = |identifier: &str| {
+        json["items"].as_array().unwrap().iter().find(|item| item["identifier"] == identifier)
+    };
+    assert_eq!(json_item(&included).unwrap()["includedInRound"], 1);
+    assert_eq!(json_item(&zero_score).unwrap()["attempts"][0]["rejection"]["reason"], "zeroScore");
+}
+
+/// Tests that bundles are placed in the block as they require. This adds a
+/// transaction, two competing top of block bundles, a bundle following the
+/// transaction, and a bundle starting at position 3 paying the most.
+#[tokio::test]
+async fn test_bundle_placement() {
+    // Each sender pays a different recipient, so that the items don't
+    // conflict.
+    let send = |i: usize, mpfpg: u64| {
+        signed_send_with_mfpg(
+            &TEST_SIGNERS[i],
+            TEST_USERS[i + 5],
+            U256::from(1000),
+            GWEI_TO_WEI as u128 * mpfpg as u128,
+            0,
+        )
+    };
+    let bundle = |tx: Recovered<TxEnvelope>, uuid: &str, placement: BundlePlacement| {
+        let mut bundle = EthSendBundle {
+            txs: vec![tx.encoded_2718().into()],
+            replacement_uuid: Some(uuid.to_owned()),
+            ..Default::default()
+        };
+        bundle.extra_fields.insert_value(PLACEMENT_FIELD.to_owned(), placement).unwrap();
+        SignetEthBundle { bundle, host_txs: vec![] }
+    };
+
+    let tx = send(0, 10).await;
+    let tx_hash = *tx.hash();
+    let cache = SimCache::default();
+    cache.add_tx(tx, 0);
+    cache.add_bundle(bundle(send(1, 5).await, "top", BundlePlacement::TopOfBlock), 0).unwrap();
+    cache.add_bundle(bundle(send(2, 2).await, "outbid", BundlePlacement::TopOfBlock), 0).unwrap();
+    cache
+        .add_bundle(bundle(send(3, 1).await, "after", BundlePlacement::After(tx_hash)), 0)
+        .unwrap();
+    cache
+        .add_bundle(bundle(send(4, 20).await, "late", BundlePlacement::MinPosition(3)), 0)
+        .unwrap();
+
+    let builder =
+        test_sim_env_with_cache(Instant::now() + Duration::from_millis(200), cache.clone());
+    let (built, report) = builder.build_with_report().await;
+
+    let signers: Vec<_> = built.transactions().iter().map(|tx| tx.signer()).collect();
+    let expected: Vec<_> = [1, 0, 3, 4].map(|i| TEST_SIGNERS[i].address()).into();
+    assert_eq!(signers, expected);
+
+    // The lower-scoring top of block bundle is rejected, and removed.
+    let outbid = report.item("outbid").unwrap();
+    assert_eq!(outbid.included_in_round, None);
+    assert_eq!(
+        outbid.attempts[0].rejection,
+        Some(RejectionReason::PlacementUnsatisfiable {
+            placement: BundlePlacement::TopOfBlock,
+            position: 1
+        })
+    );
+    assert!(cache.is_empty());
+
+    // The bundle starting at position 3 waits for a later round.
+    let late = report.item("late").unwrap();
+    assert_eq!(
+        late.attempts[0].rejection,
+        Some(RejectionReason::PlacementPending {
+            placement: BundlePlacement::MinPosition(3),
+            position: 1
+        })
+    );
+    assert!(late.included_in_round > Some(late.attempts[0].round));
+}
+
+/// Tests that a bundle requesting a refund is scored net of the refund, and
+/// that the refund is paid by a transfer signed by the refund wallet at the
+/// end of the block.
+#[tokio::test]
+async fn test_bundle_refund() {
+    let recipient = TEST_USERS[7];
+    let payer = TEST_SIGNERS[4].clone();
+
+    let tx = signed_send_with_mfpg(
+        &TEST_SIGNERS[0],
+        TEST_USERS[5],
+        U256::from(1000),
+        GWEI_TO_WEI as u128 * 10,
+        0,
+    )
+    .await;
+    let bundle = SignetEthBundle {
+        bundle: EthSendBundle {
+            txs: vec![tx.encoded_2718().into()],
+            replacement_uuid: Some("refund".to_string()),
+            refund_percent: Some(50),
+            refund_recipient: Some(recipient),
+            ..Default::default()
+        },
+        host_txs: vec![],
+    };
+
+    let cache = SimCache::default();
+    cache.add_bundle(bundle, 0).unwrap();
+    // The refunds are paid in the last 100ms of the build.
+    let builder = test_sim_env_with_cache(Instant::now() + Duration::from_millis(400), cache)
+        .with_refund_wallet(payer.clone());
+    let (built, report) = builder.build_with_report().await;
+
+    // The tip is 10 gwei per gas, and the basefee is zero, so the refund
+    // transaction has no fee.
+    let profit = U256::from(21_000 * 10 * GWEI_TO_WEI);
+    let refund = Refund { recipient, value: profit / U256::from(2), fee: U256::ZERO };
+    assert_eq!(built.refunds(), &[refund]);
+
+    let item = report.item("refund").unwrap();
+    assert_eq!(item.attempts.last().unwrap().score, profit - refund.total());
+
+    // The refund is paid at the end of the block.
+    assert_eq!(built.transactions().len(), 2);
+    let refund_tx = &built.transactions()[1];
+    assert_eq!(refund_tx.signer(), payer.address());
+    assert_eq!(refund_tx.to(), Some(recipient));
+    assert_eq!(refund_tx.value(), refund.value);
+    assert_eq!(built.receipts().len(), 2);
+    assert_eq!(built.gas_used(), 42_000);
+    assert_eq!(
+        built.rollup_state().accounts[&recipient].info.balance,
+        U256::from(1000 * ETH_TO_WEI) + refund.value
+    );
+}
+
+/// Builds a block with re-packing, adding a low-value transaction, and a
+/// bundle spending the same nonce for a much higher fee once the transaction
+/// is in the block. Returns the block, the report, and the identifiers of
+/// the transaction and the bundle.
+async fn build_with_late_bundle(min_gain: U256) -> (BuiltBlock, BuildReport, String) {
+    let builder =
+        test_sim_env(Instant::now() + Duration::from_millis(400)).with_repack_threshold(min_gain);
+    let cache = builder.sim_items().clone();
+
+    let sender = &TEST_SIGNERS[0];
+    let send = |to, mpfpg| signed_send_with_mfpg(sender, to, U256::from(1000), mpfpg, 0);
+    let low = send(TEST_USERS[5], GWEI_TO_WEI as u128).await;
+    let high = send(TEST_USERS[6], GWEI_TO_WEI as u128 * 50).await;
+    let low_hash = low.hash().to_string();
+    cache.add_tx(low, 0);
+
+    let build_task = tokio::spawn(builder.build_with_report());
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let bundle = EthSendBundle {
+        txs: vec![high.encoded_2718().into()],
+        replacement_uuid: Some("late".to_owned()),
+        ..Default::default()
+    };
+    cache.add_bundle(SignetEthBundle { bundle, host_txs: vec![] }, 0).unwrap();
+
+    let (built, report) = build_task.await.unwrap();
+    (built, report, low_hash)
+}
+
+/// Tests that the block is re-packed when a much better bundle arrives that
+/// conflicts with an item already in the block, and that the re-pack is
+/// discarded if the gain is below the threshold.
+#[tokio::test]
+async fn test_repack() {
+    let (built, report, low) = build_with_late_bundle(U256::from(GWEI_TO_WEI)).await;
+    assert_eq!(built.transactions().len(), 1);
+    assert_eq!(built.transactions()[0].to(), Some(TEST_USERS[6]));
+    assert_eq!(built.receipts().len(), 1);
+    assert_eq!(built.gas_used(), 21_000);
+
+    let displaced = report.item(&low).unwrap();
+    assert_eq!(displaced.included_in_round, None);
+    assert!(displaced
+        .attempts
+        .iter()
+        .any(|attempt| attempt.rejection == Some(RejectionReason::Displaced)));
+    assert!(report.item("late").unwrap().included_in_round.is_some());
+
+    let (built, report, low) = build_with_late_bundle(U256::MAX).await;
+    assert_eq!(built.transactions().len(), 1);
+    assert_eq!(built.transactions()[0].to(), Some(TEST_USERS[5]));
+    assert_eq!(report.item(&low).unwrap().included_in_round, Some(1));
+
+    let late = report.item("late").unwrap();
+    assert_eq!(late.included_in_round, None);
+    assert!(late
+        .attempts
+        .iter()
+        .any(|attempt| attempt.rejection == Some(RejectionReason::RepackDiscarded)));
+}
+
+/// Tests that the items rejected by a discarded re-pack stay in the cache.
+/// The late bundle spends funds received from the transaction in the block,
+/// so it fails against the state the re-pack rolls back to, and is included
+/// once the re-pack is discarded.
+#[tokio::test]
+async fn test_discarded_repack_keeps_cache() {
+    let builder =
+        test_sim_env(Instant::now() + Duration::from_millis(400)).with_repack_threshold(U256::MAX);
+    let cache = builder.sim_items().clone();
+
+    let funded = PrivateKeySigner::from_slice(&[11u8; 32]).unwrap();
+    let fund = signed_send_with_mfpg(
+        &TEST_SIGNERS[0],
+        funded.address(),
+        U256::from(ETH_TO_WEI),
+        GWEI_TO_WEI as u128,
+        0,
+    )
+    .await;
+    cache.add_tx(fund, 0);
+
+    let build_task = tokio::spawn(builder.build_with_report());
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let send = |from, to| signed_send_with_mfpg(from, to, U256::from(1000), GWEI_TO_WEI as u128, 0);
+    let bundle = EthSendBundle {
+        txs: vec![
+            send(&TEST_SIGNERS[1], TEST_USERS[6]).await.encoded_2718().into(),
+            send(&funded, TEST_USERS[7]).await.encoded_2718().into(),
+        ],
+        replacement_uuid: Some("funded".to_owned()),
+        ..Default::default()
+    };
+    cache.add_bundle(SignetEthBundle { bundle, host_txs: vec![] }, 0).unwrap();
+
+    let (built, report) = build_task.await.unwrap();
+    assert_eq!(built.transactions().len(), 3);
+
+    let bundle = report.item("funded").unwrap();
+    assert!(bundle.attempts.iter().any(|attempt| matches!(
+        attempt.rejection,
+        Some(RejectionReason::SimulationFailed { .. })
+    )));
+    assert!(bundle.included_in_round.is_some());
+}
+
+/// Tests that the built block collects the receipts and state changes of
+/// the simulated transactions. This adds two sends from the same sender.
+#[tokio::test]
+async fn test_built_block_receipts_and_state() {
+    let builder = test_sim_env(Instant::now() + Duration::from_millis(200));
+
+    let sender = &TEST_SIGNERS[0];
+    let to = TEST_USERS[6];
+    let initial = builder.rollup_env().db().basic_ref(to).unwrap().unwrap_or_default().balance;
+
+    for nonce in 0..2 {
+        let tx =
+            signed_send_with_mfpg(sender, to, U256::from(1000), GWEI_TO_WEI as u128, nonce).await;
+        builder.sim_items().add_tx(tx, 0);
+    }
+
+    let built = builder.build().await;
+    assert_eq!(built.transactions().len(), 2);
+
+    // Cumulative gas used is relative to the start of the block.
+    let receipts = built.receipts();
+    assert_eq!(receipts.len(), 2);
+    assert!(receipts.iter().all(|receipt| receipt.is_success()));
+    assert_eq!(receipts[0].cumulative_gas_used(), 21_000);
+    assert_eq!(receipts[1].cumulative_gas_used(), built.gas_used());
+    assert_eq!(built.logs().count(), 0);
+
+    // The state changes of both transactions are combined.
+    let state = built.rollup_state();
+    assert_eq!(state.accounts[&sender.address()].info.nonce, 2);
+    assert_eq!(state.accounts[&to].info.balance, initial + U256::from(2000));
+    assert!(built.host_state().accounts.is_empty());
+}
+
+/// Tests that a recorded build replays to the same block, before and after
+/// serializing the recording. This adds a transaction before the build, and
+/// two more while it runs. The replay starts from an empty cache, and runs
+/// after the recorded deadline.
+#[tokio::test]
+async fn test_build_replay() {
+    let builder = test_sim_env(Instant::now() + Duration::from_millis(200));
+    let cache = builder.sim_items().clone();
+
+    let to = TEST_USERS[7];
+    let send = |signer, nonce| {
+        signed_send_with_mfpg(signer, to, U256::from(1000), GWEI_TO_WEI as u128, nonce)
+    };
+    cache.add_tx(send(&TEST_SIGNERS[0], 0).await, 0);
+
+    let build_task = tokio::spawn(builder.build_with_recording());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    cache.add_txs([send(&TEST_SIGNERS[1], 0).await, send(&TEST_SIGNERS[0], 1).await], 0);
+
+    let (built, recording) = build_task.await.unwrap();
+    assert!(!built.is_empty());
+    assert_eq!(recording.cache().len(), 1);
+    assert!(!recording.rounds().is_empty());
+
+    let replayed = test_sim_env(Instant::now()).replay(&recording).await;
+    assert_eq!(replayed.transactions(), built.transactions());
+    assert_eq!(replayed.contents_hash(), built.contents_hash());
+    assert_eq!(replayed.gas_used(), built.gas_used());
+
+    // A deserialized recording replays to the same block.
+    let json = serde_json::to_string(&recording).unwrap();
+    let deserialized: BuildRecording = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.rounds().len(), recording.rounds().len());
+    let replayed = test_sim_env(Instant::now()).replay(&deserialized).await;
+    assert_eq!(replayed.transactions(), built.transactions());
+    assert_eq!(replayed.contents_hash(), built.contents_hash());
+}
+
 // utilities below this point are reproduced from other places, however,
 // because this test modifies the _db_ rather than the _evm_,
 // we need to handle them slightly differently here.
@@ -2,9 +2,10 @@
 //!
 //! - Txns must not revert, unless marked as revertible.
 //! - Txns must not be dropped by market rules, unless marked as droppable.
+//! - Txns marked as droppable are skipped if they fail for any reason.
 
 use alloy::{
-    consensus::{TxEnvelope, TypedTransaction},
+    consensus::{constants::GWEI_TO_WEI, TxEnvelope, TypedTransaction},
     primitives::{keccak256, Address, U256},
     signers::local::PrivateKeySigner,
     uint,
@@ -13,24 +14,33 @@ use signet_bundle::{
     BundleInspector, SignetEthBundle, SignetEthBundleDriver, SignetEthBundleError,
 };
 use signet_constants::parmigiana::{HOST_WBTC, HOST_WETH};
-use signet_evm::EvmNeedsTx;
+use signet_evm::{EvmNeedsTx, PrecompileRegistry};
+use signet_sim::{SimItem, SimItemValidity};
 use signet_test_utils::{
     chain::{HOST_CHAIN_ID, RU_CHAIN_ID, RU_ORDERS},
     contracts::{
         counter::{Counter, COUNTER_SLOT, COUNTER_TEST_ADDRESS},
         reverts::REVERT_TEST_ADDRESS,
     },
-    evm::test_signet_evm_with_inspector,
+    evm::{test_signet_evm, test_signet_evm_with_inspector, test_sim_env, SyncAsyncSource},
     specs::{sign_tx_with_key_pair, simple_bundle, simple_call, simple_send},
     users::{TEST_SIGNERS, TEST_USERS},
 };
 use signet_types::AggregateFills;
 use signet_zenith::HostOrders::{initiateCall, Filled, Input, Output};
-use std::{borrow::Cow, sync::LazyLock, time::Duration};
+use std::{
+    borrow::Cow,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
 use tokio::time::Instant;
 use trevm::{
     inspectors::{Layered, TimeLimit},
-    revm::{database::InMemoryDB, inspector::NoOpInspector},
+    revm::{
+        database::InMemoryDB,
+        inspector::NoOpInspector,
+        precompile::{u64_to_address, Precompile, PrecompileError, PrecompileId, PrecompileResult},
+    },
     BundleDriver, BundleError, NoopBlock,
 };
 
@@ -284,3 +294,235 @@ fn test_order_bundle_droppable() {
     assert_eq!(trevm.read_balance_ref(*ORDERER), inital_balance);
     assert_eq!(trevm.read_balance_ref(TX_2_RECIPIENT), U256::ONE);
 }
+
+// This bundle contains two sends from user 0:
+// 1. A send to 0x3131... with a nonce that is too high.
+// 2. A send to 0x3232... with the correct nonce.
+fn bad_nonce_bundle() -> SignetEthBundle {
+    let tx_1 = simple_send(TX_0_RECIPIENT, U256::ONE, 5, RU_CHAIN_ID);
+    let tx_2 = simple_send(TX_2_RECIPIENT, U256::ONE, 0, RU_CHAIN_ID);
+
+    let tx_1 = sign_tx_with_key_pair(&SENDER_WALLET, tx_1);
+    let tx_2 = sign_tx_with_key_pair(&SENDER_WALLET, tx_2);
+
+    simple_bundle(vec![tx_1, tx_2], vec![], 0)
+}
+
+#[test]
+fn test_bundle_dropping_revert() {
+    let trevm = bundle_evm();
+
+    let mut bundle = counter_bundle(true);
+
+    // Mark the second transaction as droppable.
+    let hash = keccak256(&bundle.txs()[1]);
+    bundle.bundle.dropping_tx_hashes.push(hash);
+
+    let bundle = bundle.try_to_recovered().unwrap();
+    let mut driver =
+        SignetEthBundleDriver::new(&bundle, host_evm(), Instant::now() + Duration::from_secs(5));
+
+    // We expect this to work and drop the second transaction.
+    let trevm = driver.run_bundle(trevm).unwrap();
+
+    assert_eq!(trevm.read_balance_ref(TX_0_RECIPIENT), U256::ONE);
+    assert_eq!(trevm.read_storage_ref(COUNTER_TEST_ADDRESS, COUNTER_SLOT), U256::ZERO);
+    assert_eq!(trevm.read_balance_ref(TX_2_RECIPIENT), U256::ONE);
+
+    // The dropped transaction has no receipt and used no gas.
+    let outputs = driver.into_outputs();
+    assert_eq!(outputs.dropped_txs, vec![hash]);
+    assert_eq!(outputs.receipts.len(), 2);
+    assert_eq!(outputs.total_gas_used, 42_000);
+}
+
+#[test]
+fn test_bundle_state_recording() {
+    let mut bundle = counter_bundle(true);
+    let hash = keccak256(&bundle.txs()[1]);
+    bundle.bundle.dropping_tx_hashes.push(hash);
+    let bundle = bundle.try_to_recovered().unwrap();
+
+    let mut driver =
+        SignetEthBundleDriver::new(&bundle, host_evm(), Instant::now() + Duration::from_secs(5));
+    driver.run_bundle(bundle_evm()).unwrap();
+    assert!(driver.into_outputs().rollup_states.is_empty());
+
+    let mut driver =
+        SignetEthBundleDriver::new(&bundle, host_evm(), Instant::now() + Duration::from_secs(5))
+            .with_state_recording();
+    driver.run_bundle(bundle_evm()).unwrap();
+
+    // The state loaded by the dropped transaction is recorded too.
+    let outputs = driver.into_outputs();
+    assert_eq!(outputs.rollup_states.len(), 3);
+    assert!(outputs.rollup_states[1].contains_key(&REVERT_TEST_ADDRESS));
+    assert!(outputs.host_states.is_empty());
+}
+
+#[test]
+fn test_bundle_dropping_bad_nonce() {
+    let bundle = bad_nonce_bundle();
+    let hash = keccak256(&bundle.txs()[0]);
+
+    // Without marking the first transaction as droppable, the bundle fails.
+    let recovered = bundle.try_to_recovered().unwrap();
+    let mut driver =
+        SignetEthBundleDriver::new(&recovered, host_evm(), Instant::now() + Duration::from_secs(5));
+    assert!(driver.run_bundle(bundle_evm()).is_err());
+
+    let mut bundle = bundle;
+    bundle.bundle.dropping_tx_hashes.push(hash);
+    let recovered = bundle.try_to_recovered().unwrap();
+    let mut driver =
+        SignetEthBundleDriver::new(&recovered, host_evm(), Instant::now() + Duration::from_secs(5));
+
+    // We expect this to work and skip the first transaction.
+    let trevm = driver.run_bundle(bundle_evm()).unwrap();
+
+    assert_eq!(trevm.read_balance_ref(TX_0_RECIPIENT), U256::ZERO);
+    assert_eq!(trevm.read_balance_ref(TX_2_RECIPIENT), U256::ONE);
+
+    let outputs = driver.into_outputs();
+    assert_eq!(outputs.dropped_txs, vec![hash]);
+    assert_eq!(outputs.receipts.len(), 1);
+    assert_eq!(outputs.total_gas_used, 21_000);
+}
+
+#[test]
+fn test_order_bundle_dropping() {
+    let trevm = bundle_evm();
+
+    let inital_balance = trevm.read_balance_ref(*ORDERER);
+
+    let mut bundle = order_bundle(vec![]);
+
+    // Mark the order transaction as droppable. No fill is provided, so it
+    // is dropped.
+    let hash = keccak256(&bundle.txs()[1]);
+    bundle.bundle.dropping_tx_hashes.push(hash);
+
+    let bundle = bundle.try_to_recovered().unwrap();
+    let mut driver =
+        SignetEthBundleDriver::new(&bundle, host_evm(), Instant::now() + Duration::from_secs(5));
+
+    let trevm = match driver.run_bundle(trevm) {
+        Ok(t) => t,
+        Err(err) => panic!("unexpected error running dropping order bundle: {:?}", err.error()),
+    };
+
+    // The order tx was dropped, but both sends were executed.
+    assert_eq!(trevm.read_balance_ref(TX_0_RECIPIENT), U256::ONE);
+    assert_eq!(trevm.read_balance_ref(*ORDERER), inital_balance);
+    assert_eq!(trevm.read_balance_ref(TX_2_RECIPIENT), U256::ONE);
+
+    let outputs = driver.into_outputs();
+    assert_eq!(outputs.dropped_txs, vec![hash]);
+    assert_eq!(outputs.bundle_orders, Default::default());
+}
+
+#[tokio::test]
+async fn test_bundle_dropping_preflight() {
+    let source = SyncAsyncSource(Arc::new(test_signet_evm().into_db()));
+
+    let bundle = bad_nonce_bundle();
+    let hash = keccak256(&bundle.txs()[0]);
+
+    // The first transaction's nonce is too high.
+    let item = SimItem::try_from(bundle.clone()).unwrap();
+    assert_eq!(item.check(&source, &source).await.unwrap(), SimItemValidity::Future);
+
+    // When it is droppable, the bundle is valid without it.
+    let mut droppable = bundle.clone();
+    droppable.bundle.dropping_tx_hashes.push(hash);
+    let item = SimItem::try_from(droppable).unwrap();
+    assert_eq!(item.check(&source, &source).await.unwrap(), SimItemValidity::Now);
+
+    // A bundle of dropped transactions may still become valid.
+    let mut only_droppable = simple_bundle(
+        vec![sign_tx_with_key_pair(
+            &SENDER_WALLET,
+            simple_send(TX_0_RECIPIENT, U256::ONE, 5, RU_CHAIN_ID),
+        )],
+        vec![],
+        0,
+    );
+    only_droppable.bundle.dropping_tx_hashes.push(hash);
+    let item = SimItem::try_from(only_droppable).unwrap();
+    assert_eq!(item.check(&source, &source).await.unwrap(), SimItemValidity::Future);
+}
+
+#[tokio::test]
+async fn test_bundle_dropping_excluded_from_block() {
+    let mut bundle = bad_nonce_bundle();
+    let hash = keccak256(&bundle.txs()[0]);
+    let included = keccak256(&bundle.txs()[1]);
+    bundle.bundle.dropping_tx_hashes.push(hash);
+
+    let builder = test_sim_env(Instant::now() + Duration::from_millis(200));
+    builder.sim_items().add_bundle(bundle, 0).unwrap();
+
+    let built = builder.build().await;
+
+    // Only the second transaction is included.
+    assert_eq!(built.transactions().len(), 1);
+    assert_eq!(*built.transactions()[0].tx_hash(), included);
+}
+
+#[test]
+fn test_bundle_refundable_increase() {
+    let bundle = counter_bundle(false);
+    let first = keccak256(&bundle.txs()[0]);
+
+    // By default, the last transaction counts towards the refund.
+    let recovered = bundle.try_to_recovered().unwrap();
+    let mut driver =
+        SignetEthBundleDriver::new(&recovered, host_evm(), Instant::now() + Duration::from_secs(5));
+    driver.run_bundle(bundle_evm()).unwrap();
+
+    // The sends tip 1 gwei per gas, and the basefee is zero.
+    let send_tip = U256::from(21_000 * GWEI_TO_WEI);
+    assert_eq!(driver.refundable_balance_increase(), send_tip);
+    assert!(driver.beneficiary_balance_increase() > send_tip * U256::from(2));
+
+    // Otherwise, the refund transactions count.
+    let mut bundle = bundle;
+    bundle.bundle.refund_tx_hashes.push(first);
+    let recovered = bundle.try_to_recovered().unwrap();
+    let mut driver =
+        SignetEthBundleDriver::new(&recovered, host_evm(), Instant::now() + Duration::from_secs(5));
+    driver.run_bundle(bundle_evm()).unwrap();
+    assert_eq!(driver.refundable_balance_increase(), send_tip);
+    assert_eq!(recovered.refund_recipient_or_default(), Some(SENDER_WALLET.address()));
+}
+
+fn always_fails(_input: &[u8], _gas_limit: u64) -> PrecompileResult {
+    Err(PrecompileError::OutOfGas)
+}
+
+const ALWAYS_FAILS: Precompile = Precompile::new(
+    PrecompileId::Custom(Cow::Borrowed("always_fails")),
+    u64_to_address(0x5150),
+    always_fails,
+);
+
+#[test]
+fn test_bundle_precompile_registry() {
+    let to = *ALWAYS_FAILS.address();
+    let tx = sign_tx_with_key_pair(&SENDER_WALLET, simple_send(to, U256::ONE, 0, RU_CHAIN_ID));
+    let bundle = simple_bundle(vec![tx], vec![], 0).try_to_recovered().unwrap();
+
+    // The precompile is not in the default registry, so the send succeeds.
+    let mut driver =
+        SignetEthBundleDriver::new(&bundle, host_evm(), Instant::now() + Duration::from_secs(5));
+    let trevm = driver.run_bundle(bundle_evm()).unwrap();
+    assert_eq!(trevm.read_balance_ref(to), U256::ONE);
+
+    // Once the precompile is active at the bundle's height, the send reverts.
+    let registry = PrecompileRegistry::default().with_precompile(0, ALWAYS_FAILS);
+    let mut driver =
+        SignetEthBundleDriver::new(&bundle, host_evm(), Instant::now() + Duration::from_secs(5))
+            .with_precompile_registry(Cow::Owned(registry));
+    let (err, _) = driver.run_bundle(bundle_evm()).unwrap_err().take_err();
+    assert!(matches!(err, SignetEthBundleError::Bundle(BundleError::BundleReverted)));
+}
@@ -3,27 +3,34 @@ use alloy::{
         constants::{ETH_TO_WEI, GWEI_TO_WEI},
         Header, ReceiptEnvelope, TxEip1559, TxEnvelope,
     },
-    primitives::{Address, U256},
+    primitives::{Address, Log, LogData, B256, U256},
     signers::{local::PrivateKeySigner, Signature},
-    sol_types::SolEvent,
+    sol_types::{SolCall, SolEvent},
     uint,
 };
 use signet_constants::SignetSystemConstants;
 use signet_evm::{
-    sys::{MintNative, MintToken, MintTokenSysLog, SysBase},
-    SignetDriver,
+    sys::{
+        MintNative, MintToken, MintTokenSysLog, StorageWrite, SysBase, SystemCall, SystemHook,
+        SystemHookContext, SystemOutput,
+    },
+    PrecompileRegistry, SignetDriver,
 };
 use signet_extract::{Extractable, ExtractedEvent, Extracts};
 use signet_test_utils::{
     chain::{
         fake_block, Chain, HOST_USDC, HOST_USDT, RU_CHAIN_ID, RU_WETH, USDC_RECORD, USDT_RECORD,
     },
+    contracts::counter::{Counter, COUNTER_SLOT, COUNTER_TEST_ADDRESS},
     evm::test_signet_evm,
     specs::{make_wallet, sign_tx_with_key_pair, simple_send},
 };
 use signet_types::primitives::{RecoveredBlock, SignetHeaderV1, TransactionSigned};
 use signet_zenith::MINTER_ADDRESS;
-use trevm::revm::database::in_memory_db::InMemoryDB;
+use trevm::revm::{
+    database::in_memory_db::InMemoryDB,
+    precompile::{u64_to_address, Precompile, PrecompileError, PrecompileId, PrecompileResult},
+};
 
 struct TestEnv {
     pub wallets: Vec<PrivateKeySigner>,
@@ -364,6 +371,128 @@ fn test_a_transact() {
     assert_eq!(trevm.read_balance(third_party), expected_third_party_balance);
 }
 
+/// Writes the rollup height to storage before the block, and increments the
+/// counter after the block.
+#[derive(Debug)]
+struct TestHook;
+
+const HOOK_STORAGE: Address = Address::repeat_byte(0x77);
+
+fn hook_log(topic: u8) -> Log {
+    Log {
+        address: MINTER_ADDRESS,
+        data: LogData::new_unchecked(vec![B256::repeat_byte(topic)], Default::default()),
+    }
+}
+
+impl SystemHook for TestHook {
+    fn name(&self) -> &'static str {
+        "TestHook"
+    }
+
+    fn pre_block(&self, ctx: &SystemHookContext<'_>) -> Vec<SystemOutput> {
+        vec![StorageWrite::new(HOOK_STORAGE, hook_log(1))
+            .with_slot(U256::from(1), U256::from(ctx.ru_height))
+            .into()]
+    }
+
+    fn post_block(&self, _ctx: &SystemHookContext<'_>) -> Vec<SystemOutput> {
+        vec![SystemCall::new(
+            COUNTER_TEST_ADDRESS,
+            Counter::incrementCall {}.abi_encode().into(),
+            hook_log(2),
+        )
+        .into()]
+    }
+}
+
+#[test]
+fn test_system_hooks() {
+    let mut context = TestEnv::new();
+    let sender = context.wallets[0].address();
+
+    let to = Address::repeat_byte(2);
+    let tx = context.signed_simple_send(0, to, U256::from(100));
+
+    // Setup the driver
+    let block = context.next_block();
+    let mut extracts = Extracts::<Chain>::empty(&block);
+    let mut driver =
+        context.driver(&mut extracts, vec![tx.clone().into()]).with_system_hook(TestHook);
+    let ru_height = driver.ru_height();
+
+    // Run the EVM
+    let mut trevm = context.trevm().drive_block(&mut driver).unwrap();
+    let (sealed_block, receipts) = driver.finish();
+
+    // The pre-block write comes first, the post-block call comes last
+    assert_eq!(
+        sealed_block.senders().collect::<Vec<_>>(),
+        vec![MINTER_ADDRESS, sender, MINTER_ADDRESS]
+    );
+    let txns: Vec<&TransactionSigned> = sealed_block.transactions().iter().map(|t| &**t).collect();
+    assert_eq!(txns[1], &TransactionSigned::from(tx));
+    assert_eq!(receipts.len(), 3);
+    assert_eq!(receipts[0].logs(), &[hook_log(1)]);
+    assert_eq!(receipts[2].logs().last(), Some(&hook_log(2)));
+    assert!(receipts[2].status());
+
+    // Both outputs are applied, and the minter nonce is bumped for each
+    assert_eq!(trevm.read_storage(HOOK_STORAGE, U256::from(1)), U256::from(ru_height));
+    assert_eq!(trevm.read_storage(COUNTER_TEST_ADDRESS, COUNTER_SLOT), U256::from(1));
+    assert_eq!(trevm.read_nonce(MINTER_ADDRESS), 2);
+    assert_eq!(trevm.read_balance(to), U256::from(100));
+}
+
+fn always_fails(_input: &[u8], _gas_limit: u64) -> PrecompileResult {
+    Err(PrecompileError::OutOfGas)
+}
+
+const ALWAYS_FAILS: Precompile = Precompile::new(
+    PrecompileId::Custom(std::borrow::Cow::Borrowed("always_fails")),
+    u64_to_address(0x5150),
+    always_fails,
+);
+
+#[test]
+fn test_precompile_activation_boundary() {
+    const ACTIVATION_HEIGHT: u64 = 10;
+
+    let mut context = TestEnv::new();
+    let to = *ALWAYS_FAILS.address();
+    let registry = PrecompileRegistry::default().with_precompile(ACTIVATION_HEIGHT, ALWAYS_FAILS);
+
+    // The first block is just before the precompile activates.
+    let tx = context.signed_simple_send(0, to, U256::from(100));
+    let block = context.next_block();
+    let mut extracts = Extracts::<Chain>::empty(&block);
+    extracts.ru_height = ACTIVATION_HEIGHT - 1;
+    let mut driver =
+        context.driver(&mut extracts, vec![tx.into()]).with_precompile_registry(registry.clone());
+
+    let mut trevm = context.trevm().drive_block(&mut driver).unwrap();
+    let (_, receipts) = driver.finish();
+
+    // Before activation, the address has no code, and the send succeeds.
+    assert!(receipts[0].status());
+    assert_eq!(trevm.read_balance(to), U256::from(100));
+
+    // The second block is the first with the precompile active.
+    let tx = context.signed_simple_send(0, to, U256::from(100));
+    let block = context.next_block();
+    let mut extracts = Extracts::<Chain>::empty(&block);
+    extracts.ru_height = ACTIVATION_HEIGHT;
+    let mut driver =
+        context.driver(&mut extracts, vec![tx.into()]).with_precompile_registry(registry);
+
+    let mut trevm = trevm.drive_block(&mut driver).unwrap();
+    let (_, receipts) = driver.finish();
+
+    // After activation, the call to the precompile fails.
+    assert!(!receipts[0].status());
+    assert_eq!(trevm.read_balance(to), U256::from(100));
+}
+
 fn fake_tx() -> TransactionSigned {
     let tx = TxEip1559::default();
     let signature = Signature::test_signature();
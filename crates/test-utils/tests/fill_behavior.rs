@@ -8,9 +8,13 @@
 //! driver behaves correctly.
 
 use alloy::{
-    consensus::{constants::ETH_TO_WEI, Header, ReceiptEnvelope, TxEnvelope, TypedTransaction},
+    consensus::{
+        constants::{ETH_TO_WEI, GWEI_TO_WEI},
+        Header, ReceiptEnvelope, TxEnvelope, TypedTransaction,
+    },
     eips::BlockNumberOrTag,
-    primitives::{keccak256, Address, U256},
+    primitives::{keccak256, Address, B256, U256},
+    rpc::types::{state::AccountOverride, BlockOverrides},
     signers::local::PrivateKeySigner,
     uint,
 };
@@ -18,27 +22,34 @@ use signet_bundle::{
     BundleInspector, SignetBundleDriver, SignetCallBundle, SignetEthBundle, SignetEthBundleDriver,
     SignetEthBundleError,
 };
-use signet_constants::test_utils::{HOST_CHAIN_ID, HOST_WBTC, HOST_WETH, RU_CHAIN_ID};
+use signet_constants::test_utils::{HOST_CHAIN_ID, HOST_WBTC, HOST_WETH, RU_CHAIN_ID, TEST_SYS};
 use signet_constants::SignetSystemConstants;
-use signet_evm::{EvmNeedsTx, SignetDriver};
+use signet_evm::{EvmNeedsTx, PrecompileRegistry, SignetDriver};
 use signet_extract::{Extractable, ExtractedEvent, Extracts};
 use signet_test_utils::{
     chain::{fake_block, Chain, RU_ORDERS},
-    evm::test_signet_evm_with_inspector,
-    specs::{sign_tx_with_key_pair, simple_bundle, simple_call, simple_send},
+    evm::{test_host_evm_with_inspector, test_signet_evm_with_inspector},
+    specs::{
+        make_wallet, sign_tx_with_key_pair, signed_simple_call, signed_simple_send, simple_bundle,
+        simple_call, simple_send,
+    },
     users::{TEST_SIGNERS, TEST_USERS},
 };
 use signet_types::{
     primitives::{SignetHeaderV1, TransactionSigned},
     AggregateFills,
 };
-use signet_zenith::HostOrders::{initiateCall, Filled, Input, Output};
+use signet_zenith::HostOrders::{fillCall, initiateCall, Filled, Input, Output};
 use std::{borrow::Cow, sync::LazyLock, time::Duration};
 use tokio::time::Instant;
 use trevm::BundleError;
 use trevm::{
     inspectors::{Layered, TimeLimit},
-    revm::{database::InMemoryDB, inspector::NoOpInspector},
+    revm::{
+        database::InMemoryDB,
+        inspector::NoOpInspector,
+        precompile::{u64_to_address, Precompile, PrecompileError, PrecompileId, PrecompileResult},
+    },
     BundleDriver, NoopBlock,
 };
 
@@ -202,6 +213,7 @@ fn to_call_bundle(bundle: &SignetEthBundle) -> SignetCallBundle {
             coinbase: None,
             timeout: None,
         },
+        ..Default::default()
     }
 }
 
@@ -239,6 +251,284 @@ mod call_bundle {
         // All three transactions should have been executed
         assert_eq!(response.results.len(), 3, "all transactions should execute in call bundle");
     }
+
+    /// Test that call bundle simulates host transactions against the host EVM
+    /// and reports their results and fills.
+    #[test]
+    fn reports_host_fills() {
+        let trevm = call_bundle_evm();
+        let host_evm = test_host_evm_with_inspector(NoOpInspector).fill_block(&NoopBlock);
+
+        let fill_tx = signed_simple_call(
+            &TEST_SIGNERS[2],
+            TEST_SYS.host_orders(),
+            &fillCall { outputs: full_fills().outputs },
+            U256::ZERO,
+            0,
+            HOST_CHAIN_ID,
+        );
+
+        let bundle = order_bundle();
+        let call_bundle = to_call_bundle(&bundle).append_2718_host_tx(fill_tx);
+
+        let mut driver = SignetBundleDriver::new_with_host(&call_bundle, host_evm);
+        let _trevm = driver.run_bundle(trevm).expect("call bundle should succeed");
+
+        // The host EVM contains the state changes of the fill
+        let host_evm = driver.take_host_evm().expect("host evm should be returned");
+        assert_eq!(host_evm.read_nonce_ref(TEST_USERS[2]), 1);
+
+        let response = driver.into_response();
+
+        // The host transaction succeeded, and does not count towards the
+        // rollup totals
+        assert_eq!(response.host_results.len(), 1);
+        assert!(response.host_results[0].revert.is_none());
+        assert_eq!(response.results.len(), 3);
+        assert_eq!(
+            response.total_gas_used,
+            response.results.iter().map(|r| r.gas_used).sum::<u64>()
+        );
+
+        // The host fills cover the orders of the bundle
+        assert_eq!(response.host_fills, aggregate_from_filled(&full_fills()));
+        response
+            .host_fills
+            .check_ru_tx_events(&response.fills, &response.orders)
+            .expect("host fills should cover the orders");
+    }
+
+    /// Test that call bundle traces every transaction when tracing is
+    /// enabled, attributing orders and fills to the frames that emitted them.
+    #[test]
+    fn traces_orders_and_fills() {
+        let trevm = call_bundle_evm();
+        let host_evm = test_host_evm_with_inspector(NoOpInspector).fill_block(&NoopBlock);
+
+        let fill_tx = signed_simple_call(
+            &TEST_SIGNERS[2],
+            TEST_SYS.host_orders(),
+            &fillCall { outputs: full_fills().outputs },
+            U256::ZERO,
+            0,
+            HOST_CHAIN_ID,
+        );
+
+        let bundle = order_bundle();
+        let call_bundle = to_call_bundle(&bundle).append_2718_host_tx(fill_tx);
+
+        let mut driver = SignetBundleDriver::new_with_host(&call_bundle, host_evm).with_tracing();
+        let _trevm = driver.run_bundle(trevm).expect("call bundle should succeed");
+
+        let response = driver.into_response();
+        assert_eq!(response.traces.len(), 3);
+        assert_eq!(response.host_traces.len(), 1);
+
+        // The order is attributed to the root frame of the order transaction.
+        let order_trace = &response.traces[1];
+        assert_eq!(order_trace.tx_hash, keccak256(&call_bundle.txs()[1]));
+        assert_eq!(order_trace.call.frame.to, Some(RU_ORDERS));
+        assert_eq!(order_trace.call.orders.len(), 1);
+        assert_eq!(order_trace.call.all_orders().count(), 1);
+        assert!(!order_trace.logs.is_empty());
+
+        // The sends emit no orders, and change the recipient's balance.
+        let send_trace = &response.traces[0];
+        assert_eq!(send_trace.call.all_orders().count(), 0);
+        assert_eq!(
+            send_trace.state_diff.post.get(&TX_0_RECIPIENT).and_then(|acct| acct.balance),
+            Some(U256::ONE)
+        );
+
+        // The fills are attributed to the host orders contract, and the token
+        // transfers they made show up as subcalls.
+        let fill_trace = &response.host_traces[0];
+        assert_eq!(fill_trace.call.frame.to, Some(TEST_SYS.host_orders()));
+        assert_eq!(fill_trace.call.fills.len(), 1);
+        assert_eq!(fill_trace.call.all_fills().count(), 1);
+        assert!(fill_trace.call.all_orders().next().is_none());
+        assert_eq!(fill_trace.call.calls.len(), 2);
+    }
+
+    /// Test that call bundle selects the precompile set for the bundle's
+    /// block from its precompile registry.
+    #[test]
+    fn applies_precompile_registry() {
+        fn always_fails(_input: &[u8], _gas_limit: u64) -> PrecompileResult {
+            Err(PrecompileError::OutOfGas)
+        }
+        let always_fails = Precompile::new(
+            PrecompileId::Custom(Cow::Borrowed("always_fails")),
+            u64_to_address(0x5150),
+            always_fails,
+        );
+        let to = *always_fails.address();
+
+        let tx = signed_simple_send(&SENDER_WALLET, to, U256::ONE, 0, RU_CHAIN_ID);
+        let call_bundle = to_call_bundle(&simple_bundle(vec![tx], vec![], 0));
+
+        let registry = PrecompileRegistry::default().with_precompile(0, always_fails);
+        let mut driver = SignetBundleDriver::new(&call_bundle).with_precompile_registry(registry);
+        let trevm = driver.run_bundle(call_bundle_evm()).expect("call bundle should succeed");
+
+        // The call to the precompile failed, so no value was transferred.
+        assert!(driver.response().results[0].revert.is_some());
+        assert_eq!(trevm.read_balance_ref(to), U256::ZERO);
+    }
+
+    /// Test that call bundle applies state overrides before simulating the
+    /// bundle.
+    #[test]
+    fn applies_state_overrides() {
+        let trevm = call_bundle_evm();
+
+        // An unfunded sender, whose nonce is overridden.
+        let wallet = make_wallet(0x77);
+        let slot = B256::with_last_byte(1);
+        let value = B256::with_last_byte(2);
+
+        let tx = signed_simple_send(&wallet, TX_0_RECIPIENT, U256::ONE, 5, RU_CHAIN_ID);
+        let call_bundle = to_call_bundle(&simple_bundle(vec![tx], vec![], 0)).with_state_overrides(
+            [
+                (
+                    wallet.address(),
+                    AccountOverride {
+                        balance: Some(U256::from(ETH_TO_WEI)),
+                        nonce: Some(5),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    TX_2_RECIPIENT,
+                    AccountOverride {
+                        state_diff: Some([(slot, value)].into_iter().collect()),
+                        ..Default::default()
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let mut driver = SignetBundleDriver::new(&call_bundle);
+        let trevm = driver.run_bundle(trevm).expect("call bundle should succeed");
+
+        assert_eq!(trevm.read_balance_ref(TX_0_RECIPIENT), U256::ONE);
+        assert_eq!(trevm.read_nonce_ref(wallet.address()), 6);
+        assert_eq!(
+            trevm.read_storage_ref(TX_2_RECIPIENT, slot.into()),
+            U256::from_be_bytes(value.0)
+        );
+
+        let response = driver.into_response();
+        assert_eq!(response.results.len(), 1);
+        assert!(response.results[0].revert.is_none());
+    }
+
+    /// Test that call bundle applies block overrides, and that they take
+    /// precedence over the bundle's block fields.
+    #[test]
+    fn applies_block_overrides() {
+        let trevm = call_bundle_evm();
+        let coinbase = Address::repeat_byte(0x55);
+
+        let tx = signed_simple_send(&SENDER_WALLET, TX_0_RECIPIENT, U256::ONE, 0, RU_CHAIN_ID);
+        let mut call_bundle =
+            to_call_bundle(&simple_bundle(vec![tx], vec![], 0)).with_block_overrides(
+                BlockOverrides { coinbase: Some(coinbase), ..Default::default() },
+            );
+        call_bundle.bundle.coinbase = Some(Address::repeat_byte(0x66));
+
+        let mut driver = SignetBundleDriver::new(&call_bundle);
+        let trevm = driver.run_bundle(trevm).expect("call bundle should succeed");
+
+        // The priority fee is paid to the overridden coinbase.
+        let fees = U256::from(21_000 * GWEI_TO_WEI);
+        assert_eq!(trevm.read_balance_ref(coinbase), fees);
+        assert_eq!(trevm.read_balance_ref(Address::repeat_byte(0x66)), U256::ZERO);
+        assert_eq!(driver.response().coinbase_diff, fees);
+    }
+
+    /// Test that call bundle rejects block hash overrides.
+    #[test]
+    fn errors_on_block_hash_overrides() {
+        let trevm = call_bundle_evm();
+
+        let tx = signed_simple_send(&SENDER_WALLET, TX_0_RECIPIENT, U256::ONE, 0, RU_CHAIN_ID);
+        let call_bundle = to_call_bundle(&simple_bundle(vec![tx], vec![], 0)).with_block_overrides(
+            BlockOverrides {
+                block_hash: Some([(1, B256::repeat_byte(1))].into_iter().collect()),
+                ..Default::default()
+            },
+        );
+
+        let mut driver = SignetBundleDriver::new(&call_bundle);
+        let err = driver.run_bundle(trevm).expect_err("call bundle should fail");
+        assert!(matches!(err.error(), BundleError::EVMError { .. }));
+    }
+
+    /// Test that call bundle rejects block number overrides.
+    #[test]
+    fn errors_on_block_number_overrides() {
+        let trevm = call_bundle_evm();
+
+        let tx = signed_simple_send(&SENDER_WALLET, TX_0_RECIPIENT, U256::ONE, 0, RU_CHAIN_ID);
+        let call_bundle = to_call_bundle(&simple_bundle(vec![tx], vec![], 0)).with_block_overrides(
+            BlockOverrides { number: Some(U256::from(2)), ..Default::default() },
+        );
+
+        let mut driver = SignetBundleDriver::new(&call_bundle);
+        let err = driver.run_bundle(trevm).expect_err("call bundle should fail");
+        assert!(matches!(err.error(), BundleError::EVMError { .. }));
+    }
+
+    /// Test that call bundle rejects `state` overrides, which replace the
+    /// storage of an account.
+    #[test]
+    fn errors_on_state_overrides() {
+        let trevm = call_bundle_evm();
+
+        let tx = signed_simple_send(&SENDER_WALLET, TX_0_RECIPIENT, U256::ONE, 0, RU_CHAIN_ID);
+        let call_bundle = to_call_bundle(&simple_bundle(vec![tx], vec![], 0)).with_state_overrides(
+            [(
+                TX_2_RECIPIENT,
+                AccountOverride {
+                    state: Some(
+                        [(B256::with_last_byte(1), B256::with_last_byte(2))].into_iter().collect(),
+                    ),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        let mut driver = SignetBundleDriver::new(&call_bundle);
+        let err = driver.run_bundle(trevm).expect_err("call bundle should fail");
+        assert!(matches!(err.error(), BundleError::EVMError { .. }));
+    }
+
+    /// Test that call bundle errors on host transactions without a host EVM.
+    #[test]
+    fn errors_on_host_txs_without_host_evm() {
+        let trevm = call_bundle_evm();
+
+        let fill_tx = signed_simple_call(
+            &TEST_SIGNERS[2],
+            TEST_SYS.host_orders(),
+            &fillCall { outputs: full_fills().outputs },
+            U256::ZERO,
+            0,
+            HOST_CHAIN_ID,
+        );
+
+        let bundle = order_bundle();
+        let call_bundle = to_call_bundle(&bundle).append_2718_host_tx(fill_tx);
+
+        let mut driver = SignetBundleDriver::new(&call_bundle);
+        let err = driver.run_bundle(trevm).expect_err("call bundle should fail");
+        assert!(matches!(err.error(), BundleError::EVMError { .. }));
+    }
 }
 
 // =============================================================================
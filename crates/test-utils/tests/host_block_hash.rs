@@ -0,0 +1,137 @@
+use alloy::{
+    consensus::Header,
+    primitives::{Address, Bytes, B256, U256},
+};
+use signet_constants::SignetSystemConstants;
+use signet_evm::{
+    sys::{
+        HostBlockHashSysLog, HostBlockHashes, HOST_BLOCK_HASHES_ADDRESS, HOST_BLOCK_HASHES_CODE,
+        HOST_BLOCK_HASH_WINDOW,
+    },
+    EvmNeedsBlock, SignetDriver,
+};
+use signet_extract::Extracts;
+use signet_test_utils::{chain::Chain, evm::test_signet_evm, specs::HostBlockSpec};
+use signet_types::primitives::SignetHeaderV1;
+use signet_zenith::MINTER_ADDRESS;
+use trevm::revm::{
+    context::{BlockEnv, TransactTo, TxEnv},
+    database::in_memory_db::InMemoryDB,
+};
+
+/// Drive an empty rollup block on top of the given host block, with the
+/// [`HostBlockHashes`] hook registered.
+fn drive(
+    trevm: EvmNeedsBlock<InMemoryDB>,
+    hbs: &HostBlockSpec,
+    ru_height: u64,
+) -> (EvmNeedsBlock<InMemoryDB>, Vec<alloy::consensus::ReceiptEnvelope>) {
+    let constants = SignetSystemConstants::test();
+    let block = hbs.recovered_block();
+    let extracts = Extracts::<Chain>::new(
+        constants.host_chain_id(),
+        &block,
+        constants.ru_chain_id(),
+        ru_height,
+    );
+
+    let header = Header { gas_limit: 30_000_000, ..Default::default() };
+    let parent = SignetHeaderV1::try_from(header).unwrap();
+    let mut driver =
+        SignetDriver::new(&extracts, Default::default(), Default::default(), parent, constants)
+            .with_system_hook(HostBlockHashes);
+
+    let trevm = trevm.drive_block(&mut driver).unwrap();
+    let (_, receipts) = driver.finish();
+    (trevm, receipts)
+}
+
+/// Query the host block hash contract for the hash of a host block.
+fn query(
+    trevm: EvmNeedsBlock<InMemoryDB>,
+    number: u64,
+) -> (Option<B256>, EvmNeedsBlock<InMemoryDB>) {
+    let tx = TxEnv {
+        caller: Address::repeat_byte(0x42),
+        kind: TransactTo::Call(HOST_BLOCK_HASHES_ADDRESS),
+        data: Bytes::from(U256::from(number).to_be_bytes::<32>()),
+        gas_limit: 100_000,
+        ..Default::default()
+    };
+    let (result, trevm) = trevm
+        .fill_block(&BlockEnv::default())
+        .fill_tx(&tx)
+        .call()
+        .map_err(|e| e.into_error())
+        .unwrap();
+    let hash = result.is_success().then(|| B256::from_slice(result.output().unwrap()));
+    (hash, trevm.close_block())
+}
+
+#[test]
+fn stores_host_block_hashes() {
+    let genesis =
+        HostBlockSpec::test().with_block_number(100).with_parent_hash(B256::repeat_byte(0xab));
+    let mut specs = vec![genesis];
+    for _ in 0..3 {
+        specs.push(specs.last().unwrap().child());
+    }
+
+    let mut trevm = test_signet_evm();
+    for (i, hbs) in specs.iter().enumerate() {
+        let (next, receipts) = drive(trevm, hbs, i as u64 + 1);
+        trevm = next;
+
+        // Each rollup block contains a single write, with a sys log for the
+        // host block's parent.
+        let expected: alloy::primitives::Log = HostBlockHashSysLog {
+            hostBlockNumber: hbs.block_number() - 1,
+            hostBlockHash: hbs.parent_hash,
+        }
+        .into();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].logs(), &[expected]);
+    }
+
+    assert_eq!(
+        trevm.read_code(HOST_BLOCK_HASHES_ADDRESS).unwrap().original_bytes(),
+        HOST_BLOCK_HASHES_CODE
+    );
+    assert_eq!(trevm.read_nonce(MINTER_ADDRESS), specs.len() as u64);
+
+    // The parent of the first block, and all but the last block, are stored.
+    let mut expected = vec![(99, B256::repeat_byte(0xab))];
+    expected.extend(specs[..specs.len() - 1].iter().map(|hbs| (hbs.block_number(), hbs.hash())));
+
+    for (number, hash) in expected {
+        assert_eq!(
+            trevm.read_storage(HOST_BLOCK_HASHES_ADDRESS, HostBlockHashes::hash_slot(number)),
+            U256::from_be_bytes(hash.0)
+        );
+        let (stored, next) = query(trevm, number);
+        trevm = next;
+        assert_eq!(stored, Some(hash));
+    }
+
+    // Unknown blocks revert.
+    let (stored, trevm) = query(trevm, 98);
+    assert_eq!(stored, None);
+    let (stored, _) = query(trevm, 98 + HOST_BLOCK_HASH_WINDOW);
+    assert_eq!(stored, None);
+}
+
+#[test]
+fn ring_overwrites_old_hashes() {
+    let first = HostBlockSpec::test().with_block_number(11).with_parent_hash(B256::repeat_byte(1));
+    let second = HostBlockSpec::test()
+        .with_block_number(11 + HOST_BLOCK_HASH_WINDOW)
+        .with_parent_hash(B256::repeat_byte(2));
+
+    let (trevm, _) = drive(test_signet_evm(), &first, 1);
+    let (trevm, _) = drive(trevm, &second, 2);
+
+    let (stored, trevm) = query(trevm, 10);
+    assert_eq!(stored, None);
+    let (stored, _) = query(trevm, 10 + HOST_BLOCK_HASH_WINDOW);
+    assert_eq!(stored, Some(B256::repeat_byte(2)));
+}
@@ -0,0 +1,240 @@
+//! Differential tests checking that [`SignetDriver::parallel`] produces the
+//! same [`BlockResult`] as sequential execution.
+
+use alloy::{
+    consensus::{constants::ETH_TO_WEI, Header, ReceiptEnvelope, TxEnvelope, TypedTransaction},
+    primitives::{Address, B256, U256},
+    signers::{local::PrivateKeySigner, Signature},
+};
+use signet_constants::{
+    test_utils::{HOST_CHAIN_ID, HOST_WBTC, RU_CHAIN_ID, TEST_SYS},
+    SignetSystemConstants,
+};
+use signet_evm::{BlockResult, SignetDriver};
+use signet_extract::{ExtractedEvent, Extracts};
+use signet_test_utils::{
+    chain::{fake_block, Chain, RU_ORDERS},
+    contracts::{
+        counter::{Counter, COUNTER_SLOT, COUNTER_TEST_ADDRESS},
+        reverts::REVERT_TEST_ADDRESS,
+    },
+    evm::{test_signet_evm, TestCfg},
+    specs::{make_wallet, sign_tx_with_key_pair, simple_call, simple_send},
+};
+use signet_types::primitives::{SignetHeaderV1, TransactionSigned};
+use signet_zenith::RollupOrders::{initiateCall, Filled, Input, Output};
+use std::num::NonZeroUsize;
+use trevm::revm::{
+    database::{InMemoryDB, State},
+    inspector::NoOpInspector,
+};
+
+/// Amount of the order input, in wei.
+const ORDER_INPUT: U256 = U256::from_limbs([ETH_TO_WEI as u64, 0, 0, 0]);
+/// Amount of the order output.
+const ORDER_OUTPUT: U256 = U256::from_limbs([100, 0, 0, 0]);
+/// Recipient of the order output.
+const ORDER_RECIPIENT: Address = Address::repeat_byte(0x31);
+
+struct TestEnv {
+    wallets: Vec<PrivateKeySigner>,
+    nonces: [u64; 10],
+}
+
+impl TestEnv {
+    fn new() -> Self {
+        let wallets = (1..=10).map(make_wallet).collect::<Vec<_>>();
+        Self { wallets, nonces: [0; 10] }
+    }
+
+    fn address(&self, idx: usize) -> Address {
+        self.wallets[idx].address()
+    }
+
+    fn sign(&mut self, from: usize, tx: impl FnOnce(u64) -> TypedTransaction) -> TxEnvelope {
+        let tx = sign_tx_with_key_pair(&self.wallets[from], tx(self.nonces[from]));
+        self.nonces[from] += 1;
+        tx
+    }
+
+    fn send(&mut self, from: usize, to: Address, amount: u64) -> TxEnvelope {
+        self.sign(from, |nonce| simple_send(to, U256::from(amount), nonce, RU_CHAIN_ID))
+    }
+
+    fn increment(&mut self, from: usize) -> TxEnvelope {
+        self.sign(from, |nonce| {
+            simple_call(
+                COUNTER_TEST_ADDRESS,
+                &Counter::incrementCall {},
+                U256::ZERO,
+                nonce,
+                RU_CHAIN_ID,
+            )
+        })
+    }
+
+    fn revert(&mut self, from: usize) -> TxEnvelope {
+        self.sign(from, |nonce| {
+            simple_call(
+                REVERT_TEST_ADDRESS,
+                &Counter::incrementCall {},
+                U256::ZERO,
+                nonce,
+                RU_CHAIN_ID,
+            )
+        })
+    }
+
+    fn order(&mut self, from: usize) -> TxEnvelope {
+        let initiate = initiateCall {
+            deadline: U256::MAX,
+            inputs: vec![Input { token: Address::ZERO, amount: ORDER_INPUT }],
+            outputs: vec![Output {
+                token: HOST_WBTC,
+                amount: ORDER_OUTPUT,
+                recipient: ORDER_RECIPIENT,
+                chainId: HOST_CHAIN_ID as u32,
+            }],
+        };
+        self.sign(from, |nonce| simple_call(RU_ORDERS, &initiate, ORDER_INPUT, nonce, RU_CHAIN_ID))
+    }
+
+    /// Create the state at the start of the block.
+    fn state(&self) -> State<InMemoryDB> {
+        let mut trevm = test_signet_evm();
+        for wallet in &self.wallets {
+            trevm.test_set_balance(wallet.address(), U256::from(ETH_TO_WEI * 100));
+        }
+        State::builder().with_database(trevm.into_db()).with_bundle_update().build()
+    }
+
+    /// Drive a block containing the transactions, with fills for a single
+    /// order. Transactions are executed in parallel on `threads` threads, or
+    /// sequentially if `None`.
+    fn run(&self, txs: &[TxEnvelope], threads: Option<usize>) -> BlockResult {
+        let block = fake_block(1);
+        let mut extracts = Extracts::<Chain>::new(HOST_CHAIN_ID, &block, RU_CHAIN_ID, 1);
+
+        let fill_tx = TransactionSigned::new_unhashed(
+            alloy::consensus::TxEip1559::default().into(),
+            Signature::test_signature(),
+        );
+        let fill_receipt = ReceiptEnvelope::Eip1559(Default::default());
+        extracts.ingest_event(ExtractedEvent {
+            tx: &fill_tx,
+            receipt: &fill_receipt,
+            log_index: 0,
+            event: signet_extract::Events::Filled(Filled {
+                outputs: vec![Output {
+                    token: HOST_WBTC,
+                    amount: ORDER_OUTPUT,
+                    recipient: ORDER_RECIPIENT,
+                    chainId: RU_CHAIN_ID as u32,
+                }],
+            }),
+        });
+
+        let header = Header { gas_limit: 30_000_000, ..Default::default() };
+        let parent = SignetHeaderV1::try_from(header).unwrap();
+        let mut driver = SignetDriver::new(
+            &extracts,
+            Default::default(),
+            txs.iter().cloned().map(Into::into).collect::<Vec<TransactionSigned>>().into(),
+            parent,
+            SignetSystemConstants::test(),
+        );
+
+        let trevm = signet_evm::signet_evm_with_inspector(self.state(), NoOpInspector, TEST_SYS)
+            .fill_cfg(&TestCfg);
+        let trevm = match threads {
+            Some(threads) => {
+                trevm.drive_block(&mut driver.parallel(NonZeroUsize::new(threads).unwrap()))
+            }
+            None => trevm.drive_block(&mut driver),
+        }
+        .unwrap();
+
+        driver.finish_trevm(trevm)
+    }
+}
+
+/// Assert that parallel execution of the transactions matches sequential
+/// execution, for several thread counts.
+fn assert_equivalent(env: &TestEnv, txs: &[TxEnvelope]) -> BlockResult {
+    let sequential = env.run(txs, None);
+    for threads in [1, 2, 4, 16] {
+        let parallel = env.run(txs, Some(threads));
+        assert_eq!(parallel.sealed_block, sequential.sealed_block, "threads: {threads}");
+        assert_eq!(parallel.receipts(), sequential.receipts(), "threads: {threads}");
+        assert_eq!(parallel.execution_outcome, sequential.execution_outcome, "threads: {threads}");
+        assert_eq!(
+            parallel.make_host_journal(B256::ZERO).journal_hash(),
+            sequential.make_host_journal(B256::ZERO).journal_hash(),
+            "threads: {threads}"
+        );
+    }
+    sequential
+}
+
+#[test]
+fn independent_transactions() {
+    let mut env = TestEnv::new();
+    let txs: Vec<_> =
+        (0..8).map(|i| env.send(i, Address::repeat_byte(0x10 + i as u8), 100)).collect();
+
+    let result = assert_equivalent(&env, &txs);
+    assert_eq!(result.sealed_block.transactions().len(), 8);
+}
+
+#[test]
+fn conflicting_transactions() {
+    let mut env = TestEnv::new();
+    let beneficiary = Address::ZERO;
+
+    let txs = vec![
+        // Same-sender chain
+        env.send(0, Address::repeat_byte(0x10), 1),
+        env.send(0, Address::repeat_byte(0x11), 2),
+        env.send(0, Address::repeat_byte(0x12), 3),
+        // Storage conflicts
+        env.increment(1),
+        env.increment(2),
+        env.send(3, Address::repeat_byte(0x13), 4),
+        env.increment(1),
+        // Balance dependency: wallet 5 spends funds received from wallet 4
+        env.send(4, env.address(5), 1_000),
+        env.send(5, Address::repeat_byte(0x14), 5),
+        // Transfer to the block beneficiary
+        env.send(6, beneficiary, 6),
+        // Revert
+        env.revert(7),
+        // Invalid nonce, discarded
+        env.sign(8, |_| simple_send(Address::repeat_byte(0x15), U256::from(7), 5, RU_CHAIN_ID)),
+        env.send(9, Address::repeat_byte(0x16), 8),
+    ];
+
+    let result = assert_equivalent(&env, &txs);
+    assert_eq!(result.sealed_block.transactions().len(), txs.len() - 1);
+    assert_eq!(
+        result.execution_outcome.bundle().account(&COUNTER_TEST_ADDRESS).unwrap().storage
+            [&COUNTER_SLOT]
+            .present_value,
+        U256::from(3)
+    );
+}
+
+#[test]
+fn order_fills() {
+    let mut env = TestEnv::new();
+
+    // Only one order is filled. The second is rejected in both modes.
+    let txs = vec![
+        env.order(0),
+        env.send(1, Address::repeat_byte(0x10), 1),
+        env.order(2),
+        env.send(3, Address::repeat_byte(0x11), 2),
+    ];
+
+    let result = assert_equivalent(&env, &txs);
+    assert_eq!(result.sealed_block.transactions().len(), 3);
+}
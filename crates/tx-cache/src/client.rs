@@ -289,6 +289,11 @@ impl TxCache {
     /// * `bundle_id` - The UUID of the bundle to update.
     /// * `bundle` - The updated [`SignetEthBundle`] to store.
     ///
+    /// Builders keying bundles by id (see [`CachedBundle::into_keyed_bundle`])
+    /// replace their copy of the bundle with the update. An update with no
+    /// transactions cancels the bundle. Set a [replacement nonce] to prevent
+    /// an earlier update from replacing a later one.
+    ///
     /// # Returns
     ///
     /// A [`BundleResponse`] containing the bundle's UUID on success.
@@ -317,6 +322,8 @@ impl TxCache {
     /// ```
     ///
     /// [`TxCacheError::NotFound`]: crate::error::TxCacheError::NotFound
+    /// [`CachedBundle::into_keyed_bundle`]: crate::types::CachedBundle::into_keyed_bundle
+    /// [replacement nonce]: SignetEthBundle::replacement_nonce
     #[instrument(skip_all)]
     pub async fn update_bundle(
         &self,
@@ -139,6 +139,20 @@ impl CachedBundle {
         self.bundle
     }
 
+    /// Convert the cached bundle to a [`SignetEthBundle`] whose replacement
+    /// UUID is the bundle id.
+    ///
+    /// Builders should key cached bundles by id, so that a bundle updated via
+    /// [`TxCache::update_bundle`] replaces the previous version, rather than
+    /// being simulated alongside it.
+    ///
+    /// [`TxCache::update_bundle`]: crate::TxCache::update_bundle
+    pub fn into_keyed_bundle(self) -> SignetEthBundle {
+        let mut bundle = self.bundle;
+        bundle.bundle.replacement_uuid = Some(self.id.to_string());
+        bundle
+    }
+
     /// Convert the cached bundle to a [uuid::Uuid].
     pub fn into_id(self) -> uuid::Uuid {
         self.id
@@ -598,6 +612,16 @@ mod tests {
         assert_eq!(deserialized, cache_response);
     }
 
+    #[test]
+    fn test_keyed_bundle() {
+        let uuid = Uuid::from_str("5932d4bb-58d9-41a9-851d-8dd7f04ccc33").unwrap();
+        let mut cached = dummy_bundle_with_id(uuid);
+        cached.bundle.bundle.replacement_uuid = None;
+
+        let bundle = cached.into_keyed_bundle();
+        assert_eq!(bundle.replacement_uuid(), Some("5932d4bb-58d9-41a9-851d-8dd7f04ccc33"));
+    }
+
     #[test]
     fn test_pagination_params_simple_deser() {
         let tx_key = TxKey {
@@ -19,6 +19,8 @@ pub(crate) enum Flags {
     EnterToken = 0x02,
     /// Flag used to identify Transacts
     Transact = 0x03,
+    /// Flag used to identify system outputs
+    System = 0x04,
 }
 
 impl From<Flags> for u8 {
@@ -35,6 +37,7 @@ impl TryFrom<u8> for Flags {
             0x01 => Ok(Self::Enter),
             0x02 => Ok(Self::EnterToken),
             0x03 => Ok(Self::Transact),
+            0x04 => Ok(Self::System),
             _ => Err(()),
         }
     }
@@ -62,6 +65,9 @@ pub enum MagicSigInfo {
         /// non-ephemeral contracts.
         aliased: bool,
     },
+    /// A system output, produced by the rollup consensus rather than a host
+    /// chain event. E.g. the output of a system hook.
+    System,
 }
 
 impl MagicSigInfo {
@@ -71,6 +77,7 @@ impl MagicSigInfo {
             Self::Enter => Flags::Enter as u8,
             Self::EnterToken => Flags::EnterToken as u8,
             Self::Transact { .. } => Flags::Transact as u8,
+            Self::System => Flags::System as u8,
         }
     }
 
@@ -98,12 +105,13 @@ impl MagicSigInfo {
             Flags::Transact => {
                 Some(Self::Transact { aliased: s[11] != 0, sender: Address::from_slice(&s[12..]) })
             }
+            Flags::System => Some(Self::System),
         }
     }
 
     /// Get the sender from the magic signature info. For enter and enter token
-    /// events, this is the [`MINTER_ADDRESS`]. For transact events, this is the
-    /// sender.
+    /// events, and system outputs, this is the [`MINTER_ADDRESS`]. For
+    /// transact events, this is the sender.
     pub const fn raw_sender(&self) -> Address {
         match self {
             Self::Transact { sender, .. } => *sender,
@@ -112,8 +120,8 @@ impl MagicSigInfo {
     }
 
     /// Get the rollup sender from the magic signature info. For enter and
-    /// enter token events, this is the [`MINTER_ADDRESS`]. For transact events,
-    /// this is the aliased or non-aliased sender.
+    /// enter token events, and system outputs, this is the [`MINTER_ADDRESS`].
+    /// For transact events, this is the aliased or non-aliased sender.
     pub fn rollup_sender(&self) -> Address {
         match self {
             Self::Transact { sender, aliased } => {
@@ -144,13 +152,15 @@ impl MagicSigInfo {
 ///
 /// The magic signature format is as follows:
 /// - odd_y_parity: RESERVED (false)
-/// - r: 32-byte txid of the transaction that emitted the event
+/// - r: 32-byte txid of the transaction that emitted the event. For system
+///   outputs, this is chosen by the system.
 /// - s:
 ///   - `[0..4]`: A 4-byte sentinel value (0xffeeddcc).
 ///   - `[4..8]`: A 4-bytes BE u32 containing the index of the event in the
-///     transaction's log array.
+///     transaction's log array. For system outputs, the index of the output
+///     in the block.
 ///   - `[8..9]`: A 1-byte flag (0x01 for enter, 0x02 for enter token, 0x03
-///     for transact).
+///     for transact, 0x04 for system outputs).
 ///   - `[9..12]`: A 3-byte RESERVED region.
 ///   - `[12..32]`: For transact events, (flag byte 3) the sender's address.
 ///     RESERVED otherwise.
@@ -205,6 +215,11 @@ impl MagicSig {
         Self { ty: MagicSigInfo::Transact { sender, aliased }, txid, event_idx }
     }
 
+    /// Create a new [`MagicSig`] for a system output.
+    pub const fn system(txid: B256, event_idx: usize) -> Self {
+        Self { ty: MagicSigInfo::System, txid, event_idx }
+    }
+
     /// Get the sender of the magic signature.
     pub fn rollup_sender(&self) -> Address {
         self.ty.rollup_sender()
@@ -267,6 +282,13 @@ mod test {
         test_roundtrip(msig);
     }
 
+    #[test]
+    fn test_system_roundtrip() {
+        let msig = MagicSig::system(B256::repeat_byte(0xcc), 7);
+        test_roundtrip(msig);
+        assert_eq!(msig.rollup_sender(), MINTER_ADDRESS);
+    }
+
     #[test]
     fn test_tx_decode_sig() {
         let tx: alloy::consensus::TxEnvelope = serde_json::from_str(ENTER_TOKEN_TX).unwrap();